@@ -52,7 +52,7 @@ done
 echo "📡 Calling API: $API_URL"
 echo "   Parameters: $PARAMS"
 
-RESPONSE=$(curl -s -X POST "$API_URL?$PARAMS")
+RESPONSE=$(curl -s -X POST -H "Accept: text/plain" "$API_URL?$PARAMS")
 
 echo ""
 echo "📋 API Response:"
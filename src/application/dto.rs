@@ -1,11 +1,16 @@
-use crate::domain::common::{ByteRange};
-use crate::domain::video::{VideoMetadata};
+use std::time::SystemTime;
+use serde::{Deserialize, Serialize};
+use crate::domain::common::{ByteRange, FieldError};
+use crate::domain::conditional::ConditionalRequest;
+use crate::domain::media::MediaStream;
+use crate::domain::video::{VideoMetadata, VideoByteStream};
 
 /// DTO for video streaming request
 #[derive(Debug)]
 pub struct StreamVideoRequest {
     pub video_id: String,
     pub range_header: Option<String>,
+    pub conditional: ConditionalRequest,
 }
 
 /// DTO for video streaming response
@@ -13,22 +18,52 @@ pub struct StreamVideoRequest {
 pub struct StreamVideoResponse {
     pub video_id: String,
     pub content_type: String,
+    pub etag: String,
+    pub last_modified: Option<SystemTime>,
+    pub not_modified: bool,
+    pub partial: bool, // false when the whole file is sent
+    pub total_size: u64,
+    pub parts: Vec<StreamVideoPart>,
+}
+
+/// DTO for a single byte range of a video streaming response
+#[derive(Debug)]
+pub struct StreamVideoPart {
     pub content_range: String,
-    pub data: Vec<u8>,
+    pub length: u64,
+    pub body: VideoByteStream, // read lazily, so HEAD requests never touch the file
 }
 
 /// DTO for video metadata response
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct VideoMetadataResponse {
     pub video_id: String,
     pub total_size: u64,
     pub content_type: String,
     pub duration: Option<f64>,
     pub bitrate: Option<u32>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub video_codec: Option<String>,
+    pub audio_codec: Option<String>,
+    pub frame_rate: Option<f64>,
+    pub streams: Vec<MediaStreamResponse>,
+}
+
+/// DTO for one stream of a probed video
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MediaStreamResponse {
+    pub index: u32,
+    pub kind: String,
+    pub codec: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub frame_rate: Option<f64>,
+    pub bitrate: Option<u64>,
 }
 
 /// DTO for session creation request
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CreateSessionRequest {
     pub video_id: String,
     pub user_agent: String,
@@ -36,7 +71,7 @@ pub struct CreateSessionRequest {
 }
 
 /// DTO for session response
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SessionResponse {
     pub session_id: String,
     pub video_id: String,
@@ -45,7 +80,7 @@ pub struct SessionResponse {
 }
 
 /// DTO for session metrics
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct SessionMetricsResponse {
     pub bytes_requested: u64,
     pub chunks_requested: u64,
@@ -54,42 +89,59 @@ pub struct SessionMetricsResponse {
 }
 
 /// DTO for video creation request
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
 pub struct CreateVideoRequest {
-    pub image_paths: Vec<String>,
-    pub output_path: String,
     pub video_id: String,
+    pub images: Vec<CreateVideoImage>, // in playback order
+    pub output: CreateVideoOutput,
+}
+
+/// DTO for one image of a video creation request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateVideoImage {
+    pub path: String,
+    pub duration: Option<u32>, // seconds, overrides output.duration_per_image
+}
+
+/// DTO for the output settings of a video creation request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CreateVideoOutput {
+    pub path: String,
     pub width: Option<u32>,
     pub height: Option<u32>,
     pub duration_per_image: Option<u32>,
+    pub profile: Option<String>, // encoder profile, the configured default when unset
 }
 
 /// DTO for video creation response
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct CreateVideoResponse {
     pub job_id: String,
     pub video_id: String,
     pub status: String,
     pub total_frames: usize,
     pub estimated_duration: u32,
-    pub created_at: String,
+    pub created_at: u64, // unix seconds
 }
 
 /// DTO for video creation job status
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct VideoCreationJobStatusResponse {
     pub job_id: String,
     pub video_id: String,
     pub status: String,
     pub progress: Option<VideoCreationProgressResponse>,
-    pub created_at: String,
-    pub completed_at: Option<String>,
+    pub created_at: u64, // unix seconds
+    pub completed_at: Option<u64>,
     pub error_message: Option<String>,
     pub duration_seconds: Option<u64>,
 }
 
 /// DTO for video creation progress
-#[derive(Debug)]
+#[derive(Debug, Serialize, Deserialize)]
 pub struct VideoCreationProgressResponse {
     pub current_frame: usize,
     pub total_frames: usize,
@@ -97,14 +149,161 @@ pub struct VideoCreationProgressResponse {
     pub estimated_time_remaining: Option<f32>,
 }
 
+/// DTO for the query parameters of the job listing; values are checked by the service
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListJobsRequest {
+    pub status: Option<String>,
+    pub video_id: Option<String>,
+    pub since: Option<String>, // unix timestamp in seconds
+    pub limit: Option<String>,
+    pub cursor: Option<String>,
+}
+
+/// DTO for one job of the job listing
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VideoCreationJobSummaryResponse {
+    pub job_id: String,
+    pub video_id: String,
+    pub status: String,
+    pub percentage: Option<f32>,
+    pub created_at: u64, // unix seconds
+    pub completed_at: Option<u64>,
+    pub error_message: Option<String>,
+}
+
+/// DTO for a page of the job listing
+#[derive(Debug, Serialize, Deserialize)]
+pub struct VideoCreationJobListResponse {
+    pub jobs: Vec<VideoCreationJobSummaryResponse>,
+    /// Pass as `cursor` (with the same filters) for the next page; absent on the last page
+    pub next_cursor: Option<String>,
+}
+
+/// DTO for image validation response
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ValidateImagesResponse {
+    pub valid: bool,
+    pub image_count: usize,
+    pub message: String,
+}
+
+/// DTO for the health check
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HealthResponse {
+    pub status: String,
+    pub ffmpeg_available: bool,
+    pub config_valid: bool,
+    pub default_image_spec: ImageSpecResponse,
+    pub block_cache: BlockCacheStatsResponse,
+    pub ffmpeg: Option<FFmpegCapabilitiesResponse>, // when probed at startup
+    pub timestamp: u64, // unix seconds
+}
+
+/// DTO for what the FFmpeg installation supports
+#[derive(Debug, Serialize, Deserialize)]
+pub struct FFmpegCapabilitiesResponse {
+    pub path: String,
+    pub version: Option<String>,
+    pub encoder_count: usize,
+    pub filter_count: usize,
+    pub muxer_count: usize,
+    pub encoder_profiles: Vec<EncoderProfileSupportResponse>,
+}
+
+/// DTO for whether the FFmpeg installation can encode with a built-in profile
+#[derive(Debug, Serialize, Deserialize)]
+pub struct EncoderProfileSupportResponse {
+    pub name: String,
+    pub supported: bool,
+    pub missing: Vec<String>,
+}
+
+/// DTO for an image specification
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ImageSpecResponse {
+    pub width: u32,
+    pub height: u32,
+    pub duration_seconds: u32,
+}
+
+/// DTO for block cache counters
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BlockCacheStatsResponse {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub blocks: usize,
+    pub bytes: u64,
+    pub capacity: u64,
+}
+
+/// DTO for an error reported by an endpoint: RFC 9457 problem details
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    pub code: String, // stable, see DomainError::code
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub entity: Option<String>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldErrorResponse>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub summary: Option<String>, // last line of the tool's output, without file paths
+
+}
+
+/// DTO for a problem with one field of a request
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FieldErrorResponse {
+    pub field: String, // e.g. "images[2].path"
+    pub message: String,
+}
+
 impl From<VideoMetadata> for VideoMetadataResponse {
     fn from(metadata: VideoMetadata) -> Self {
+        let media = metadata.media.unwrap_or_default();
         VideoMetadataResponse {
             video_id: "".to_string(), // Will be set by the caller
             total_size: metadata.total_size,
             content_type: metadata.content_type.as_str().to_string(),
             duration: metadata.duration,
             bitrate: metadata.bitrate,
+            width: media.width(),
+            height: media.height(),
+            video_codec: media.video_codec().map(str::to_string),
+            audio_codec: media.audio_codec().map(str::to_string),
+            frame_rate: media.frame_rate(),
+            streams: media.streams.into_iter().map(MediaStreamResponse::from).collect(),
+        }
+    }
+}
+
+impl From<FieldError> for FieldErrorResponse {
+    fn from(error: FieldError) -> Self {
+        FieldErrorResponse {
+            field: error.field,
+            message: error.message,
+        }
+    }
+}
+
+impl From<MediaStream> for MediaStreamResponse {
+    fn from(stream: MediaStream) -> Self {
+        MediaStreamResponse {
+            index: stream.index,
+            kind: stream.kind.as_str().to_string(),
+            codec: stream.codec,
+            width: stream.width,
+            height: stream.height,
+            frame_rate: stream.frame_rate,
+            bitrate: stream.bitrate,
         }
     }
 }
@@ -1,12 +1,19 @@
-use crate::domain::common::DomainResult;
-use crate::domain::video::VideoId;
-use crate::domain::video_creation::{VideoCreationManager, ImageSpec, VideoCreationJobId, VideoCreator, VideoCreationRepository};
+use std::sync::Arc;
+use crate::domain::common::{DomainResult, DomainError, FieldError, FilePath, SharedIdGenerator, UuidV7Generator};
+use crate::domain::video::{VideoId, VideoByteStream};
+use crate::domain::video_creation::{
+    VideoCreationManager, EncoderProfile, ImageSpec, VideoCreationJob, VideoCreationJobCursor, VideoCreationJobId, VideoCreationJobQuery,
+    VideoCreationRepository, VideoCreationStatus, ENCODER_PROFILE_NAMES, unix_seconds,
+};
 use crate::application::dto::{
-    StreamVideoRequest, StreamVideoResponse, CreateSessionRequest, SessionResponse,
-    CreateVideoRequest, CreateVideoResponse, VideoCreationJobStatusResponse, VideoCreationProgressResponse
+    StreamVideoRequest, StreamVideoResponse, StreamVideoPart, CreateSessionRequest, SessionResponse,
+    CreateVideoRequest, CreateVideoResponse, VideoCreationJobStatusResponse, VideoCreationProgressResponse,
+    ListJobsRequest, VideoCreationJobListResponse, VideoCreationJobSummaryResponse, CreateVideoOutput
 };
-use crate::infrastructure::ffmpeg::FFmpegVideoCreator;
-use crate::infrastructure::repositories::InMemoryVideoCreationRepository;
+use crate::infrastructure::events::JobEventLog;
+use crate::infrastructure::ffmpeg::SharedFFmpegCapabilities;
+use crate::infrastructure::jobs::VideoCreationWorkerPool;
+use crate::infrastructure::repositories::SharedVideoCreationRepository;
 
 /// Simplified application service for basic operations
 pub struct VideoStreamingAppService;
@@ -22,12 +29,26 @@ impl VideoStreamingAppService {
         Ok(StreamVideoResponse {
             video_id: request.video_id,
             content_type: "video/webm".to_string(),
-            content_range: "bytes 0-1023/2048".to_string(),
-            data: vec![0u8; 1024],
+            etag: "\"800\"".to_string(),
+            last_modified: None,
+            not_modified: false,
+            partial: true,
+            total_size: 2048,
+            parts: vec![StreamVideoPart {
+                content_range: "bytes 0-1023/2048".to_string(),
+                length: 1024,
+                body: VideoByteStream::from_bytes(vec![0u8; 1024].into()),
+            }],
         })
     }
 }
 
+impl Default for VideoStreamingAppService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Simplified session management service
 pub struct SessionManagementAppService;
 
@@ -95,113 +116,306 @@ impl SessionManagementAppService {
     }
 }
 
-/// Video creation application service
+impl Default for SessionManagementAppService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Jobs per page of the job listing, unless the client asks for another `limit`
+const DEFAULT_JOB_PAGE_SIZE: usize = 50;
+const MAX_JOB_PAGE_SIZE: usize = 200;
+
+/// Video creation application service, shared by every HTTP worker so that
+/// jobs created by one request are visible to the next
+#[derive(Clone)]
 pub struct VideoCreationAppService {
     config: crate::shared::config::Config,
-    repository: InMemoryVideoCreationRepository,
-    ffmpeg_creator: FFmpegVideoCreator<InMemoryVideoCreationRepository>,
+    repository: SharedVideoCreationRepository,
+    workers: VideoCreationWorkerPool,
+    ids: SharedIdGenerator,
+    capabilities: Option<SharedFFmpegCapabilities>,
 }
 
 impl VideoCreationAppService {
-    pub fn new(config: crate::shared::config::Config) -> Self {
-        let repository = InMemoryVideoCreationRepository::new();
-        let ffmpeg_creator = FFmpegVideoCreator::new(repository.clone());
-        
+    /// Jobs are stored in, and run from, the worker pool's repository
+    pub fn new(config: crate::shared::config::Config, workers: VideoCreationWorkerPool) -> Self {
         Self { 
             config,
-            repository,
-            ffmpeg_creator,
+            repository: workers.repository().clone(),
+            workers,
+            ids: Arc::new(UuidV7Generator),
+            capabilities: None,
+        }
+    }
+
+    /// Name new jobs with ids from `ids` instead of UUIDv7s
+    pub fn with_id_generator(mut self, ids: SharedIdGenerator) -> Self {
+        self.ids = ids;
+        self
+    }
+
+    /// Reject requests the probed FFmpeg cannot encode before they are queued
+    pub fn with_ffmpeg_capabilities(mut self, capabilities: SharedFFmpegCapabilities) -> Self {
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    /// Check a creation request field by field, so clients learn which input to fix
+    pub fn validate_request(&self, request: &CreateVideoRequest) -> DomainResult<()> {
+        let mut errors = Vec::new();
+
+        if request.video_id.trim().is_empty() {
+            errors.push(FieldError::new("video_id", "must not be empty"));
+        }
+
+        if request.images.is_empty() {
+            errors.push(FieldError::new("images", "must contain at least one image"));
+        }
+        for (index, image) in request.images.iter().enumerate() {
+            let field = format!("images[{}].path", index);
+            if image.path.trim().is_empty() {
+                errors.push(FieldError::new(field, "must not be empty"));
+            } else if let Some(error) = Self::image_error(field, &image.path) {
+                errors.push(error);
+            }
+            if image.duration == Some(0) {
+                errors.push(FieldError::new(format!("images[{}].duration", index), "must be positive"));
+            }
+        }
+
+        if request.output.path.trim().is_empty() {
+            errors.push(FieldError::new("output.path", "must not be empty"));
+        }
+        for (field, value) in [
+            ("output.width", request.output.width),
+            ("output.height", request.output.height),
+            ("output.duration_per_image", request.output.duration_per_image),
+        ] {
+            if value == Some(0) {
+                errors.push(FieldError::new(field, "must be positive"));
+            }
+        }
+
+        match self.encoder_profile(&request.output) {
+            Ok(profile) => {
+                if !request.output.path.trim().is_empty() && !profile.container.accepts_path(&request.output.path) {
+                    errors.push(FieldError::new("output.path", format!(
+                        "must end in .{} for the {} profile",
+                        profile.container.extensions().join(" or ."),
+                        profile.name,
+                    )));
+                }
+                if let Some(capabilities) = &self.capabilities {
+                    errors.extend(capabilities.unsupported(&profile));
+                }
+            }
+            Err(_) if request.output.profile.is_some() => errors.push(FieldError::new(
+                "output.profile",
+                format!("unknown encoder profile (expected one of {})", ENCODER_PROFILE_NAMES.join(", ")),
+            )),
+            // A broken default is a server problem, reported by create_video
+            Err(_) => {}
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(DomainError::Validation(errors))
         }
     }
 
+    /// Field error for an image that is missing or not in a supported format
+    fn image_error(field: String, image_path: &str) -> Option<FieldError> {
+        match VideoCreationManager::validate_images(&[FilePath::new(image_path.to_string())]) {
+            Ok(()) => None,
+            Err(DomainError::NotFound { .. }) => Some(FieldError::new(field, "image file does not exist")),
+            Err(DomainError::Unsupported(_)) => Some(FieldError::new(field, "unsupported image format")),
+            Err(other) => Some(FieldError::new(field, other.to_string())),
+        }
+    }
+
+    /// Encoder profile the request names, else the configured default
+    fn encoder_profile(&self, output: &CreateVideoOutput) -> DomainResult<EncoderProfile> {
+        match &output.profile {
+            Some(name) => EncoderProfile::named(name),
+            None => self.config.default_encoder_profile(),
+        }
+    }
+
+    /// Queue a creation job on the worker pool and return without waiting for FFmpeg
     pub fn create_video(&self, request: CreateVideoRequest) -> DomainResult<CreateVideoResponse> {
+        if let Some(capabilities) = self.capabilities.as_ref().filter(|capabilities| !capabilities.available) {
+            return Err(DomainError::external_tool("ffmpeg", format!("{} could not be run at startup", capabilities.ffmpeg_path)));
+        }
+
         // Parse video ID
         let video_id = VideoId::new(request.video_id.clone());
 
-        // Create image specification from request or use config defaults
-        let image_spec = if let (Some(width), Some(height), Some(duration)) = 
-            (request.width, request.height, request.duration_per_image) {
-            Some(ImageSpec::new(width, height, duration)?)
-        } else {
-            Some(self.config.default_image_spec())
-        };
+        // Fill unset output settings from the config defaults
+        let defaults = self.config.default_image_spec();
+        let image_spec = ImageSpec::new(
+            request.output.width.unwrap_or(defaults.width),
+            request.output.height.unwrap_or(defaults.height),
+            request.output.duration_per_image.unwrap_or(defaults.duration_seconds),
+        )?;
+        let encoder_profile = self.encoder_profile(&request.output)?;
 
         // Create domain job using the domain service
-        let job = VideoCreationManager::create_job(
-            request.image_paths.clone(),
-            request.output_path.clone(),
+        let (image_paths, image_durations) = request.images.iter()
+            .map(|image| (image.path.clone(), image.duration))
+            .unzip();
+        let job = VideoCreationManager::create_job_with_ids(
+            self.ids.as_ref(),
+            image_paths,
+            image_durations,
+            request.output.path.clone(),
             video_id,
-            image_spec,
+            Some(image_spec),
+            Some(encoder_profile),
         )?;
 
-        // Save the job to repository
-        self.repository.save_job(&job)?;
-
-        // Start the job
-        let mut job = job;
-        job.start()?;
-        self.repository.update_job(&job)?;
-
-        // Actually create the video using FFmpeg
-        match self.ffmpeg_creator.create_video(&job.request) {
-            Ok(completed_job) => {
-                // Update the job with final status
-                self.repository.update_job(&completed_job)?;
-                
-                Ok(CreateVideoResponse {
-                    job_id: completed_job.id.as_str().to_string(),
-                    video_id: request.video_id,
-                    status: format!("{:?}", completed_job.status),
-                    total_frames: completed_job.request.frame_count(),
-                    estimated_duration: completed_job.request.total_duration(),
-                    created_at: format!("{:?}", completed_job.created_at),
-                })
-            }
-            Err(e) => {
-                // Mark job as failed
-                job.fail(e.to_string())?;
-                self.repository.update_job(&job)?;
-                
-                Err(e)
-            }
-        }
+        let response = CreateVideoResponse {
+            job_id: job.id.as_str().to_string(),
+            video_id: request.video_id,
+            status: job.status.as_str().to_string(),
+            total_frames: job.request.frame_count(),
+            estimated_duration: job.request.total_duration(),
+            created_at: unix_seconds(job.created_at),
+        };
+
+        // The workers pick the job up from here
+        self.workers.submit(job)?;
+
+        Ok(response)
     }
 
     pub fn get_job_status(&self, job_id: &str) -> DomainResult<VideoCreationJobStatusResponse> {
         let job_id = VideoCreationJobId::new(job_id.to_string());
         
         match self.repository.find_job_by_id(&job_id)? {
-            Some(job) => {
-                let progress = job.progress.as_ref().map(|p| VideoCreationProgressResponse {
-                    current_frame: p.current_frame,
-                    total_frames: p.total_frames,
-                    percentage: p.percentage,
-                    estimated_time_remaining: p.estimated_time_remaining_seconds,
-                });
-
-                Ok(VideoCreationJobStatusResponse {
-                    job_id: job.id.as_str().to_string(),
-                    video_id: job.request.video_id.as_str().to_string(),
-                    status: format!("{:?}", job.status),
-                    progress,
-                    created_at: format!("{:?}", job.created_at),
-                    completed_at: job.completed_at.map(|t| format!("{:?}", t)),
-                    error_message: job.error_message.clone(),
-                    duration_seconds: job.duration().map(|d| d.as_secs() as u64),
-                })
+            Some(job) => Ok(Self::job_status_response(&job)),
+            None => Err(DomainError::not_found("job", job_id.as_str()))
+        }
+    }
+
+    /// List jobs oldest first, filtered by status, video and creation time, one page at a time
+    pub fn list_jobs(&self, request: &ListJobsRequest) -> DomainResult<VideoCreationJobListResponse> {
+        let mut errors = Vec::new();
+        let mut query = VideoCreationJobQuery::new(DEFAULT_JOB_PAGE_SIZE);
+
+        if let Some(status) = &request.status {
+            match VideoCreationStatus::parse(status) {
+                Ok(status) => query.status = Some(status),
+                Err(_) => errors.push(FieldError::new("status", format!("unknown job status: {}", status))),
+            }
+        }
+        if let Some(video_id) = &request.video_id {
+            query.video_id = Some(VideoId::new(video_id.clone()));
+        }
+        if let Some(since) = &request.since {
+            match since.trim().parse::<u64>() {
+                Ok(seconds) => query.since = Some(std::time::UNIX_EPOCH + std::time::Duration::from_secs(seconds)),
+                Err(_) => errors.push(FieldError::new("since", "must be a unix timestamp in seconds")),
+            }
+        }
+        if let Some(limit) = &request.limit {
+            match limit.trim().parse::<usize>() {
+                Ok(limit) if (1..=MAX_JOB_PAGE_SIZE).contains(&limit) => query.limit = limit,
+                _ => errors.push(FieldError::new("limit", format!("must be between 1 and {}", MAX_JOB_PAGE_SIZE))),
+            }
+        }
+        if let Some(cursor) = &request.cursor {
+            match VideoCreationJobCursor::parse(cursor) {
+                Ok(cursor) => query.after = Some(cursor),
+                Err(DomainError::Validation(mut cursor_errors)) => errors.append(&mut cursor_errors),
+                Err(e) => return Err(e),
             }
-            None => Err(crate::domain::common::DomainError::FileNotFound)
         }
+        if !errors.is_empty() {
+            return Err(DomainError::Validation(errors));
+        }
+
+        // One extra job tells whether another page follows
+        let page_size = query.limit;
+        query.limit += 1;
+        let mut jobs = self.repository.query_jobs(&query)?;
+        let next_cursor = if jobs.len() > page_size {
+            jobs.truncate(page_size);
+            jobs.last().map(|job| VideoCreationJobCursor::for_job(job).encode())
+        } else {
+            None
+        };
+
+        Ok(VideoCreationJobListResponse {
+            jobs: jobs.iter().map(Self::job_summary_response).collect(),
+            next_cursor,
+        })
+    }
+
+    /// Event log to follow a job's lifecycle in. Jobs this process has not changed yet
+    /// (queued, or finished by a previous run) get an event for their stored state.
+    pub fn job_events(&self, job_id: &str) -> DomainResult<JobEventLog> {
+        let job_id = VideoCreationJobId::new(job_id.to_string());
+        let job = self.repository
+            .find_job_by_id(&job_id)?
+            .ok_or_else(|| DomainError::not_found("job", job_id.as_str()))?;
+
+        let events = self.workers.events();
+        events.record_if_unknown(&job);
+        Ok(events.clone())
+    }
+
+    /// Cancel a queued or running job; finished jobs are a conflict
+    pub fn cancel_job(&self, job_id: &str) -> DomainResult<VideoCreationJobStatusResponse> {
+        let job = self.workers.cancel(&VideoCreationJobId::new(job_id.to_string()))?;
+        Ok(Self::job_status_response(&job))
     }
 
+    pub fn job_status_response(job: &VideoCreationJob) -> VideoCreationJobStatusResponse {
+        let progress = job.progress.as_ref().map(|p| VideoCreationProgressResponse {
+            current_frame: p.current_frame,
+            total_frames: p.total_frames,
+            percentage: p.percentage,
+            estimated_time_remaining: p.estimated_time_remaining_seconds,
+        });
+
+        VideoCreationJobStatusResponse {
+            job_id: job.id.as_str().to_string(),
+            video_id: job.request.video_id.as_str().to_string(),
+            status: job.status.as_str().to_string(),
+            progress,
+            created_at: unix_seconds(job.created_at),
+            completed_at: job.completed_at.map(unix_seconds),
+            error_message: job.error_message.clone(),
+            duration_seconds: job.duration().map(|d| d.as_secs()),
+        }
+    }
+
+    fn job_summary_response(job: &VideoCreationJob) -> VideoCreationJobSummaryResponse {
+        VideoCreationJobSummaryResponse {
+            job_id: job.id.as_str().to_string(),
+            video_id: job.request.video_id.as_str().to_string(),
+            status: job.status.as_str().to_string(),
+            percentage: job.progress.as_ref().map(|p| p.percentage),
+            created_at: unix_seconds(job.created_at),
+            completed_at: job.completed_at.map(unix_seconds),
+            error_message: job.error_message.clone(),
+        }
+    }
+
+    /// Validate images named by the image1, image2, ... query parameters
     pub fn validate_images(&self, image_paths: &[String]) -> DomainResult<bool> {
-        // Convert to FilePath and validate using domain service
-        let file_paths: Vec<crate::domain::common::FilePath> = image_paths
+        let errors: Vec<FieldError> = image_paths
             .iter()
-            .map(|p| crate::domain::common::FilePath::new(p.clone()))
+            .enumerate()
+            .filter_map(|(index, path)| Self::image_error(format!("image{}", index + 1), path))
             .collect();
 
-        VideoCreationManager::validate_images(&file_paths)?;
-        Ok(true)
+        if errors.is_empty() {
+            Ok(true)
+        } else {
+            Err(DomainError::Validation(errors))
+        }
     }
-} 
\ No newline at end of file
+}
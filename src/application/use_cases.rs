@@ -1,7 +1,10 @@
-use crate::domain::common::{DomainResult};
-use crate::domain::video::{ VideoId, VideoRepository, VideoStreamingService, RangeParser};
+use std::sync::Arc;
+use crate::domain::common::{DomainResult, SharedIdGenerator, UuidV7Generator};
+use crate::domain::conditional::ConditionalOutcome;
+use crate::domain::media::MediaProbe;
+use crate::domain::video::{ VideoId, VideoRepository, VideoStreamingService, RangeParser, RangeSelection};
 use crate::domain::streaming::{SessionId, SessionRepository, SessionManager, ClientInfo};
-use crate::application::dto::{StreamVideoRequest, StreamVideoResponse, CreateSessionRequest, SessionResponse};
+use crate::application::dto::{StreamVideoRequest, StreamVideoResponse, StreamVideoPart, VideoMetadataResponse, CreateSessionRequest, SessionResponse};
 
 /// Use Case: Stream Video
 pub struct StreamVideoUseCase<R, S> 
@@ -11,6 +14,7 @@ where
 {
     video_repository: R,
     streaming_service: S,
+    max_chunk_size: Option<u64>,
 }
 
 impl<R, S> StreamVideoUseCase<R, S>
@@ -22,41 +26,125 @@ where
         Self {
             video_repository,
             streaming_service,
+            max_chunk_size: None,
         }
     }
 
+    /// Cap every requested range to `max_chunk_size` bytes so clients fetch large files in windows
+    pub fn with_max_chunk_size(mut self, max_chunk_size: Option<u64>) -> Self {
+        self.max_chunk_size = max_chunk_size;
+        self
+    }
+
     pub fn execute(&self, request: StreamVideoRequest) -> DomainResult<StreamVideoResponse> {
         let video_id = VideoId::new(request.video_id);
         
         // Find video
         let video = self.video_repository.find_by_id(&video_id)?
-            .ok_or_else(|| crate::domain::common::DomainError::FileNotFound)?;
+            .ok_or_else(|| crate::domain::common::DomainError::not_found("video", video_id.as_str()))?;
         
-        // Parse range header
-        let range = RangeParser::parse_range_header(
-            request.range_header.as_deref(), 
-            video.metadata.total_size
-        )?;
+        // Evaluate preconditions against the current file version
+        let validators = video.metadata.validators();
+        let range_header = match request.conditional.evaluate(&validators) {
+            ConditionalOutcome::NotModified => {
+                return Ok(StreamVideoResponse {
+                    video_id: video_id.as_str().to_string(),
+                    content_type: video.metadata.content_type.as_str().to_string(),
+                    etag: validators.etag.to_string(),
+                    last_modified: validators.last_modified,
+                    not_modified: true,
+                    partial: false,
+                    total_size: video.metadata.total_size,
+                    parts: Vec::new(),
+                });
+            }
+            ConditionalOutcome::Proceed { honor_range: true } => request.range_header.as_deref(),
+            // If-Range did not match: the client's partial copy is stale, send everything
+            ConditionalOutcome::Proceed { honor_range: false } => None,
+        };
         
-        // Read video chunk
-        let chunk = self.streaming_service.read_chunk(&video, &range)?;
+        // Parse range header (may contain several ranges)
+        let selection = RangeParser::select(range_header, video.metadata.total_size)?
+            .capped(self.max_chunk_size);
+        let partial = matches!(selection, RangeSelection::Partial(_));
+        let ranges = selection.into_ranges(video.metadata.total_size);
+        
+        // Open one lazy stream per range
+        let mut parts = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            parts.push(StreamVideoPart {
+                body: self.streaming_service.open_stream(&video, &range)?,
+                length: range.size(),
+                content_range: range.into(),
+            });
+        }
         
         // Create response
         Ok(StreamVideoResponse {
             video_id: video_id.as_str().to_string(),
             content_type: video.metadata.content_type.as_str().to_string(),
-            content_range: format!("bytes {}-{}/{}", range.start, range.end, range.total_size),
-            data: chunk.data,
+            etag: validators.etag.to_string(),
+            last_modified: validators.last_modified,
+            not_modified: false,
+            partial,
+            total_size: video.metadata.total_size,
+            parts,
         })
     }
 }
 
+/// Use Case: Get Video Metadata
+pub struct GetVideoMetadataUseCase<R, P>
+where
+    R: VideoRepository,
+    P: MediaProbe,
+{
+    video_repository: R,
+    media_probe: P,
+}
+
+impl<R, P> GetVideoMetadataUseCase<R, P>
+where
+    R: VideoRepository,
+    P: MediaProbe,
+{
+    pub fn new(video_repository: R, media_probe: P) -> Self {
+        Self {
+            video_repository,
+            media_probe,
+        }
+    }
+
+    /// File metadata enriched with the probed media details.
+    /// A failed probe still returns what the file system knows.
+    pub fn execute(&self, video_id: &str) -> DomainResult<VideoMetadataResponse> {
+        let video_id = VideoId::new(video_id.to_string());
+        
+        // Find video
+        let video = self.video_repository.find_by_id(&video_id)?
+            .ok_or_else(|| crate::domain::common::DomainError::not_found("video", video_id.as_str()))?;
+        
+        let metadata = match self.media_probe.probe(&video.file_path) {
+            Ok(media) => video.metadata.with_media_info(media),
+            Err(e) => {
+                eprintln!("Warning: Failed to probe {}: {}", video.file_path.as_str(), e);
+                video.metadata
+            }
+        };
+        
+        let mut response = VideoMetadataResponse::from(metadata);
+        response.video_id = video_id.as_str().to_string();
+        Ok(response)
+    }
+}
+
 /// Use Case: Create Streaming Session
 pub struct CreateSessionUseCase<R> 
 where 
     R: SessionRepository,
 {
     session_repository: R,
+    ids: SharedIdGenerator,
 }
 
 impl<R> CreateSessionUseCase<R>
@@ -64,7 +152,16 @@ where
     R: SessionRepository,
 {
     pub fn new(session_repository: R) -> Self {
-        Self { session_repository }
+        Self { 
+            session_repository,
+            ids: Arc::new(UuidV7Generator),
+        }
+    }
+
+    /// Name new sessions with ids from `ids` instead of UUIDv7s
+    pub fn with_id_generator(mut self, ids: SharedIdGenerator) -> Self {
+        self.ids = ids;
+        self
     }
 
     pub fn execute(&self, request: CreateSessionRequest) -> DomainResult<SessionResponse> {
@@ -72,7 +169,7 @@ where
         let client_info = ClientInfo::new(request.user_agent, request.ip_address);
         
         // Create session
-        let mut session = SessionManager::create_session(video_id, client_info);
+        let mut session = SessionManager::create_session_with_ids(self.ids.as_ref(), video_id, client_info);
         
         // Start session
         session.start()?;
@@ -115,7 +212,7 @@ where
         let session_id = SessionId::new(session_id.to_string());
         
         let mut session = self.session_repository.find_by_id(&session_id)?
-            .ok_or_else(|| crate::domain::common::DomainError::FileNotFound)?;
+            .ok_or_else(|| crate::domain::common::DomainError::not_found("session", session_id.as_str()))?;
         
         session.pause()?;
         self.session_repository.save(&session)?;
@@ -137,7 +234,7 @@ where
         let session_id = SessionId::new(session_id.to_string());
         
         let mut session = self.session_repository.find_by_id(&session_id)?
-            .ok_or_else(|| crate::domain::common::DomainError::FileNotFound)?;
+            .ok_or_else(|| crate::domain::common::DomainError::not_found("session", session_id.as_str()))?;
         
         session.resume()?;
         self.session_repository.save(&session)?;
@@ -159,7 +256,7 @@ where
         let session_id = SessionId::new(session_id.to_string());
         
         let mut session = self.session_repository.find_by_id(&session_id)?
-            .ok_or_else(|| crate::domain::common::DomainError::FileNotFound)?;
+            .ok_or_else(|| crate::domain::common::DomainError::not_found("session", session_id.as_str()))?;
         
         session.end()?;
         self.session_repository.save(&session)?;
@@ -1,32 +1,151 @@
 use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
 
 /// Domain error types
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum DomainError {
+    /// A video, job, file or image that does not exist
+    NotFound { entity: &'static str, id: String },
+    /// Input that failed validation, reported per field
+    Validation(Vec<FieldError>),
+    /// The operation clashes with the current state, e.g. starting a job twice
+    Conflict(String),
+    /// A stored file format or feature this service cannot handle
+    Unsupported(String),
+    /// A request body in a media type the endpoint does not accept
+    UnsupportedMediaType(String),
+    /// An external tool (FFmpeg, ffprobe) could not run or exited with an error
+    ExternalTool { tool: String, stderr: String },
+    Io(String),
     InvalidRange,
-    FileNotFound,
-    InvalidContentType,
-    InvalidState(String),
-    IoError(String),
+    RangeNotSatisfiable(u64),
+    ProbeFailed(String),
+}
+
+impl DomainError {
+    pub fn not_found(entity: &'static str, id: impl Into<String>) -> Self {
+        DomainError::NotFound { entity, id: id.into() }
+    }
+
+    /// Validation failure of a single field
+    pub fn validation(field: impl Into<String>, message: impl Into<String>) -> Self {
+        DomainError::Validation(vec![FieldError::new(field, message)])
+    }
+
+    pub fn external_tool(tool: impl Into<String>, stderr: impl Into<String>) -> Self {
+        DomainError::ExternalTool { tool: tool.into(), stderr: stderr.into() }
+    }
+
+    /// Stable, machine-readable identifier of the error kind
+    pub fn code(&self) -> &'static str {
+        match self {
+            DomainError::NotFound { .. } => "not_found",
+            DomainError::Validation(_) => "validation_failed",
+            DomainError::Conflict(_) => "conflict",
+            DomainError::Unsupported(_) => "unsupported",
+            DomainError::UnsupportedMediaType(_) => "unsupported_media_type",
+            DomainError::ExternalTool { .. } => "external_tool_failed",
+            DomainError::Io(_) => "io_error",
+            DomainError::InvalidRange => "invalid_range",
+            DomainError::RangeNotSatisfiable(_) => "range_not_satisfiable",
+            DomainError::ProbeFailed(_) => "probe_failed",
+        }
+    }
 }
 
 impl fmt::Display for DomainError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
+            DomainError::NotFound { entity, id } => write!(f, "{} not found: {}", entity, id),
+            DomainError::Validation(errors) => {
+                write!(f, "Invalid input: ")?;
+                for (i, error) in errors.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}", error)?;
+                }
+                Ok(())
+            }
+            DomainError::Conflict(msg) => write!(f, "Conflict: {}", msg),
+            DomainError::Unsupported(msg) => write!(f, "Unsupported: {}", msg),
+            DomainError::UnsupportedMediaType(msg) => write!(f, "Unsupported media type: {}", msg),
+            DomainError::ExternalTool { tool, stderr } => write!(f, "{} failed: {}", tool, stderr),
+            DomainError::Io(msg) => write!(f, "IO error: {}", msg),
             DomainError::InvalidRange => write!(f, "Invalid range request"),
-            DomainError::FileNotFound => write!(f, "File not found"),
-            DomainError::InvalidContentType => write!(f, "Invalid content type"),
-            DomainError::InvalidState(msg) => write!(f, "Invalid state: {}", msg),
-            DomainError::IoError(msg) => write!(f, "IO error: {}", msg),
+            DomainError::RangeNotSatisfiable(total_size) => write!(f, "Range not satisfiable for {} bytes", total_size),
+            DomainError::ProbeFailed(msg) => write!(f, "Media probe failed: {}", msg),
         }
     }
 }
 
 impl std::error::Error for DomainError {}
 
+/// Value Object: Problem with one field of some input
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError {
+    pub field: String, // e.g. "images[2].path"
+    pub message: String,
+}
+
+impl FieldError {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        FieldError {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.field, self.message)
+    }
+}
+
 /// Result type for domain operations
 pub type DomainResult<T> = Result<T, DomainError>;
 
+/// Domain Service: Source of identifiers for new entities
+pub trait IdGenerator: Send + Sync {
+    /// A new identifier, never returned before by this generator
+    fn next_id(&self) -> String;
+}
+
+/// Shared identifier source, e.g. held by application services
+pub type SharedIdGenerator = Arc<dyn IdGenerator>;
+
+/// UUIDv7 identifiers: unique across requests and processes, and sorted by creation time
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidV7Generator;
+
+impl IdGenerator for UuidV7Generator {
+    fn next_id(&self) -> String {
+        uuid::Uuid::now_v7().to_string()
+    }
+}
+
+/// Predictable identifiers `1`, `2`, ... zero-padded to sort in order; for tests
+#[derive(Debug, Default)]
+pub struct SequentialIdGenerator {
+    last: AtomicU64,
+}
+
+impl SequentialIdGenerator {
+    pub fn new() -> Self {
+        Self {
+            last: AtomicU64::new(0),
+        }
+    }
+}
+
+impl IdGenerator for SequentialIdGenerator {
+    fn next_id(&self) -> String {
+        format!("{:08}", self.last.fetch_add(1, Ordering::SeqCst) + 1)
+    }
+}
+
 /// Value Object: Content Type
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ContentType(String);
@@ -35,8 +154,9 @@ impl ContentType {
     pub fn new(content_type: String) -> DomainResult<Self> {
         match content_type.as_str() {
             "video/webm" | "video/mp4" | "video/x-msvideo" | 
-            "video/quicktime" | "video/x-matroska" => Ok(ContentType(content_type)),
-            _ => Err(DomainError::InvalidContentType),
+            "video/quicktime" | "video/x-matroska" |
+            "video/mp2t" | "video/ogg" => Ok(ContentType(content_type)),
+            other => Err(DomainError::Unsupported(format!("content type {}", other))),
         }
     }
 
@@ -89,4 +209,18 @@ impl ByteRange {
         self.end < self.total_size && 
         self.start < self.total_size
     }
+
+    /// Shorten the range to at most `max_size` bytes, keeping its start
+    pub fn capped(&self, max_size: u64) -> Self {
+        let max_size = max_size.max(1);
+        if self.size() <= max_size {
+            return self.clone();
+        }
+
+        ByteRange {
+            start: self.start,
+            end: self.start + max_size - 1,
+            total_size: self.total_size,
+        }
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,185 @@
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Value Object: Entity Tag (RFC 9110, section 8.8.3)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EntityTag {
+    pub tag: String,
+    pub weak: bool,
+}
+
+impl EntityTag {
+    pub fn strong(tag: String) -> Self {
+        EntityTag { tag, weak: false }
+    }
+
+    pub fn weak(tag: String) -> Self {
+        EntityTag { tag, weak: true }
+    }
+
+    /// Parse a quoted entity tag such as `"abc"` or `W/"abc"`
+    pub fn parse(value: &str) -> Option<Self> {
+        let value = value.trim();
+        let (weak, quoted) = match value.strip_prefix("W/") {
+            Some(rest) => (true, rest),
+            None => (false, value),
+        };
+
+        let tag = quoted.strip_prefix('"')?.strip_suffix('"')?;
+        if tag.contains('"') {
+            return None;
+        }
+
+        Some(EntityTag { tag: tag.to_string(), weak })
+    }
+
+    /// Strong comparison: both tags are strong and identical
+    pub fn strong_eq(&self, other: &EntityTag) -> bool {
+        !self.weak && !other.weak && self.tag == other.tag
+    }
+
+    /// Weak comparison: the opaque tags are identical, weakness is ignored
+    pub fn weak_eq(&self, other: &EntityTag) -> bool {
+        self.tag == other.tag
+    }
+}
+
+impl fmt::Display for EntityTag {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.weak {
+            write!(f, "W/\"{}\"", self.tag)
+        } else {
+            write!(f, "\"{}\"", self.tag)
+        }
+    }
+}
+
+/// Value Object: If-None-Match condition
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EntityTagMatch {
+    Any,
+    Tags(Vec<EntityTag>),
+}
+
+impl EntityTagMatch {
+    /// Parse `*` or a comma separated list of entity tags; invalid members are skipped
+    pub fn parse(value: &str) -> Option<Self> {
+        if value.trim() == "*" {
+            return Some(EntityTagMatch::Any);
+        }
+
+        let tags: Vec<EntityTag> = value.split(',')
+            .filter_map(EntityTag::parse)
+            .collect();
+
+        if tags.is_empty() {
+            None
+        } else {
+            Some(EntityTagMatch::Tags(tags))
+        }
+    }
+
+    fn matches_weak(&self, etag: &EntityTag) -> bool {
+        match self {
+            EntityTagMatch::Any => true,
+            EntityTagMatch::Tags(tags) => tags.iter().any(|t| t.weak_eq(etag)),
+        }
+    }
+}
+
+/// Value Object: If-Range condition, either an entity tag or an HTTP date
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IfRange {
+    EntityTag(EntityTag),
+    Date(SystemTime),
+}
+
+/// Value Object: Validators describing the current version of a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Validators {
+    pub etag: EntityTag,
+    pub last_modified: Option<SystemTime>,
+}
+
+impl Validators {
+    /// Derive a strong ETag from the file size, modification time and inode
+    pub fn from_file_attributes(total_size: u64, last_modified: Option<SystemTime>, inode: Option<u64>) -> Self {
+        let mut tag = format!("{:x}", total_size);
+        if let Some(nanos) = last_modified.and_then(|t| t.duration_since(UNIX_EPOCH).ok()).map(|d| d.as_nanos()) {
+            tag.push_str(&format!("-{:x}", nanos));
+        }
+        if let Some(inode) = inode {
+            tag.push_str(&format!("-{:x}", inode));
+        }
+
+        Validators {
+            etag: EntityTag::strong(tag),
+            last_modified,
+        }
+    }
+
+    /// Last-Modified truncated to the one-second precision of HTTP dates
+    pub fn last_modified_seconds(&self) -> Option<SystemTime> {
+        self.last_modified.map(truncate_to_seconds)
+    }
+}
+
+/// Value Object: Conditional request headers relevant to GET and HEAD
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ConditionalRequest {
+    pub if_none_match: Option<EntityTagMatch>,
+    pub if_modified_since: Option<SystemTime>,
+    pub if_range: Option<IfRange>,
+}
+
+/// Value Object: Result of evaluating preconditions (RFC 9110, section 13.2.2)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConditionalOutcome {
+    /// The client's cached copy is current: reply 304 Not Modified
+    NotModified,
+    /// Serve the file; `honor_range` is false when If-Range did not match
+    Proceed { honor_range: bool },
+}
+
+impl ConditionalRequest {
+    pub fn evaluate(&self, validators: &Validators) -> ConditionalOutcome {
+        // If-None-Match takes precedence; If-Modified-Since is ignored when it is present
+        if let Some(if_none_match) = &self.if_none_match {
+            if if_none_match.matches_weak(&validators.etag) {
+                return ConditionalOutcome::NotModified;
+            }
+        } else if let (Some(since), Some(modified)) = (self.if_modified_since, validators.last_modified_seconds()) {
+            if modified <= since {
+                return ConditionalOutcome::NotModified;
+            }
+        }
+
+        let honor_range = match &self.if_range {
+            None => true,
+            Some(IfRange::EntityTag(etag)) => etag.strong_eq(&validators.etag),
+            Some(IfRange::Date(date)) => validators.last_modified_seconds() == Some(truncate_to_seconds(*date)),
+        };
+
+        ConditionalOutcome::Proceed { honor_range }
+    }
+}
+
+/// Inode number of a file, where the platform exposes one
+#[cfg(unix)]
+pub fn file_inode(metadata: &std::fs::Metadata) -> Option<u64> {
+    use std::os::unix::fs::MetadataExt;
+    Some(metadata.ino())
+}
+
+/// Inode number of a file, where the platform exposes one
+#[cfg(not(unix))]
+pub fn file_inode(_metadata: &std::fs::Metadata) -> Option<u64> {
+    None
+}
+
+fn truncate_to_seconds(time: SystemTime) -> SystemTime {
+    match time.duration_since(UNIX_EPOCH) {
+        Ok(duration) => UNIX_EPOCH + Duration::from_secs(duration.as_secs()),
+        Err(_) => time,
+    }
+}
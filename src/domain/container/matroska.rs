@@ -0,0 +1,296 @@
+// EBML reader for Matroska and WebM files
+use std::io::{Read, Seek, SeekFrom};
+use crate::domain::common::{DomainResult, DomainError};
+use crate::domain::media::{MediaInfo, MediaStream, MediaStreamKind};
+use super::{ByteCursor, overall_bitrate, read_element, read_up_to};
+
+pub(crate) const EBML_MAGIC: [u8; 4] = [0x1A, 0x45, 0xDF, 0xA3];
+
+// Element IDs (https://www.matroska.org/technical/elements.html)
+const EBML: u32 = 0x1A45_DFA3;
+const DOC_TYPE: u32 = 0x4282;
+const SEGMENT: u32 = 0x1853_8067;
+const INFO: u32 = 0x1549_A966;
+const TIMESTAMP_SCALE: u32 = 0x2A_D7B1;
+const DURATION: u32 = 0x4489;
+const TRACKS: u32 = 0x1654_AE6B;
+const TRACK_ENTRY: u32 = 0xAE;
+const TRACK_TYPE: u32 = 0x83;
+const CODEC_ID: u32 = 0x86;
+const DEFAULT_DURATION: u32 = 0x23_E383;
+const VIDEO: u32 = 0xE0;
+const PIXEL_WIDTH: u32 = 0xB0;
+const PIXEL_HEIGHT: u32 = 0xBA;
+const CLUSTER: u32 = 0x1F43_B675;
+
+/// Nanoseconds per Segment tick unless the file says otherwise
+const DEFAULT_TIMESTAMP_SCALE: u64 = 1_000_000;
+
+/// Segment children visited before giving up on finding Info and Tracks
+const MAX_SEGMENT_CHILDREN: usize = 4096;
+
+/// Read the EBML header, then the `Info` and `Tracks` elements of the first Segment.
+/// Clusters are skipped with seeks; the walk ends at the first cluster once both are found.
+pub fn parse_matroska<R: Read + Seek>(reader: &mut R, file_size: u64) -> DomainResult<MediaInfo> {
+    reader.seek(SeekFrom::Start(0))
+        .map_err(|e| DomainError::Io(e.to_string()))?;
+
+    let (id, size, header_size) = read_header(reader)?;
+    let Some(size) = size.filter(|_| id == EBML) else {
+        return Err(DomainError::ProbeFailed("Missing EBML header".to_string()));
+    };
+    let header = read_element(reader, size.min(4096))?;
+    let doc_type = Elements::new(&header)
+        .find(|(id, _)| *id == DOC_TYPE)
+        .map(|(_, body)| read_string(body));
+
+    // The Segment follows the header, possibly after Void elements
+    let mut position = header_size.saturating_add(size);
+    reader.seek(SeekFrom::Start(position))
+        .map_err(|e| DomainError::Io(e.to_string()))?;
+    let segment_end = loop {
+        let (id, size, header_size) = read_header(reader)?;
+        let body_start = position.saturating_add(header_size);
+        if id == SEGMENT {
+            break size.map_or(file_size, |size| body_start.saturating_add(size).min(file_size));
+        }
+        position = body_start.checked_add(size.ok_or_else(|| DomainError::ProbeFailed("Missing Segment".to_string()))?)
+            .ok_or_else(|| DomainError::ProbeFailed("Invalid element size".to_string()))?;
+        reader.seek(SeekFrom::Start(position))
+            .map_err(|e| DomainError::Io(e.to_string()))?;
+    };
+
+    let mut info = None;
+    let mut tracks = None;
+    for _ in 0..MAX_SEGMENT_CHILDREN {
+        let position = reader.stream_position()
+            .map_err(|e| DomainError::Io(e.to_string()))?;
+        if position >= segment_end || (info.is_some() && tracks.is_some()) {
+            break;
+        }
+
+        let (id, size, header_size) = match read_header(reader) {
+            Ok(header) => header,
+            Err(_) => break, // truncated file: keep what was found so far
+        };
+        let Some(size) = size else {
+            break; // unknown-size children (live clusters) cannot be skipped
+        };
+
+        // Truncated files: read whatever part of the element is present
+        let available = size.min(segment_end.saturating_sub(position.saturating_add(header_size)));
+        match id {
+            INFO => info = Some(read_element(reader, available)?),
+            TRACKS => tracks = Some(read_element(reader, available)?),
+            CLUSTER if info.is_some() && tracks.is_some() => break,
+            _ => {}
+        }
+
+        let next = position.saturating_add(header_size).saturating_add(size);
+        reader.seek(SeekFrom::Start(next))
+            .map_err(|e| DomainError::Io(e.to_string()))?;
+    }
+
+    if info.is_none() && tracks.is_none() {
+        return Err(DomainError::ProbeFailed("No Segment Info or Tracks found".to_string()));
+    }
+
+    let (timestamp_scale, duration) = info.as_deref().map(parse_info).unwrap_or((DEFAULT_TIMESTAMP_SCALE, None));
+    Ok(MediaInfo {
+        format: Some(doc_type.unwrap_or_else(|| "matroska".to_string())),
+        duration,
+        bitrate: overall_bitrate(file_size, duration),
+        timescale: u32::try_from(1_000_000_000 / timestamp_scale.max(1)).ok().filter(|&t| t > 0),
+        streams: tracks.as_deref().map(parse_tracks).unwrap_or_default(),
+    })
+}
+
+/// DocType declared by the EBML header at the start of `head`
+pub(crate) fn doc_type(head: &[u8]) -> Option<String> {
+    let (id, header) = Elements::new(head).next()?;
+    if id != EBML {
+        return None;
+    }
+    Elements::new(header)
+        .find(|(id, _)| *id == DOC_TYPE)
+        .map(|(_, body)| read_string(body))
+}
+
+/// Timestamp scale and duration in seconds from a Segment `Info` element
+fn parse_info(info: &[u8]) -> (u64, Option<f64>) {
+    let mut timestamp_scale = DEFAULT_TIMESTAMP_SCALE;
+    let mut ticks = None;
+
+    for (id, body) in Elements::new(info) {
+        match id {
+            TIMESTAMP_SCALE => timestamp_scale = read_uint(body).filter(|&s| s > 0).unwrap_or(DEFAULT_TIMESTAMP_SCALE),
+            DURATION => ticks = read_float(body),
+            _ => {}
+        }
+    }
+
+    let duration = ticks
+        .map(|ticks| ticks * timestamp_scale as f64 / 1_000_000_000.0)
+        .filter(|seconds| seconds.is_finite() && *seconds >= 0.0);
+    (timestamp_scale, duration)
+}
+
+fn parse_tracks(tracks: &[u8]) -> Vec<MediaStream> {
+    Elements::new(tracks)
+        .filter(|(id, _)| *id == TRACK_ENTRY)
+        .enumerate()
+        .map(|(index, (_, entry))| parse_track_entry(entry, index as u32))
+        .collect()
+}
+
+fn parse_track_entry(entry: &[u8], index: u32) -> MediaStream {
+    let mut stream = MediaStream {
+        index,
+        kind: MediaStreamKind::Other("unknown".to_string()),
+        codec: None,
+        width: None,
+        height: None,
+        frame_rate: None,
+        bitrate: None,
+        duration: None,
+    };
+
+    for (id, body) in Elements::new(entry) {
+        match id {
+            TRACK_TYPE => {
+                stream.kind = match read_uint(body) {
+                    Some(1) => MediaStreamKind::Video,
+                    Some(2) => MediaStreamKind::Audio,
+                    Some(17) => MediaStreamKind::Subtitle,
+                    Some(other) => MediaStreamKind::Other(format!("type {}", other)),
+                    None => MediaStreamKind::Other("unknown".to_string()),
+                }
+            }
+            CODEC_ID => stream.codec = Some(read_string(body)),
+            // Nanoseconds per frame
+            DEFAULT_DURATION => {
+                stream.frame_rate = read_uint(body).filter(|&ns| ns > 0).map(|ns| 1_000_000_000.0 / ns as f64)
+            }
+            VIDEO => {
+                for (id, body) in Elements::new(body) {
+                    match id {
+                        PIXEL_WIDTH => stream.width = read_uint(body).and_then(|w| u32::try_from(w).ok()),
+                        PIXEL_HEIGHT => stream.height = read_uint(body).and_then(|h| u32::try_from(h).ok()),
+                        _ => {}
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    if stream.kind != MediaStreamKind::Video {
+        stream.frame_rate = None;
+    }
+    stream
+}
+
+/// Read an element ID and size from a stream; returns the header length as well
+fn read_header<R: Read>(reader: &mut R) -> DomainResult<(u32, Option<u64>, u64)> {
+    let truncated = || DomainError::ProbeFailed("Truncated EBML element".to_string());
+
+    let mut first = [0u8; 1];
+    if read_up_to(reader, &mut first)? == 0 {
+        return Err(truncated());
+    }
+    let id_length = first[0].leading_zeros() as usize + 1;
+    if id_length > 4 {
+        return Err(DomainError::ProbeFailed("Invalid EBML element ID".to_string()));
+    }
+    let mut id_bytes = [0u8; 4];
+    id_bytes[0] = first[0];
+    if read_up_to(reader, &mut id_bytes[1..id_length])? < id_length - 1 {
+        return Err(truncated());
+    }
+
+    if read_up_to(reader, &mut first)? == 0 {
+        return Err(truncated());
+    }
+    let size_length = first[0].leading_zeros() as usize + 1;
+    if size_length > 8 {
+        return Err(DomainError::ProbeFailed("Invalid EBML element size".to_string()));
+    }
+    let mut size_bytes = [0u8; 8];
+    size_bytes[0] = first[0];
+    if read_up_to(reader, &mut size_bytes[1..size_length])? < size_length - 1 {
+        return Err(truncated());
+    }
+
+    let id = id_bytes[..id_length].iter().fold(0u32, |id, &b| (id << 8) | b as u32);
+    let size = decode_size(&size_bytes[..size_length]);
+    Ok((id, size, (id_length + size_length) as u64))
+}
+
+/// Decode a size VINT; all value bits set means "unknown size"
+fn decode_size(bytes: &[u8]) -> Option<u64> {
+    let length = bytes.len();
+    let marker_mask = (0xFFu16 >> length) as u8;
+    let value = bytes[1..].iter().fold((bytes[0] & marker_mask) as u64, |value, &b| (value << 8) | b as u64);
+    let unknown = (1u64 << (7 * length)) - 1;
+    (value != unknown).then_some(value)
+}
+
+/// Iterator over the elements contained in a buffer, stopping at the first malformed header
+struct Elements<'a> {
+    cursor: ByteCursor<'a>,
+}
+
+impl<'a> Elements<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { cursor: ByteCursor::new(data) }
+    }
+}
+
+impl<'a> Iterator for Elements<'a> {
+    type Item = (u32, &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let first = self.cursor.u8()?;
+        let id_length = first.leading_zeros() as usize + 1;
+        if id_length > 4 {
+            return None;
+        }
+        let id = self.cursor.take(id_length - 1)?
+            .iter()
+            .fold(first as u32, |id, &b| (id << 8) | b as u32);
+
+        let first = self.cursor.u8()?;
+        let size_length = first.leading_zeros() as usize + 1;
+        if size_length > 8 {
+            return None;
+        }
+        let mut size_bytes = vec![first];
+        size_bytes.extend_from_slice(self.cursor.take(size_length - 1)?);
+
+        let body = match decode_size(&size_bytes) {
+            Some(size) => self.cursor.take(usize::try_from(size).ok()?)?,
+            None => self.cursor.rest(),
+        };
+        Some((id, body))
+    }
+}
+
+fn read_uint(body: &[u8]) -> Option<u64> {
+    if body.is_empty() || body.len() > 8 {
+        return None;
+    }
+    Some(body.iter().fold(0u64, |value, &b| (value << 8) | b as u64))
+}
+
+fn read_float(body: &[u8]) -> Option<f64> {
+    let mut cursor = ByteCursor::new(body);
+    match body.len() {
+        4 => cursor.u32().map(|bits| f32::from_bits(bits) as f64),
+        8 => cursor.u64().map(f64::from_bits),
+        _ => None,
+    }
+}
+
+fn read_string(body: &[u8]) -> String {
+    String::from_utf8_lossy(body).trim_end_matches('\0').to_string()
+}
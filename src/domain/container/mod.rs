@@ -0,0 +1,135 @@
+// Native container parsers - read media metadata without external tools
+pub mod mp4;
+pub mod matroska;
+pub mod sniff;
+
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use crate::domain::common::{DomainResult, DomainError, FilePath};
+use crate::domain::media::{MediaInfo, MediaProbe};
+
+pub use mp4::parse_mp4;
+pub use matroska::parse_matroska;
+pub use sniff::{sniff_content_type, content_type_from_extension, detect_content_type};
+
+/// Largest metadata element (`moov`, `Tracks`, ...) read into memory.
+/// Anything bigger is treated as corrupt rather than risking a huge allocation.
+pub const MAX_METADATA_ELEMENT_SIZE: u64 = 64 * 1024 * 1024;
+
+/// Parse a container, picking the parser from the file's leading bytes
+pub fn parse_container<R: Read + Seek>(reader: &mut R, file_size: u64) -> DomainResult<MediaInfo> {
+    let mut head = [0u8; 8];
+    reader.seek(SeekFrom::Start(0))
+        .map_err(|e| DomainError::Io(e.to_string()))?;
+    read_up_to(reader, &mut head)?;
+
+    if head[..4] == matroska::EBML_MAGIC {
+        parse_matroska(reader, file_size)
+    } else if mp4::is_top_level_box(&head[4..8]) {
+        parse_mp4(reader, file_size)
+    } else {
+        Err(DomainError::ProbeFailed("Unrecognized container format".to_string()))
+    }
+}
+
+/// Domain Service: Media probe backed by the native MP4 and Matroska parsers
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NativeMediaProbe;
+
+impl MediaProbe for NativeMediaProbe {
+    fn probe(&self, file_path: &FilePath) -> DomainResult<MediaInfo> {
+        let file = File::open(file_path.as_str())
+            .map_err(|_| DomainError::not_found("file", file_path.as_str()))?;
+        let file_size = file.metadata()
+            .map_err(|e| DomainError::Io(e.to_string()))?
+            .len();
+
+        parse_container(&mut BufReader::new(file), file_size)
+    }
+}
+
+/// Overall bitrate in bits per second from the file size and duration
+fn overall_bitrate(file_size: u64, duration: Option<f64>) -> Option<u64> {
+    duration
+        .filter(|&seconds| seconds > 0.0)
+        .map(|seconds| (file_size as f64 * 8.0 / seconds) as u64)
+}
+
+/// Fill `buffer` as far as the reader allows, returning how many bytes were read
+fn read_up_to<R: Read>(reader: &mut R, buffer: &mut [u8]) -> DomainResult<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match reader.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(read) => filled += read,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(DomainError::Io(e.to_string())),
+        }
+    }
+    Ok(filled)
+}
+
+/// Read an element body of `length` bytes, truncated to what the file actually holds
+fn read_element<R: Read>(reader: &mut R, length: u64) -> DomainResult<Vec<u8>> {
+    if length > MAX_METADATA_ELEMENT_SIZE {
+        return Err(DomainError::ProbeFailed(format!("Metadata element of {} bytes is too large", length)));
+    }
+
+    let mut buffer = vec![0u8; length as usize];
+    let read = read_up_to(reader, &mut buffer)?;
+    buffer.truncate(read);
+    Ok(buffer)
+}
+
+/// Bounds-checked big-endian reader over an in-memory buffer.
+/// Every accessor returns `None` instead of panicking when the data runs out.
+#[derive(Debug, Clone)]
+pub(crate) struct ByteCursor<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> ByteCursor<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    pub(crate) fn remaining(&self) -> usize {
+        self.data.len() - self.position
+    }
+
+    pub(crate) fn take(&mut self, length: usize) -> Option<&'a [u8]> {
+        if length > self.remaining() {
+            return None;
+        }
+        let bytes = &self.data[self.position..self.position + length];
+        self.position += length;
+        Some(bytes)
+    }
+
+    pub(crate) fn skip(&mut self, length: usize) -> Option<()> {
+        self.take(length).map(|_| ())
+    }
+
+    pub(crate) fn rest(&mut self) -> &'a [u8] {
+        let bytes = &self.data[self.position..];
+        self.position = self.data.len();
+        bytes
+    }
+
+    pub(crate) fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|b| u16::from_be_bytes([b[0], b[1]]))
+    }
+
+    pub(crate) fn u32(&mut self) -> Option<u32> {
+        self.take(4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub(crate) fn u64(&mut self) -> Option<u64> {
+        self.take(8).map(|b| u64::from_be_bytes([b[0], b[1], b[2], b[3], b[4], b[5], b[6], b[7]]))
+    }
+}
@@ -0,0 +1,270 @@
+// ISO Base Media File Format (MP4, QuickTime) box walker
+use std::io::{Read, Seek, SeekFrom};
+use crate::domain::common::{DomainResult, DomainError};
+use crate::domain::media::{MediaInfo, MediaStream, MediaStreamKind};
+use super::{ByteCursor, overall_bitrate, read_element, read_up_to};
+
+/// Box types that may appear first in an ISO-BMFF file
+const TOP_LEVEL_BOXES: [&[u8; 4]; 7] = [b"ftyp", b"moov", b"mdat", b"free", b"skip", b"wide", b"pnot"];
+
+pub(crate) fn is_top_level_box(box_type: &[u8]) -> bool {
+    TOP_LEVEL_BOXES.iter().any(|t| t.as_slice() == box_type)
+}
+
+/// Walk the top-level boxes of an MP4/QuickTime file and read its `moov` box.
+/// `mdat` and other payload boxes are skipped with seeks, so `moov` may sit at either end.
+pub fn parse_mp4<R: Read + Seek>(reader: &mut R, file_size: u64) -> DomainResult<MediaInfo> {
+    let mut position = 0u64;
+    let mut brand = None;
+
+    while position.saturating_add(8) <= file_size {
+        reader.seek(SeekFrom::Start(position))
+            .map_err(|e| DomainError::Io(e.to_string()))?;
+
+        let mut header = [0u8; 16];
+        if read_up_to(reader, &mut header[..8])? < 8 {
+            break;
+        }
+        let mut cursor = ByteCursor::new(&header[..8]);
+        let size = cursor.u32().unwrap_or(0) as u64;
+        let box_type: [u8; 4] = header[4..8].try_into().unwrap_or_default();
+
+        let (box_size, header_size) = match size {
+            0 => (file_size.saturating_sub(position), 8),
+            1 => {
+                if read_up_to(reader, &mut header[8..16])? < 8 {
+                    break;
+                }
+                (ByteCursor::new(&header[8..16]).u64().unwrap_or(0), 16)
+            }
+            size => (size, 8),
+        };
+        if box_size < header_size {
+            return Err(DomainError::ProbeFailed(format!("Invalid size for box {}", fourcc(&box_type))));
+        }
+
+        // Truncated files: read whatever part of the box is present
+        let body_size = (box_size - header_size).min(file_size.saturating_sub(position + header_size));
+        match &box_type {
+            b"ftyp" => {
+                let body = read_element(reader, body_size.min(256))?;
+                brand = body.get(..4).map(fourcc);
+            }
+            b"moov" => {
+                let body = read_element(reader, body_size)?;
+                return Ok(parse_moov(&body, brand.as_deref(), file_size));
+            }
+            _ => {}
+        }
+
+        position = match position.checked_add(box_size) {
+            Some(next) => next,
+            None => break,
+        };
+    }
+
+    Err(DomainError::ProbeFailed("No moov box found".to_string()))
+}
+
+/// Iterator over the boxes contained in a buffer, stopping at the first malformed header
+struct Boxes<'a> {
+    cursor: ByteCursor<'a>,
+}
+
+impl<'a> Boxes<'a> {
+    fn new(data: &'a [u8]) -> Self {
+        Self { cursor: ByteCursor::new(data) }
+    }
+}
+
+impl<'a> Iterator for Boxes<'a> {
+    type Item = ([u8; 4], &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let size = self.cursor.u32()? as u64;
+        let box_type: [u8; 4] = self.cursor.take(4)?.try_into().ok()?;
+
+        let body = match size {
+            0 => self.cursor.rest(),
+            1 => {
+                let size = self.cursor.u64()?;
+                self.cursor.take(usize::try_from(size.checked_sub(16)?).ok()?)?
+            }
+            size => self.cursor.take(usize::try_from(size.checked_sub(8)?).ok()?)?,
+        };
+        Some((box_type, body))
+    }
+}
+
+/// Find the first child box of the given type
+fn child<'a>(data: &'a [u8], box_type: &[u8; 4]) -> Option<&'a [u8]> {
+    Boxes::new(data).find(|(t, _)| t == box_type).map(|(_, body)| body)
+}
+
+/// Follow a path of nested boxes, such as `mdia/minf/stbl`
+fn descend<'a>(data: &'a [u8], path: &[&[u8; 4]]) -> Option<&'a [u8]> {
+    path.iter().try_fold(data, |body, box_type| child(body, box_type))
+}
+
+fn parse_moov(moov: &[u8], brand: Option<&str>, file_size: u64) -> MediaInfo {
+    let header = child(moov, b"mvhd").and_then(parse_full_header);
+
+    let duration = header.and_then(|(timescale, duration)| seconds(duration, timescale));
+    let streams = Boxes::new(moov)
+        .filter(|(box_type, _)| box_type == b"trak")
+        .enumerate()
+        .map(|(index, (_, trak))| parse_trak(trak, index as u32))
+        .collect();
+
+    MediaInfo {
+        format: Some(match brand {
+            Some("qt  ") => "mov".to_string(),
+            _ => "mp4".to_string(),
+        }),
+        duration,
+        bitrate: overall_bitrate(file_size, duration),
+        timescale: header.map(|(timescale, _)| timescale).filter(|&t| t > 0),
+        streams,
+    }
+}
+
+/// Timescale and duration from an `mvhd` or `mdhd` box, for both box versions
+fn parse_full_header(body: &[u8]) -> Option<(u32, u64)> {
+    let mut cursor = ByteCursor::new(body);
+    let version = cursor.u8()?;
+    cursor.skip(3)?; // flags
+
+    if version == 1 {
+        cursor.skip(16)?; // creation and modification time
+        let timescale = cursor.u32()?;
+        let duration = cursor.u64()?;
+        Some((timescale, duration))
+    } else {
+        cursor.skip(8)?;
+        let timescale = cursor.u32()?;
+        // All ones marks an unknown duration
+        let duration = match cursor.u32()? {
+            u32::MAX => u64::MAX,
+            duration => duration as u64,
+        };
+        Some((timescale, duration))
+    }
+}
+
+fn seconds(duration: u64, timescale: u32) -> Option<f64> {
+    if timescale == 0 || duration == u64::MAX {
+        return None;
+    }
+    Some(duration as f64 / timescale as f64)
+}
+
+fn parse_trak(trak: &[u8], index: u32) -> MediaStream {
+    let mdia = child(trak, b"mdia");
+    let media_header = mdia.and_then(|mdia| child(mdia, b"mdhd")).and_then(parse_full_header);
+    let handler = mdia.and_then(|mdia| child(mdia, b"hdlr")).and_then(|hdlr| {
+        let mut cursor = ByteCursor::new(hdlr);
+        cursor.skip(8)?; // version, flags, pre_defined
+        cursor.take(4)
+    });
+    let stbl = mdia.and_then(|mdia| descend(mdia, &[b"minf", b"stbl"]));
+    let sample_entry = stbl.and_then(|stbl| child(stbl, b"stsd")).and_then(parse_sample_entry);
+
+    let kind = match handler {
+        Some(b"vide") => MediaStreamKind::Video,
+        Some(b"soun") => MediaStreamKind::Audio,
+        Some(b"subt") | Some(b"text") | Some(b"sbtl") => MediaStreamKind::Subtitle,
+        Some(b"meta") | Some(b"hint") | Some(b"tmcd") => MediaStreamKind::Data,
+        Some(other) => MediaStreamKind::Other(fourcc(other)),
+        None => MediaStreamKind::Other("unknown".to_string()),
+    };
+
+    let (width, height) = match (kind == MediaStreamKind::Video, child(trak, b"tkhd").and_then(parse_track_dimensions)) {
+        (true, Some((width, height))) if width > 0 && height > 0 => (Some(width), Some(height)),
+        (true, _) => sample_entry.as_ref().map(|e| (e.width, e.height)).unwrap_or_default(),
+        (false, _) => (None, None),
+    };
+
+    let frame_rate = match (&kind, stbl.and_then(|stbl| child(stbl, b"stts")), media_header) {
+        (MediaStreamKind::Video, Some(stts), Some((timescale, _))) => frame_rate(stts, timescale),
+        _ => None,
+    };
+
+    MediaStream {
+        index,
+        kind,
+        codec: sample_entry.map(|e| e.codec),
+        width,
+        height,
+        frame_rate,
+        bitrate: None,
+        duration: media_header.and_then(|(timescale, duration)| seconds(duration, timescale)),
+    }
+}
+
+/// Presentation size from a `tkhd` box (16.16 fixed point)
+fn parse_track_dimensions(tkhd: &[u8]) -> Option<(u32, u32)> {
+    let mut cursor = ByteCursor::new(tkhd);
+    let version = cursor.u8()?;
+    cursor.skip(3)?;
+    // times, track id, reserved and duration
+    cursor.skip(if version == 1 { 32 } else { 20 })?;
+    // reserved, layer, alternate group, volume, reserved and matrix
+    cursor.skip(8 + 8 + 36)?;
+    let width = cursor.u32()? >> 16;
+    let height = cursor.u32()? >> 16;
+    Some((width, height))
+}
+
+struct SampleEntry {
+    codec: String,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+/// Codec fourcc of the first `stsd` entry, with the coded size for visual entries
+fn parse_sample_entry(stsd: &[u8]) -> Option<SampleEntry> {
+    let mut cursor = ByteCursor::new(stsd);
+    cursor.skip(4)?; // version and flags
+    if cursor.u32()? == 0 {
+        return None;
+    }
+
+    let (codec, body) = Boxes::new(cursor.rest()).next()?;
+    // reserved, data reference index, pre_defined and reserved of a visual sample entry
+    let mut visual = ByteCursor::new(body);
+    let dimensions = visual.skip(8 + 16).and_then(|_| Some((visual.u16()? as u32, visual.u16()? as u32)));
+
+    Some(SampleEntry {
+        codec: fourcc(&codec),
+        width: dimensions.map(|(width, _)| width).filter(|&w| w > 0),
+        height: dimensions.map(|(_, height)| height).filter(|&h| h > 0),
+    })
+}
+
+/// Average frame rate from the decoding time-to-sample table
+fn frame_rate(stts: &[u8], timescale: u32) -> Option<f64> {
+    let mut cursor = ByteCursor::new(stts);
+    cursor.skip(4)?;
+    let entries = cursor.u32()?;
+
+    let mut samples = 0u64;
+    let mut duration = 0u64;
+    for _ in 0..entries {
+        let (Some(count), Some(delta)) = (cursor.u32(), cursor.u32()) else {
+            break;
+        };
+        samples = samples.saturating_add(count as u64);
+        duration = duration.saturating_add(count as u64 * delta as u64);
+    }
+
+    if samples == 0 || duration == 0 || timescale == 0 {
+        return None;
+    }
+    Some(samples as f64 * timescale as f64 / duration as f64)
+}
+
+fn fourcc(bytes: &[u8]) -> String {
+    bytes.iter()
+        .map(|&b| if b.is_ascii_graphic() || b == b' ' { b as char } else { '?' })
+        .collect()
+}
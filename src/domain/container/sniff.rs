@@ -0,0 +1,83 @@
+// Content type detection from container signatures
+use std::fs::File;
+use std::path::Path;
+use super::{matroska, mp4, read_up_to};
+
+/// Bytes read from the start of a file for sniffing
+pub const SNIFF_LENGTH: usize = 4096;
+
+const MPEG_TS_SYNC_BYTE: u8 = 0x47;
+const MPEG_TS_PACKET_SIZE: usize = 188;
+/// M2TS (Blu-ray) packets carry a 4 byte timestamp before each TS packet
+const M2TS_PACKET_SIZE: usize = 192;
+
+/// Detect a video content type from the leading bytes of a file.
+/// Returns `None` when no known signature matches.
+pub fn sniff_content_type(head: &[u8]) -> Option<&'static str> {
+    // ISO-BMFF: `ftyp` box, whose major brand tells QuickTime from MP4
+    if head.get(4..8) == Some(b"ftyp") {
+        return match head.get(8..12) {
+            Some(b"qt  ") => Some("video/quicktime"),
+            _ => Some("video/mp4"),
+        };
+    }
+
+    // EBML header; the DocType distinguishes WebM from other Matroska files
+    if head.starts_with(&matroska::EBML_MAGIC) {
+        return match matroska::doc_type(head).as_deref() {
+            Some("webm") => Some("video/webm"),
+            _ => Some("video/x-matroska"),
+        };
+    }
+
+    if head.len() >= 12 && &head[..4] == b"RIFF" && &head[8..12] == b"AVI " {
+        return Some("video/x-msvideo");
+    }
+
+    if head.starts_with(b"OggS") {
+        return Some("video/ogg");
+    }
+
+    if is_mpeg_ts(head, 0, MPEG_TS_PACKET_SIZE) || is_mpeg_ts(head, 4, M2TS_PACKET_SIZE) {
+        return Some("video/mp2t");
+    }
+
+    // Old QuickTime files start directly with a movie or media box
+    if head.len() >= 8 && mp4::is_top_level_box(&head[4..8]) {
+        return Some("video/quicktime");
+    }
+
+    None
+}
+
+/// Sync bytes at the start of the first packets; a single 0x47 is too weak a signal
+fn is_mpeg_ts(head: &[u8], offset: usize, packet_size: usize) -> bool {
+    let packets = head.len().saturating_sub(offset) / packet_size;
+    packets >= 2 && (0..packets.min(4))
+        .all(|packet| head[offset + packet * packet_size] == MPEG_TS_SYNC_BYTE)
+}
+
+/// Content type implied by a file extension
+pub fn content_type_from_extension(path: &Path) -> Option<&'static str> {
+    let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+    match extension.as_str() {
+        "webm" => Some("video/webm"),
+        "mp4" | "m4v" => Some("video/mp4"),
+        "avi" => Some("video/x-msvideo"),
+        "mov" => Some("video/quicktime"),
+        "mkv" => Some("video/x-matroska"),
+        "ts" | "m2ts" => Some("video/mp2t"),
+        "ogv" | "ogg" => Some("video/ogg"),
+        _ => None,
+    }
+}
+
+/// Detect the content type of a file by its signature, falling back to its extension
+pub fn detect_content_type(path: &Path) -> Option<&'static str> {
+    let mut head = vec![0u8; SNIFF_LENGTH];
+    let sniffed = File::open(path).ok()
+        .and_then(|mut file| read_up_to(&mut file, &mut head).ok())
+        .and_then(|length| sniff_content_type(&head[..length]));
+
+    sniffed.or_else(|| content_type_from_extension(path))
+}
@@ -0,0 +1,133 @@
+use std::sync::Arc;
+use crate::domain::common::{DomainResult, FilePath};
+
+/// Value Object: Kind of an elementary stream inside a media container
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MediaStreamKind {
+    Video,
+    Audio,
+    Subtitle,
+    Data,
+    Other(String),
+}
+
+impl MediaStreamKind {
+    pub fn as_str(&self) -> &str {
+        match self {
+            MediaStreamKind::Video => "video",
+            MediaStreamKind::Audio => "audio",
+            MediaStreamKind::Subtitle => "subtitle",
+            MediaStreamKind::Data => "data",
+            MediaStreamKind::Other(kind) => kind,
+        }
+    }
+}
+
+impl From<&str> for MediaStreamKind {
+    fn from(kind: &str) -> Self {
+        match kind {
+            "video" => MediaStreamKind::Video,
+            "audio" => MediaStreamKind::Audio,
+            "subtitle" => MediaStreamKind::Subtitle,
+            "data" => MediaStreamKind::Data,
+            other => MediaStreamKind::Other(other.to_string()),
+        }
+    }
+}
+
+/// Value Object: One elementary stream of a media file
+#[derive(Debug, Clone, PartialEq)]
+pub struct MediaStream {
+    pub index: u32,
+    pub kind: MediaStreamKind,
+    pub codec: Option<String>,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub frame_rate: Option<f64>, // frames per second
+    pub bitrate: Option<u64>,    // in bits per second
+    pub duration: Option<f64>,   // in seconds
+}
+
+/// Value Object: Result of analysing a media file
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct MediaInfo {
+    pub format: Option<String>,
+    pub duration: Option<f64>, // in seconds
+    pub bitrate: Option<u64>,  // overall, in bits per second
+    pub timescale: Option<u32>, // container time units per second
+    pub streams: Vec<MediaStream>,
+}
+
+impl MediaInfo {
+    /// First video stream, which players treat as the main picture
+    pub fn primary_video(&self) -> Option<&MediaStream> {
+        self.streams.iter().find(|s| s.kind == MediaStreamKind::Video)
+    }
+
+    /// First audio stream
+    pub fn primary_audio(&self) -> Option<&MediaStream> {
+        self.streams.iter().find(|s| s.kind == MediaStreamKind::Audio)
+    }
+
+    pub fn width(&self) -> Option<u32> {
+        self.primary_video().and_then(|s| s.width)
+    }
+
+    pub fn height(&self) -> Option<u32> {
+        self.primary_video().and_then(|s| s.height)
+    }
+
+    pub fn frame_rate(&self) -> Option<f64> {
+        self.primary_video().and_then(|s| s.frame_rate)
+    }
+
+    pub fn video_codec(&self) -> Option<&str> {
+        self.primary_video().and_then(|s| s.codec.as_deref())
+    }
+
+    pub fn audio_codec(&self) -> Option<&str> {
+        self.primary_audio().and_then(|s| s.codec.as_deref())
+    }
+}
+
+/// Domain Service: Media Probe
+pub trait MediaProbe {
+    fn probe(&self, file_path: &FilePath) -> DomainResult<MediaInfo>;
+}
+
+/// Domain Service: Media probe that asks a second probe when the first one fails
+pub struct FallbackMediaProbe<P, F>
+where
+    P: MediaProbe,
+    F: MediaProbe,
+{
+    primary: P,
+    fallback: F,
+}
+
+impl<P, F> FallbackMediaProbe<P, F>
+where
+    P: MediaProbe,
+    F: MediaProbe,
+{
+    pub fn new(primary: P, fallback: F) -> Self {
+        Self { primary, fallback }
+    }
+}
+
+impl<P, F> MediaProbe for FallbackMediaProbe<P, F>
+where
+    P: MediaProbe,
+    F: MediaProbe,
+{
+    fn probe(&self, file_path: &FilePath) -> DomainResult<MediaInfo> {
+        self.primary.probe(file_path)
+            .or_else(|_| self.fallback.probe(file_path))
+    }
+}
+
+impl<P: MediaProbe + ?Sized> MediaProbe for Arc<P> {
+    fn probe(&self, file_path: &FilePath) -> DomainResult<MediaInfo> {
+        (**self).probe(file_path)
+    }
+}
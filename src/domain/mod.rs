@@ -3,8 +3,14 @@ pub mod video;
 pub mod streaming;
 pub mod video_creation;
 pub mod common;
+pub mod conditional;
+pub mod media;
+pub mod container;
 
 pub use video::*;
 pub use streaming::*;
 pub use video_creation::*;
-pub use common::*; 
\ No newline at end of file
+pub use common::*;
+pub use conditional::*;
+pub use media::*;
+pub use container::*;
@@ -1,4 +1,5 @@
-use crate::domain::common::{DomainResult, DomainError, ByteRange};
+use std::sync::Arc;
+use crate::domain::common::{DomainResult, DomainError, ByteRange, IdGenerator, UuidV7Generator};
 use crate::domain::video::{VideoChunk, VideoId};
 
 /// Aggregate Root: Streaming Session
@@ -29,7 +30,7 @@ impl StreamingSession {
                 self.metrics.start_time = std::time::SystemTime::now();
                 Ok(())
             }
-            _ => Err(DomainError::InvalidState("Session already started".to_string())),
+            _ => Err(DomainError::Conflict("Session already started".to_string())),
         }
     }
 
@@ -40,7 +41,7 @@ impl StreamingSession {
                 self.metrics.update_pause_time();
                 Ok(())
             }
-            _ => Err(DomainError::InvalidState("Session not active".to_string())),
+            _ => Err(DomainError::Conflict("Session not active".to_string())),
         }
     }
 
@@ -51,7 +52,7 @@ impl StreamingSession {
                 self.metrics.update_resume_time();
                 Ok(())
             }
-            _ => Err(DomainError::InvalidState("Session not paused".to_string())),
+            _ => Err(DomainError::Conflict("Session not paused".to_string())),
         }
     }
 
@@ -63,7 +64,7 @@ impl StreamingSession {
 
     pub fn request_chunk(&mut self, range: &ByteRange) -> DomainResult<VideoChunk> {
         if self.state != SessionState::Active {
-            return Err(DomainError::InvalidState("Session not active".to_string()));
+            return Err(DomainError::Conflict("Session not active".to_string()));
         }
 
         self.metrics.bytes_requested += range.size();
@@ -115,6 +116,27 @@ pub enum SessionState {
     Ended,
 }
 
+impl SessionState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SessionState::Created => "Created",
+            SessionState::Active => "Active",
+            SessionState::Paused => "Paused",
+            SessionState::Ended => "Ended",
+        }
+    }
+
+    pub fn parse(name: &str) -> DomainResult<Self> {
+        match name {
+            "Created" => Ok(SessionState::Created),
+            "Active" => Ok(SessionState::Active),
+            "Paused" => Ok(SessionState::Paused),
+            "Ended" => Ok(SessionState::Ended),
+            other => Err(DomainError::validation("state", format!("unknown session state: {}", other))),
+        }
+    }
+}
+
 /// Value Object: Session Metrics
 #[derive(Debug, Clone)]
 pub struct SessionMetrics {
@@ -153,6 +175,12 @@ impl SessionMetrics {
     }
 }
 
+impl Default for SessionMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 /// Domain Service: Session Repository Interface
 pub trait SessionRepository {
     fn find_by_id(&self, id: &SessionId) -> DomainResult<Option<StreamingSession>>;
@@ -161,21 +189,41 @@ pub trait SessionRepository {
     fn find_active_sessions(&self) -> DomainResult<Vec<StreamingSession>>;
 }
 
+impl<R: SessionRepository + ?Sized> SessionRepository for Arc<R> {
+    fn find_by_id(&self, id: &SessionId) -> DomainResult<Option<StreamingSession>> {
+        (**self).find_by_id(id)
+    }
+
+    fn save(&self, session: &StreamingSession) -> DomainResult<()> {
+        (**self).save(session)
+    }
+
+    fn delete(&self, id: &SessionId) -> DomainResult<()> {
+        (**self).delete(id)
+    }
+
+    fn find_active_sessions(&self) -> DomainResult<Vec<StreamingSession>> {
+        (**self).find_active_sessions()
+    }
+}
+
 /// Domain Service: Session Manager
 pub struct SessionManager;
 
 impl SessionManager {
     pub fn create_session(video_id: VideoId, client_info: ClientInfo) -> StreamingSession {
-        let session_id = SessionId::new(format!("session_{}", std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis()));
+        Self::create_session_with_ids(&UuidV7Generator, video_id, client_info)
+    }
+
+    /// Create a session whose id comes from `ids`
+    pub fn create_session_with_ids(ids: &dyn IdGenerator, video_id: VideoId, client_info: ClientInfo) -> StreamingSession {
+        let session_id = SessionId::new(format!("session_{}", ids.next_id()));
         StreamingSession::new(session_id, video_id, client_info)
     }
 
     pub fn validate_session(session: &StreamingSession) -> DomainResult<()> {
         if session.state == SessionState::Ended {
-            return Err(DomainError::InvalidState("Session has ended".to_string()));
+            return Err(DomainError::Conflict("Session has ended".to_string()));
         }
         Ok(())
     }
@@ -1,7 +1,15 @@
 use std::fs::{File};
-use std::io::{BufReader, Read, Seek, SeekFrom};
 use std::path::Path;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+use std::time::SystemTime;
+use bytes::Bytes;
+use futures_core::Stream;
 use crate::domain::common::{DomainResult, DomainError, ContentType, FilePath, ByteRange};
+use crate::domain::conditional::{Validators, file_inode};
+use crate::domain::container::detect_content_type;
+use crate::domain::media::MediaInfo;
 
 /// Entity: Video ID
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
@@ -24,6 +32,9 @@ pub struct VideoMetadata {
     pub content_type: ContentType,
     pub duration: Option<f64>, // in seconds
     pub bitrate: Option<u32>,  // in bits per second
+    pub last_modified: Option<SystemTime>,
+    pub inode: Option<u64>,
+    pub media: Option<MediaInfo>, // filled in by a MediaProbe
 }
 
 impl VideoMetadata {
@@ -33,43 +44,52 @@ impl VideoMetadata {
             content_type,
             duration: None,
             bitrate: None,
+            last_modified: None,
+            inode: None,
+            media: None,
         }
     }
 
+    /// Attach the result of probing the file, taking duration and bitrate from it
+    pub fn with_media_info(mut self, media: MediaInfo) -> Self {
+        self.duration = media.duration;
+        self.bitrate = media.bitrate.map(|bitrate| bitrate.min(u32::MAX as u64) as u32);
+        self.media = Some(media);
+        self
+    }
+
+    /// ETag and Last-Modified for the file version this metadata was read from
+    pub fn validators(&self) -> Validators {
+        Validators::from_file_attributes(self.total_size, self.last_modified, self.inode)
+    }
+
     pub fn from_path(file_path: &FilePath) -> DomainResult<Self> {
         let path = Path::new(file_path.as_str());
         
         if !path.exists() {
-            return Err(DomainError::FileNotFound);
+            return Err(DomainError::not_found("file", file_path.as_str()));
         }
 
         let metadata = std::fs::metadata(file_path.as_str())
-            .map_err(|e| DomainError::IoError(e.to_string()))?;
+            .map_err(|e| DomainError::Io(e.to_string()))?;
 
         let content_type = Self::infer_content_type(file_path.as_str())?;
 
         Ok(VideoMetadata {
             total_size: metadata.len(),
             content_type,
-            duration: None, // Would be extracted by a media analysis service
-            bitrate: None,  // Would be extracted by a media analysis service
+            duration: None, // Filled in by with_media_info
+            bitrate: None,  // Filled in by with_media_info
+            last_modified: metadata.modified().ok(),
+            inode: file_inode(&metadata),
+            media: None,
         })
     }
 
+    /// Sniff the container signature, trusting the extension only when that is inconclusive
     fn infer_content_type(file_path: &str) -> DomainResult<ContentType> {
-        let extension = Path::new(file_path)
-            .extension()
-            .and_then(|ext| ext.to_str())
-            .unwrap_or("");
-
-        let content_type = match extension {
-            "webm" => "video/webm",
-            "mp4" => "video/mp4",
-            "avi" => "video/x-msvideo",
-            "mov" => "video/quicktime",
-            "mkv" => "video/x-matroska",
-            _ => return Err(DomainError::InvalidContentType),
-        };
+        let content_type = detect_content_type(Path::new(file_path))
+            .ok_or_else(|| DomainError::Unsupported(format!("unrecognized video format: {}", file_path)))?;
 
         ContentType::new(content_type.to_string())
     }
@@ -104,6 +124,15 @@ impl Video {
 pub struct VideoInfo {
     pub total_size: u64,
     pub content_type: String,
+    pub last_modified: Option<SystemTime>,
+    pub inode: Option<u64>,
+}
+
+impl VideoInfo {
+    /// ETag and Last-Modified for the file version this info was read from
+    pub fn validators(&self) -> Validators {
+        Validators::from_file_attributes(self.total_size, self.last_modified, self.inode)
+    }
 }
 
 /// Range request information
@@ -114,6 +143,16 @@ pub struct RangeRequest {
     pub total_size: u64,
 }
 
+impl From<ByteRange> for RangeRequest {
+    fn from(range: ByteRange) -> Self {
+        RangeRequest {
+            start: range.start,
+            end: range.end,
+            total_size: range.total_size,
+        }
+    }
+}
+
 /// Video streaming data
 #[derive(Debug)]
 pub struct VideoChunk {
@@ -125,15 +164,42 @@ impl VideoChunk {
     pub fn new(_video_id: VideoId, range: ByteRange, data: Vec<u8>) -> Self {
         VideoChunk {
             data,
-            range: RangeRequest {
-                start: range.start,
-                end: range.end,
-                total_size: range.total_size,
-            },
+            range: range.into(),
         }
     }
 }
 
+/// Value Object: Asynchronous stream of video bytes, produced in bounded buffers
+pub struct VideoByteStream(Pin<Box<dyn Stream<Item = DomainResult<Bytes>> + Send>>);
+
+impl VideoByteStream {
+    pub fn new<S>(stream: S) -> Self
+    where
+        S: Stream<Item = DomainResult<Bytes>> + Send + 'static,
+    {
+        VideoByteStream(Box::pin(stream))
+    }
+
+    /// Stream yielding a single in-memory buffer
+    pub fn from_bytes(bytes: Bytes) -> Self {
+        Self::new(futures_util::stream::once(async move { Ok(bytes) }))
+    }
+}
+
+impl Stream for VideoByteStream {
+    type Item = DomainResult<Bytes>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.0.as_mut().poll_next(cx)
+    }
+}
+
+impl std::fmt::Debug for VideoByteStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("VideoByteStream")
+    }
+}
+
 /// Domain Service: Video Repository Interface
 pub trait VideoRepository {
     fn find_by_id(&self, id: &VideoId) -> DomainResult<Option<Video>>;
@@ -141,58 +207,221 @@ pub trait VideoRepository {
     fn delete(&self, id: &VideoId) -> DomainResult<()>;
 }
 
+impl<R: VideoRepository + ?Sized> VideoRepository for Arc<R> {
+    fn find_by_id(&self, id: &VideoId) -> DomainResult<Option<Video>> {
+        (**self).find_by_id(id)
+    }
+
+    fn save(&self, video: &Video) -> DomainResult<()> {
+        (**self).save(video)
+    }
+
+    fn delete(&self, id: &VideoId) -> DomainResult<()> {
+        (**self).delete(id)
+    }
+}
+
 /// Domain Service: Video Streaming Service
 pub trait VideoStreamingService {
-    fn read_chunk(&self, video: &Video, range: &ByteRange) -> DomainResult<VideoChunk>;
+    /// Lazily stream a range; no I/O happens until the stream is first polled
+    fn open_stream(&self, video: &Video, range: &ByteRange) -> DomainResult<VideoByteStream>;
     fn get_metadata(&self, video: &Video) -> DomainResult<VideoMetadata>;
 }
 
-/// Domain Service: Range Parser
+/// Value Object: One range-spec of a Range header, before it is resolved against a file size
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeSpec {
+    /// `first-last`
+    Bounded { first: u64, last: u64 },
+    /// `first-` (to the end of the file)
+    From { first: u64 },
+    /// `-length` (the last `length` bytes)
+    Suffix { length: u64 },
+}
+
+impl RangeSpec {
+    /// Resolve against a file size; `None` if the spec selects no bytes of the file
+    pub fn resolve(&self, total_size: u64) -> Option<ByteRange> {
+        if total_size == 0 {
+            return None;
+        }
+
+        let (start, end) = match *self {
+            RangeSpec::Bounded { first, last } => (first, last.min(total_size - 1)),
+            RangeSpec::From { first } => (first, total_size - 1),
+            RangeSpec::Suffix { length } if length > 0 => (total_size.saturating_sub(length), total_size - 1),
+            RangeSpec::Suffix { .. } => return None,
+        };
+
+        ByteRange::new(start, end, total_size).ok()
+    }
+}
+
+/// Value Object: Range header resolved against a file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RangeSelection {
+    /// No usable Range header: the whole file is selected
+    Full,
+    /// Satisfiable ranges, coalesced and ordered by start offset
+    Partial(Vec<ByteRange>),
+}
+
+impl RangeSelection {
+    /// Byte ranges to send; a full selection of an empty file has none
+    pub fn into_ranges(self, total_size: u64) -> Vec<ByteRange> {
+        match self {
+            RangeSelection::Full if total_size == 0 => Vec::new(),
+            RangeSelection::Full => vec![ByteRange { start: 0, end: total_size - 1, total_size }],
+            RangeSelection::Partial(ranges) => ranges,
+        }
+    }
+
+    /// Limit every requested range to `max_chunk_size` bytes.
+    /// A full selection is left alone since it is answered with 200, not 206.
+    pub fn capped(self, max_chunk_size: Option<u64>) -> Self {
+        match (self, max_chunk_size) {
+            (RangeSelection::Partial(ranges), Some(max_chunk_size)) => RangeSelection::Partial(
+                ranges.iter().map(|range| range.capped(max_chunk_size)).collect()
+            ),
+            (selection, _) => selection,
+        }
+    }
+}
+
+/// Most ranges one Range header may ask for; longer headers are ignored and the whole file is sent
+pub const MAX_RANGE_SPECS: usize = 64;
+
+/// Domain Service: Range Parser (RFC 9110, section 14)
 pub struct RangeParser;
 
 impl RangeParser {
+    /// Parse the header grammar without looking at the file.
+    /// Returns `None` for range units other than `bytes` and for headers with more than
+    /// `MAX_RANGE_SPECS` ranges, which are ignored (RFC 9110 allows ignoring any Range header).
+    pub fn parse_specs(range_header: &str) -> DomainResult<Option<Vec<RangeSpec>>> {
+        let (unit, range_set) = range_header.trim()
+            .split_once('=')
+            .ok_or(DomainError::InvalidRange)?;
+
+        if !Self::is_token(unit) {
+            return Err(DomainError::InvalidRange);
+        }
+        if !unit.eq_ignore_ascii_case("bytes") {
+            return Ok(None);
+        }
+
+        // range-set = 1#range-spec; empty list elements are allowed but ignored.
+        // Stop counting past the limit, so huge headers cost no parsing
+        let elements: Vec<&str> = range_set.split(',')
+            .map(str::trim)
+            .filter(|spec| !spec.is_empty())
+            .take(MAX_RANGE_SPECS + 1)
+            .collect();
+        if elements.len() > MAX_RANGE_SPECS {
+            return Ok(None);
+        }
+        let specs = elements.into_iter()
+            .map(Self::parse_spec)
+            .collect::<DomainResult<Vec<RangeSpec>>>()?;
+
+        if specs.is_empty() {
+            return Err(DomainError::InvalidRange);
+        }
+        Ok(Some(specs))
+    }
+
+    /// Resolve a Range header against a file size. A header that cannot be parsed is
+    /// ignored and the whole file selected (RFC 9110, section 14.2).
+    /// Fails with `RangeNotSatisfiable` when a valid header selects no bytes.
+    pub fn select(range_header: Option<&str>, total_size: u64) -> DomainResult<RangeSelection> {
+        let specs = match range_header {
+            Some(header) => match Self::parse_specs(header) {
+                Ok(Some(specs)) => specs,
+                Ok(None) | Err(_) => return Ok(RangeSelection::Full),
+            },
+            None => return Ok(RangeSelection::Full),
+        };
+
+        // Unsatisfiable specs are dropped as long as at least one remains
+        let ranges: Vec<ByteRange> = specs.iter()
+            .filter_map(|spec| spec.resolve(total_size))
+            .collect();
+
+        if ranges.is_empty() {
+            return Err(DomainError::RangeNotSatisfiable(total_size));
+        }
+        Ok(RangeSelection::Partial(Self::coalesce(ranges)))
+    }
+
+    /// Parse a single-range header; multi-range headers must go through `parse_ranges`
     pub fn parse_range_header(range_header: Option<&str>, total_size: u64) -> DomainResult<ByteRange> {
-        match range_header {
-            Some(range) => {
-                let parts: Vec<&str> = range.trim_start_matches("bytes=").split('-').collect();
-                let start = parts.get(0).and_then(|s| s.parse::<u64>().ok()).unwrap_or(0);
-                let end = parts.get(1)
-                    .and_then(|s| if s.is_empty() { None } else { Some(s) })
-                    .and_then(|s| s.parse::<u64>().ok())
-                    .unwrap_or(total_size - 1);
-                
-                ByteRange::new(start, end.min(total_size - 1), total_size)
+        let mut ranges = Self::parse_ranges(range_header, total_size)?;
+        match ranges.len() {
+            0 => Err(DomainError::RangeNotSatisfiable(total_size)),
+            1 => Ok(ranges.remove(0)),
+            _ => Err(DomainError::InvalidRange),
+        }
+    }
+
+    /// Parse every range of a (possibly multi-range) header, coalescing overlapping ones
+    pub fn parse_ranges(range_header: Option<&str>, total_size: u64) -> DomainResult<Vec<ByteRange>> {
+        Ok(Self::select(range_header, total_size)?.into_ranges(total_size))
+    }
+
+    /// Merge overlapping or adjacent ranges, ordered by start offset
+    pub fn coalesce(mut ranges: Vec<ByteRange>) -> Vec<ByteRange> {
+        ranges.sort_by_key(|r| r.start);
+
+        let mut merged: Vec<ByteRange> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end.saturating_add(1) => {
+                    last.end = last.end.max(range.end);
+                }
+                _ => merged.push(range),
             }
-            None => ByteRange::new(0, total_size - 1, total_size)
-        }
-    }
-}
-
-/// Parse range header from HTTP request
-pub fn parse_range_header(range_header: Option<&str>, total_size: u64) -> RangeRequest {
-    match range_header {
-        Some(range) => {
-            let parts: Vec<&str> = range.trim_start_matches("bytes=").split('-').collect();
-            let start = parts.get(0).and_then(|s| s.parse::<u64>().ok()).unwrap_or(0);
-            let end = parts.get(1)
-                .and_then(|s| if s.is_empty() { None } else { Some(s) })
-                .and_then(|s| s.parse::<u64>().ok())
-                .unwrap_or(total_size - 1);
-            
-            RangeRequest {
-                start,
-                end: end.min(total_size - 1),
-                total_size,
+        }
+        merged
+    }
+
+    fn parse_spec(spec: &str) -> DomainResult<RangeSpec> {
+        let (first, last) = spec.split_once('-').ok_or(DomainError::InvalidRange)?;
+
+        match (first.is_empty(), last.is_empty()) {
+            (true, true) => Err(DomainError::InvalidRange),
+            (true, false) => Ok(RangeSpec::Suffix { length: Self::parse_position(last)? }),
+            (false, true) => Ok(RangeSpec::From { first: Self::parse_position(first)? }),
+            (false, false) => {
+                let first = Self::parse_position(first)?;
+                let last = Self::parse_position(last)?;
+                if last < first {
+                    return Err(DomainError::InvalidRange);
+                }
+                Ok(RangeSpec::Bounded { first, last })
             }
         }
-        None => RangeRequest {
-            start: 0,
-            end: total_size - 1,
-            total_size,
+    }
+
+    /// Parse a run of ASCII digits; values too large for u64 saturate
+    fn parse_position(digits: &str) -> DomainResult<u64> {
+        if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+            return Err(DomainError::InvalidRange);
         }
+        Ok(digits.parse::<u64>().unwrap_or(u64::MAX))
+    }
+
+    fn is_token(value: &str) -> bool {
+        !value.is_empty() && value.bytes().all(|b| {
+            b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b)
+        })
     }
 }
 
+/// Parse a single-range header from HTTP request
+pub fn parse_range_header(range_header: Option<&str>, total_size: u64) -> DomainResult<RangeRequest> {
+    RangeParser::parse_range_header(range_header, total_size).map(RangeRequest::from)
+}
+
 /// Get video file metadata
 pub fn get_video_metadata(file_path: &str) -> Result<VideoInfo, std::io::Error> {
     let file = File::open(file_path)?;
@@ -201,41 +430,16 @@ pub fn get_video_metadata(file_path: &str) -> Result<VideoInfo, std::io::Error>
     Ok(VideoInfo {
         total_size: metadata.len(),
         content_type: infer_content_type(file_path),
+        last_modified: metadata.modified().ok(),
+        inode: file_inode(&metadata),
     })
 }
 
-/// Read video chunk based on range request
-pub fn read_video_chunk(file_path: &str, range: &RangeRequest) -> Result<VideoChunk, std::io::Error> {
-    let file = File::open(file_path)?;
-    let mut reader = BufReader::new(file);
-    
-    // Seek to start position
-    reader.seek(SeekFrom::Start(range.start))?;
-    
-    // Calculate chunk size
-    let chunk_size = (range.end - range.start + 1) as usize;
-    
-    // Read the chunk
-    let mut buffer = vec![0u8; chunk_size];
-    let bytes_read = reader.take(chunk_size as u64).read(&mut buffer)?;
-    buffer.truncate(bytes_read);
-    
-    Ok(VideoChunk {
-        data: buffer,
-        range: range.clone(),
-    })
-}
-
-/// Infer content type from file extension
+/// Infer content type from the file signature, then the extension
 fn infer_content_type(file_path: &str) -> String {
-    match Path::new(file_path).extension().and_then(|ext| ext.to_str()) {
-        Some("webm") => "video/webm".to_string(),
-        Some("mp4") => "video/mp4".to_string(),
-        Some("avi") => "video/x-msvideo".to_string(),
-        Some("mov") => "video/quicktime".to_string(),
-        Some("mkv") => "video/x-matroska".to_string(),
-        _ => "application/octet-stream".to_string(),
-    }
+    detect_content_type(Path::new(file_path))
+        .unwrap_or("application/octet-stream")
+        .to_string()
 }
 
 /// Validate range request
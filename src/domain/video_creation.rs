@@ -1,6 +1,11 @@
 use std::path::Path;
-use crate::domain::common::{DomainResult, DomainError, FilePath};
+use std::sync::Arc;
+use crate::domain::common::{DomainResult, DomainError, FieldError, FilePath, IdGenerator, UuidV7Generator};
 use crate::domain::video::VideoId;
+use serde::{Deserialize, Serialize};
+
+/// Frame rate of created videos; one frame per second keeps image timing exact
+pub const OUTPUT_FRAME_RATE: u32 = 1;
 
 /// Value Object: Image specification
 #[derive(Debug, Clone)]
@@ -12,8 +17,13 @@ pub struct ImageSpec {
 
 impl ImageSpec {
     pub fn new(width: u32, height: u32, duration_seconds: u32) -> DomainResult<Self> {
-        if width == 0 || height == 0 || duration_seconds <= 0 {
-            return Err(DomainError::InvalidRange);
+        let errors: Vec<FieldError> = [("width", width), ("height", height), ("duration_seconds", duration_seconds)]
+            .into_iter()
+            .filter(|&(_, value)| value == 0)
+            .map(|(field, _)| FieldError::new(field, "must be positive"))
+            .collect();
+        if !errors.is_empty() {
+            return Err(DomainError::Validation(errors));
         }
         
         Ok(ImageSpec {
@@ -34,12 +44,160 @@ impl Default for ImageSpec {
     }
 }
 
+/// Value Object: Container a created video is written in
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum VideoContainer {
+    Mp4,
+    WebM,
+    Matroska,
+    QuickTime,
+}
+
+impl VideoContainer {
+    /// FFmpeg muxer name, as passed to `-f`
+    pub fn muxer(&self) -> &'static str {
+        match self {
+            VideoContainer::Mp4 => "mp4",
+            VideoContainer::WebM => "webm",
+            VideoContainer::Matroska => "matroska",
+            VideoContainer::QuickTime => "mov",
+        }
+    }
+
+    pub fn extensions(&self) -> &'static [&'static str] {
+        match self {
+            VideoContainer::Mp4 => &["mp4", "m4v"],
+            VideoContainer::WebM => &["webm"],
+            VideoContainer::Matroska => &["mkv"],
+            VideoContainer::QuickTime => &["mov"],
+        }
+    }
+
+    /// Whether an output file may be written in this container; paths without an extension always may
+    pub fn accepts_path(&self, path: &str) -> bool {
+        match Path::new(path).extension().and_then(|ext| ext.to_str()) {
+            Some(extension) => self.extensions().iter().any(|known| known.eq_ignore_ascii_case(extension)),
+            None => true,
+        }
+    }
+
+    /// Whether video from the FFmpeg encoder `codec` can be written in this container.
+    /// Encoders of other formats than H.264/HEVC, VP8/VP9/AV1 and ProRes are let through.
+    pub fn accepts_codec(&self, codec: &str) -> bool {
+        let codec = codec.to_ascii_lowercase();
+        let family = |names: &[&str]| names.iter().any(|name| codec.contains(name));
+
+        if family(&["264", "265", "hevc"]) {
+            matches!(self, VideoContainer::Mp4 | VideoContainer::Matroska | VideoContainer::QuickTime)
+        } else if family(&["vp8", "vp9", "vpx", "av1"]) {
+            matches!(self, VideoContainer::WebM | VideoContainer::Matroska | VideoContainer::Mp4)
+        } else if family(&["prores"]) {
+            matches!(self, VideoContainer::QuickTime | VideoContainer::Matroska)
+        } else {
+            true
+        }
+    }
+}
+
+/// Value Object: Rate control of an encoder
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EncoderQuality {
+    /// Constant quality; lower is better
+    Crf(u32),
+    /// Average bitrate in kbit/s
+    BitrateKbps(u32),
+}
+
+/// Names of the built-in encoder profiles
+pub const ENCODER_PROFILE_NAMES: &[&str] = &["h264-web", "vp9-webm", "av1-archive", "prores-proxy"];
+
+/// Value Object: Named encoder settings a video is created with
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EncoderProfile {
+    pub name: String,
+    pub codec: String,
+    /// Codec profile (`-profile:v`), e.g. the ProRes flavour
+    pub codec_profile: Option<String>,
+    pub pixel_format: String,
+    /// `None` leaves rate control to the codec
+    pub quality: Option<EncoderQuality>,
+    pub preset: Option<String>,
+    /// Frames between keyframes (seconds, at `OUTPUT_FRAME_RATE`); `None` for the codec default
+    pub gop_size: Option<u32>,
+    pub container: VideoContainer,
+}
+
+impl EncoderProfile {
+    /// The built-in profile called `name`, if there is one
+    pub fn builtin(name: &str) -> Option<Self> {
+        let profile = |codec: &str, pixel_format: &str, quality, preset: Option<&str>, gop_size, container| EncoderProfile {
+            name: name.to_string(),
+            codec: codec.to_string(),
+            codec_profile: None,
+            pixel_format: pixel_format.to_string(),
+            quality,
+            preset: preset.map(str::to_string),
+            gop_size,
+            container,
+        };
+
+        match name {
+            // Small files that start playing quickly in any browser
+            "h264-web" => Some(profile("libx264", "yuv420p", Some(EncoderQuality::Crf(23)), Some("veryfast"), Some(2), VideoContainer::Mp4)),
+            "vp9-webm" => Some(profile("libvpx-vp9", "yuv420p", Some(EncoderQuality::Crf(33)), None, Some(2), VideoContainer::WebM)),
+            // Slow, compact, 10-bit
+            "av1-archive" => Some(profile("libsvtav1", "yuv420p10le", Some(EncoderQuality::Crf(28)), Some("4"), Some(10), VideoContainer::Matroska)),
+            // Intra-only editing proxies
+            "prores-proxy" => Some(EncoderProfile {
+                codec_profile: Some("0".to_string()),
+                ..profile("prores_ks", "yuv422p10le", None, None, None, VideoContainer::QuickTime)
+            }),
+            _ => None,
+        }
+    }
+
+    /// The built-in profile called `name`, or a validation error naming the known ones
+    pub fn named(name: &str) -> DomainResult<Self> {
+        Self::builtin(name).ok_or_else(|| DomainError::validation(
+            "profile",
+            format!("unknown encoder profile: {} (expected one of {})", name, ENCODER_PROFILE_NAMES.join(", ")),
+        ))
+    }
+
+    /// Switch to another encoder; the preset and codec profile only mean something to the
+    /// profile's own encoder, so they are dropped when the encoder changes
+    pub fn with_codec(mut self, codec: impl Into<String>) -> Self {
+        let codec = codec.into();
+        if codec != self.codec {
+            self.preset = None;
+            self.codec_profile = None;
+        }
+        self.codec = codec;
+        self
+    }
+
+    pub fn with_pixel_format(mut self, pixel_format: impl Into<String>) -> Self {
+        self.pixel_format = pixel_format.into();
+        self
+    }
+}
+
+impl Default for EncoderProfile {
+    fn default() -> Self {
+        Self::builtin("h264-web").expect("h264-web is a built-in profile")
+    }
+}
+
 /// Value Object: Video creation request
 #[derive(Debug, Clone)]
 pub struct VideoCreationRequest {
     pub image_paths: Vec<FilePath>,
+    pub image_durations: Vec<Option<u32>>, // per-image overrides of image_spec.duration_seconds
     pub output_path: FilePath,
     pub image_spec: ImageSpec,
+    pub encoder_profile: EncoderProfile,
     pub video_id: VideoId,
 }
 
@@ -51,35 +209,69 @@ impl VideoCreationRequest {
         video_id: VideoId,
     ) -> DomainResult<Self> {
         if image_paths.is_empty() {
-            return Err(DomainError::InvalidState("No images provided".to_string()));
+            return Err(DomainError::validation("images", "must contain at least one image"));
         }
 
         // Validate all image paths exist
         for image_path in &image_paths {
             if !Path::new(image_path.as_str()).exists() {
-                return Err(DomainError::FileNotFound);
+                return Err(DomainError::not_found("image", image_path.as_str()));
             }
         }
 
         Ok(VideoCreationRequest {
+            image_durations: vec![None; image_paths.len()],
             image_paths,
             output_path,
             image_spec,
+            encoder_profile: EncoderProfile::default(),
             video_id,
         })
     }
 
+    /// Encode with `encoder_profile` instead of the default `h264-web`
+    pub fn with_encoder_profile(mut self, encoder_profile: EncoderProfile) -> Self {
+        self.encoder_profile = encoder_profile;
+        self
+    }
+
+    /// Set how long each image is shown; `None` keeps the spec's duration
+    pub fn with_image_durations(mut self, image_durations: Vec<Option<u32>>) -> DomainResult<Self> {
+        if image_durations.len() != self.image_paths.len() {
+            return Err(DomainError::validation("image_durations", "must have one entry per image"));
+        }
+        if image_durations.contains(&Some(0)) {
+            return Err(DomainError::validation("image_durations", "must be positive"));
+        }
+
+        self.image_durations = image_durations;
+        Ok(self)
+    }
+
+    /// Seconds the image at `index` is shown for
+    pub fn image_duration(&self, index: usize) -> u32 {
+        self.image_durations.get(index)
+            .copied()
+            .flatten()
+            .unwrap_or(self.image_spec.duration_seconds)
+    }
+
     pub fn total_duration(&self) -> u32 {
-        self.image_paths.len() as u32 * self.image_spec.duration_seconds
+        (0..self.image_paths.len()).map(|index| self.image_duration(index)).sum()
     }
 
     pub fn frame_count(&self) -> usize {
         self.image_paths.len()
     }
+
+    /// Frames in the rendered video, which shows each image for its duration at `OUTPUT_FRAME_RATE`
+    pub fn output_frame_count(&self) -> usize {
+        (self.total_duration() * OUTPUT_FRAME_RATE) as usize
+    }
 }
 
 /// Value Object: Video creation progress
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct VideoCreationProgress {
     pub current_frame: usize,
     pub total_frames: usize,
@@ -118,6 +310,7 @@ pub struct VideoCreationJob {
     pub created_at: std::time::SystemTime,
     pub completed_at: Option<std::time::SystemTime>,
     pub error_message: Option<String>,
+    pub attempts: u32, // times the job has been started
 }
 
 impl VideoCreationJob {
@@ -130,6 +323,7 @@ impl VideoCreationJob {
             created_at: std::time::SystemTime::now(),
             completed_at: None,
             error_message: None,
+            attempts: 0,
         }
     }
 
@@ -137,20 +331,50 @@ impl VideoCreationJob {
         match self.status {
             VideoCreationStatus::Pending => {
                 self.status = VideoCreationStatus::InProgress;
-                self.progress = Some(VideoCreationProgress::new(0, self.request.frame_count()));
+                self.attempts += 1;
+                self.progress = Some(VideoCreationProgress::new(0, self.request.output_frame_count()));
                 Ok(())
             }
-            _ => Err(DomainError::InvalidState("Job already started".to_string())),
+            _ => Err(DomainError::Conflict("Job already started".to_string())),
         }
     }
 
     pub fn update_progress(&mut self, current_frame: usize) -> DomainResult<()> {
         match self.status {
             VideoCreationStatus::InProgress => {
-                self.progress = Some(VideoCreationProgress::new(current_frame, self.request.frame_count()));
+                self.progress = Some(VideoCreationProgress::new(current_frame, self.request.output_frame_count()));
+                Ok(())
+            }
+            _ => Err(DomainError::Conflict("Job not in progress".to_string())),
+        }
+    }
+
+    /// Record how far the encoder got: `encoded_seconds` of output video so far,
+    /// with the remaining time estimated from throughput when known
+    pub fn record_encoding_progress(
+        &mut self,
+        current_frame: usize,
+        encoded_seconds: f32,
+        estimated_time_remaining_seconds: Option<f32>,
+    ) -> DomainResult<()> {
+        match self.status {
+            VideoCreationStatus::InProgress => {
+                let total_seconds = self.request.total_duration() as f32;
+                let percentage = if total_seconds > 0.0 {
+                    (encoded_seconds / total_seconds * 100.0).clamp(0.0, 100.0)
+                } else {
+                    0.0
+                };
+
+                self.progress = Some(VideoCreationProgress {
+                    current_frame,
+                    total_frames: self.request.output_frame_count(),
+                    percentage,
+                    estimated_time_remaining_seconds,
+                });
                 Ok(())
             }
-            _ => Err(DomainError::InvalidState("Job not in progress".to_string())),
+            _ => Err(DomainError::Conflict("Job not in progress".to_string())),
         }
     }
 
@@ -159,18 +383,49 @@ impl VideoCreationJob {
             VideoCreationStatus::InProgress => {
                 self.status = VideoCreationStatus::Completed;
                 self.completed_at = Some(std::time::SystemTime::now());
-                self.progress = Some(VideoCreationProgress::new(self.request.frame_count(), self.request.frame_count()));
+                let total_frames = self.request.output_frame_count();
+                self.progress = Some(VideoCreationProgress::new(total_frames, total_frames));
+                Ok(())
+            }
+            _ => Err(DomainError::Conflict("Job not in progress".to_string())),
+        }
+    }
+
+    /// Put an interrupted job back in the queue; its next start counts as another attempt
+    pub fn requeue(&mut self) -> DomainResult<()> {
+        match self.status {
+            VideoCreationStatus::InProgress => {
+                self.status = VideoCreationStatus::Pending;
+                self.progress = None;
                 Ok(())
             }
-            _ => Err(DomainError::InvalidState("Job not in progress".to_string())),
+            _ => Err(DomainError::Conflict("Job not in progress".to_string())),
         }
     }
 
+    /// Stop a job that has not finished yet
+    pub fn cancel(&mut self) -> DomainResult<()> {
+        match self.status {
+            VideoCreationStatus::Pending | VideoCreationStatus::InProgress => {
+                self.status = VideoCreationStatus::Cancelled;
+                self.completed_at = Some(std::time::SystemTime::now());
+                Ok(())
+            }
+            _ => Err(DomainError::Conflict("Job already finished".to_string())),
+        }
+    }
+
+    /// Give up on a job that has not finished yet
     pub fn fail(&mut self, error_message: String) -> DomainResult<()> {
-        self.status = VideoCreationStatus::Failed;
-        self.error_message = Some(error_message);
-        self.completed_at = Some(std::time::SystemTime::now());
-        Ok(())
+        match self.status {
+            VideoCreationStatus::Pending | VideoCreationStatus::InProgress => {
+                self.status = VideoCreationStatus::Failed;
+                self.error_message = Some(error_message);
+                self.completed_at = Some(std::time::SystemTime::now());
+                Ok(())
+            }
+            _ => Err(DomainError::Conflict("Job already finished".to_string())),
+        }
     }
 
     pub fn duration(&self) -> Option<std::time::Duration> {
@@ -179,7 +434,7 @@ impl VideoCreationJob {
 }
 
 /// Entity: Video Creation Job ID
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct VideoCreationJobId(String);
 
 impl VideoCreationJobId {
@@ -187,11 +442,13 @@ impl VideoCreationJobId {
         VideoCreationJobId(id)
     }
 
+    /// A fresh id from the default (UUIDv7) generator
     pub fn generate() -> Self {
-        VideoCreationJobId(format!("job_{}", std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_millis()))
+        Self::generate_with(&UuidV7Generator)
+    }
+
+    pub fn generate_with(ids: &dyn IdGenerator) -> Self {
+        VideoCreationJobId(format!("job_{}", ids.next_id()))
     }
 
     pub fn as_str(&self) -> &str {
@@ -206,20 +463,216 @@ pub enum VideoCreationStatus {
     InProgress,
     Completed,
     Failed,
+    Cancelled,
+}
+
+impl VideoCreationStatus {
+    /// Stable name, as shown in API responses and stored by persistent repositories
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VideoCreationStatus::Pending => "pending",
+            VideoCreationStatus::InProgress => "in_progress",
+            VideoCreationStatus::Completed => "completed",
+            VideoCreationStatus::Failed => "failed",
+            VideoCreationStatus::Cancelled => "cancelled",
+        }
+    }
+
+    /// Completed, failed and cancelled jobs never change again
+    pub fn is_terminal(&self) -> bool {
+        matches!(self, VideoCreationStatus::Completed | VideoCreationStatus::Failed | VideoCreationStatus::Cancelled)
+    }
+
+    /// Parse a status name, ignoring case and `_`/`-` separators (`failed`, `in_progress`, `InProgress`)
+    pub fn parse(name: &str) -> DomainResult<Self> {
+        let normalized: String = name.chars()
+            .filter(|c| *c != '_' && *c != '-')
+            .collect::<String>()
+            .to_ascii_lowercase();
+        match normalized.as_str() {
+            "pending" => Ok(VideoCreationStatus::Pending),
+            "inprogress" => Ok(VideoCreationStatus::InProgress),
+            "completed" => Ok(VideoCreationStatus::Completed),
+            "failed" => Ok(VideoCreationStatus::Failed),
+            "cancelled" => Ok(VideoCreationStatus::Cancelled),
+            _ => Err(DomainError::validation("status", format!("unknown job status: {}", name))),
+        }
+    }
+}
+
+/// Value Object: Kind of change in a job's lifecycle, as reported to subscribers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VideoCreationEventKind {
+    Started,
+    Progress,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl VideoCreationEventKind {
+    /// Event name, as sent in `event:` lines of a job's event stream
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            VideoCreationEventKind::Started => "started",
+            VideoCreationEventKind::Progress => "progress",
+            VideoCreationEventKind::Completed => "completed",
+            VideoCreationEventKind::Failed => "failed",
+            VideoCreationEventKind::Cancelled => "cancelled",
+        }
+    }
+
+    /// No events follow a terminal one
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            VideoCreationEventKind::Completed | VideoCreationEventKind::Failed | VideoCreationEventKind::Cancelled
+        )
+    }
+
+    /// The event that took a job from `previous` (the state last reported, if any) to `current`;
+    /// `None` when nothing subscribers care about changed
+    pub fn between(previous: Option<&VideoCreationJob>, current: &VideoCreationJob) -> Option<Self> {
+        let status_changed = !matches!(previous, Some(previous) if previous.status == current.status);
+
+        match current.status {
+            VideoCreationStatus::Pending => None,
+            VideoCreationStatus::InProgress => match previous {
+                // A re-queued job starting again is a new start
+                Some(previous) if !status_changed && previous.attempts == current.attempts => {
+                    (previous.progress != current.progress).then_some(VideoCreationEventKind::Progress)
+                }
+                _ => Some(VideoCreationEventKind::Started),
+            },
+            VideoCreationStatus::Completed => status_changed.then_some(VideoCreationEventKind::Completed),
+            VideoCreationStatus::Failed => status_changed.then_some(VideoCreationEventKind::Failed),
+            VideoCreationStatus::Cancelled => status_changed.then_some(VideoCreationEventKind::Cancelled),
+        }
+    }
 }
 
 /// Domain Service: Video Creator Interface
 pub trait VideoCreator {
-    fn create_video(&self, request: &VideoCreationRequest) -> DomainResult<VideoCreationJob>;
+    /// Run a job that was created (and stored) by the caller; the job keeps its id throughout
+    fn create_video(&self, job: VideoCreationJob) -> DomainResult<VideoCreationJob>;
     fn get_job_status(&self, job_id: &VideoCreationJobId) -> DomainResult<Option<VideoCreationJob>>;
 }
 
+/// Value Object: Position in the job list, which is ordered by creation time and then id
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct VideoCreationJobCursor {
+    pub created_at_millis: u64,
+    pub job_id: VideoCreationJobId,
+}
+
+impl VideoCreationJobCursor {
+    /// Cursor pointing at `job`; the next page starts after it
+    pub fn for_job(job: &VideoCreationJob) -> Self {
+        Self {
+            created_at_millis: unix_millis(job.created_at),
+            job_id: job.id.clone(),
+        }
+    }
+
+    /// Opaque form handed to API clients
+    pub fn encode(&self) -> String {
+        format!("{}.{}", self.created_at_millis, self.job_id.as_str())
+    }
+
+    pub fn parse(value: &str) -> DomainResult<Self> {
+        value.split_once('.')
+            .and_then(|(millis, job_id)| {
+                let created_at_millis = millis.parse().ok()?;
+                (!job_id.is_empty()).then(|| Self {
+                    created_at_millis,
+                    job_id: VideoCreationJobId::new(job_id.to_string()),
+                })
+            })
+            .ok_or_else(|| DomainError::validation("cursor", "is not a cursor returned by this API"))
+    }
+}
+
+/// Value Object: Filter and page of a job listing. Jobs come oldest first;
+/// timestamps are compared at millisecond precision, as persistent repositories store them.
+#[derive(Debug, Clone)]
+pub struct VideoCreationJobQuery {
+    pub status: Option<VideoCreationStatus>,
+    pub video_id: Option<VideoId>,
+    /// Only jobs created at or after this time
+    pub since: Option<std::time::SystemTime>,
+    /// Only jobs after this position, i.e. the page after the one it ended
+    pub after: Option<VideoCreationJobCursor>,
+    pub limit: usize,
+}
+
+impl VideoCreationJobQuery {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            status: None,
+            video_id: None,
+            since: None,
+            after: None,
+            limit,
+        }
+    }
+
+    pub fn matches(&self, job: &VideoCreationJob) -> bool {
+        self.status.as_ref().is_none_or(|status| *status == job.status)
+            && self.video_id.as_ref().is_none_or(|video_id| video_id.as_str() == job.request.video_id.as_str())
+            && self.since.is_none_or(|since| unix_millis(job.created_at) >= unix_millis(since))
+            && self.after.as_ref().is_none_or(|after| VideoCreationJobCursor::for_job(job) > *after)
+    }
+}
+
+/// Milliseconds since the Unix epoch; earlier times count as the epoch
+pub fn unix_millis(time: std::time::SystemTime) -> u64 {
+    time.duration_since(std::time::UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as u64)
+        .unwrap_or(0)
+}
+
+/// Whole seconds since the Unix epoch, the format API clients send and receive
+pub fn unix_seconds(time: std::time::SystemTime) -> u64 {
+    unix_millis(time) / 1000
+}
+
 /// Domain Service: Video Creation Repository Interface
 pub trait VideoCreationRepository {
     fn save_job(&self, job: &VideoCreationJob) -> DomainResult<()>;
     fn find_job_by_id(&self, id: &VideoCreationJobId) -> DomainResult<Option<VideoCreationJob>>;
     fn find_jobs_by_status(&self, status: &VideoCreationStatus) -> DomainResult<Vec<VideoCreationJob>>;
+    /// Jobs matching the query, ordered by creation time then id, at most `query.limit` of them
+    fn query_jobs(&self, query: &VideoCreationJobQuery) -> DomainResult<Vec<VideoCreationJob>>;
     fn update_job(&self, job: &VideoCreationJob) -> DomainResult<()>;
+    /// Store the job only if the stored one has not finished yet, as a single atomic step.
+    /// Returns whether it was written; a missing job is not written either.
+    fn update_job_unless_finished(&self, job: &VideoCreationJob) -> DomainResult<bool>;
+}
+
+impl<R: VideoCreationRepository + ?Sized> VideoCreationRepository for Arc<R> {
+    fn save_job(&self, job: &VideoCreationJob) -> DomainResult<()> {
+        (**self).save_job(job)
+    }
+
+    fn find_job_by_id(&self, id: &VideoCreationJobId) -> DomainResult<Option<VideoCreationJob>> {
+        (**self).find_job_by_id(id)
+    }
+
+    fn find_jobs_by_status(&self, status: &VideoCreationStatus) -> DomainResult<Vec<VideoCreationJob>> {
+        (**self).find_jobs_by_status(status)
+    }
+
+    fn query_jobs(&self, query: &VideoCreationJobQuery) -> DomainResult<Vec<VideoCreationJob>> {
+        (**self).query_jobs(query)
+    }
+
+    fn update_job(&self, job: &VideoCreationJob) -> DomainResult<()> {
+        (**self).update_job(job)
+    }
+
+    fn update_job_unless_finished(&self, job: &VideoCreationJob) -> DomainResult<bool> {
+        (**self).update_job_unless_finished(job)
+    }
 }
 
 /// Domain Service: Video Creation Manager
@@ -231,6 +684,31 @@ impl VideoCreationManager {
         output_path: String,
         video_id: VideoId,
         image_spec: Option<ImageSpec>,
+    ) -> DomainResult<VideoCreationJob> {
+        let image_durations = vec![None; image_paths.len()];
+        Self::create_job_with_durations(image_paths, image_durations, output_path, video_id, image_spec)
+    }
+
+    /// Create a job where each image may override the spec's duration
+    pub fn create_job_with_durations(
+        image_paths: Vec<String>,
+        image_durations: Vec<Option<u32>>,
+        output_path: String,
+        video_id: VideoId,
+        image_spec: Option<ImageSpec>,
+    ) -> DomainResult<VideoCreationJob> {
+        Self::create_job_with_ids(&UuidV7Generator, image_paths, image_durations, output_path, video_id, image_spec, None)
+    }
+
+    /// Create a job whose id comes from `ids`, encoded with `encoder_profile` (`h264-web` when `None`)
+    pub fn create_job_with_ids(
+        ids: &dyn IdGenerator,
+        image_paths: Vec<String>,
+        image_durations: Vec<Option<u32>>,
+        output_path: String,
+        video_id: VideoId,
+        image_spec: Option<ImageSpec>,
+        encoder_profile: Option<EncoderProfile>,
     ) -> DomainResult<VideoCreationJob> {
         // Convert strings to FilePath value objects
         let image_file_paths: Vec<FilePath> = image_paths
@@ -246,9 +724,11 @@ impl VideoCreationManager {
             output_file_path,
             spec,
             video_id,
-        )?;
+        )?
+        .with_image_durations(image_durations)?
+        .with_encoder_profile(encoder_profile.unwrap_or_default());
 
-        let job_id = VideoCreationJobId::generate();
+        let job_id = VideoCreationJobId::generate_with(ids);
         Ok(VideoCreationJob::new(job_id, request))
     }
 
@@ -257,17 +737,17 @@ impl VideoCreationManager {
             let file_path = Path::new(path.as_str());
             
             if !file_path.exists() {
-                return Err(DomainError::FileNotFound);
+                return Err(DomainError::not_found("image", path.as_str()));
             }
 
             // Check if it's a valid image extension
             if let Some(extension) = file_path.extension().and_then(|ext| ext.to_str()) {
                 match extension.to_lowercase().as_str() {
                     "jpg" | "jpeg" | "png" | "bmp" | "tiff" | "webp" => continue,
-                    _ => return Err(DomainError::InvalidContentType),
+                    _ => return Err(DomainError::Unsupported(format!("image format: {}", path.as_str()))),
                 }
             } else {
-                return Err(DomainError::InvalidContentType);
+                return Err(DomainError::Unsupported(format!("image format: {}", path.as_str())));
             }
         }
         Ok(())
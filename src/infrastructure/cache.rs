@@ -0,0 +1,209 @@
+// Infrastructure layer in-process caches
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use bytes::Bytes;
+
+/// Size of the aligned file blocks held by the block cache
+pub const DEFAULT_CACHE_BLOCK_SIZE: u64 = 64 * 1024;
+
+/// Key of one aligned block of a specific file version.
+/// The ETag is part of the key, so blocks of a replaced file are never served and simply age out.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BlockKey {
+    pub video_id: String,
+    pub etag: String,
+    pub index: u64,
+}
+
+impl BlockKey {
+    pub fn new(video_id: &str, etag: &str, index: u64) -> Self {
+        Self {
+            video_id: video_id.to_string(),
+            etag: etag.to_string(),
+            index,
+        }
+    }
+}
+
+/// Snapshot of the block cache counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct BlockCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    pub blocks: usize,
+    pub bytes: u64,
+    pub capacity: u64,
+}
+
+impl BlockCacheStats {
+    /// Fraction of lookups served from memory
+    pub fn hit_ratio(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+}
+
+struct CachedBlock {
+    data: Bytes,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct LruState {
+    blocks: HashMap<BlockKey, CachedBlock>,
+    // Recency order: smallest tick is the least recently used block
+    recency: BTreeMap<u64, BlockKey>,
+    tick: u64,
+    bytes: u64,
+    // Keys that missed once while the cache was full, keyed to the tick of that miss
+    candidates: HashMap<BlockKey, u64>,
+    candidate_order: BTreeMap<u64, BlockKey>,
+}
+
+impl LruState {
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    /// Remember a key that was refused admission; returns whether it had been refused before
+    fn seen_before(&mut self, key: &BlockKey, limit: usize) -> bool {
+        if let Some(tick) = self.candidates.remove(key) {
+            self.candidate_order.remove(&tick);
+            return true;
+        }
+
+        let tick = self.next_tick();
+        self.candidates.insert(key.clone(), tick);
+        self.candidate_order.insert(tick, key.clone());
+        while self.candidate_order.len() > limit {
+            if let Some((_, oldest)) = self.candidate_order.pop_first() {
+                self.candidates.remove(&oldest);
+            }
+        }
+        false
+    }
+}
+
+#[derive(Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+/// LRU cache of aligned file blocks, bounded by a memory budget.
+/// Cloning is cheap and every clone shares the same blocks and counters,
+/// so one instance can be handed to all actix workers.
+#[derive(Clone)]
+pub struct BlockCache {
+    block_size: u64,
+    capacity: u64,
+    state: Arc<Mutex<LruState>>,
+    counters: Arc<Counters>,
+}
+
+impl BlockCache {
+    /// Create a cache holding at most `capacity` bytes of block data; 0 disables caching
+    pub fn new(capacity: u64) -> Self {
+        Self::with_block_size(capacity, DEFAULT_CACHE_BLOCK_SIZE)
+    }
+
+    pub fn with_block_size(capacity: u64, block_size: u64) -> Self {
+        Self {
+            block_size: block_size.max(1),
+            capacity,
+            state: Arc::new(Mutex::new(LruState::default())),
+            counters: Arc::new(Counters::default()),
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.capacity >= self.block_size
+    }
+
+    pub fn block_size(&self) -> u64 {
+        self.block_size
+    }
+
+    /// Look up a block, marking it as most recently used
+    pub fn get(&self, key: &BlockKey) -> Option<Bytes> {
+        let mut state = self.state.lock().unwrap();
+        let tick = state.next_tick();
+
+        let found = match state.blocks.get_mut(key) {
+            Some(block) => {
+                let previous = std::mem::replace(&mut block.last_used, tick);
+                Some((previous, block.data.clone()))
+            }
+            None => None,
+        };
+
+        match found {
+            Some((previous, data)) => {
+                state.recency.remove(&previous);
+                state.recency.insert(tick, key.clone());
+                self.counters.hits.fetch_add(1, Ordering::Relaxed);
+                Some(data)
+            }
+            None => {
+                self.counters.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    /// Store a block, evicting the least recently used blocks to stay within the budget.
+    /// Once the cache is full a new block is only admitted on its second miss,
+    /// so a single sequential read of a large file cannot push out the hot set.
+    pub fn insert(&self, key: BlockKey, data: Bytes) {
+        let size = data.len() as u64;
+        if size > self.capacity {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if let Some(previous) = state.blocks.remove(&key) {
+            state.recency.remove(&previous.last_used);
+            state.bytes -= previous.data.len() as u64;
+        } else if state.bytes + size > self.capacity {
+            let limit = (self.capacity / self.block_size).max(1) as usize;
+            if !state.seen_before(&key, limit) {
+                return;
+            }
+        }
+
+        while state.bytes + size > self.capacity {
+            let Some((_, oldest)) = state.recency.pop_first() else {
+                break;
+            };
+            if let Some(evicted) = state.blocks.remove(&oldest) {
+                state.bytes -= evicted.data.len() as u64;
+                self.counters.evictions.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+
+        let tick = state.next_tick();
+        state.recency.insert(tick, key.clone());
+        state.blocks.insert(key, CachedBlock { data, last_used: tick });
+        state.bytes += size;
+    }
+
+    pub fn stats(&self) -> BlockCacheStats {
+        let state = self.state.lock().unwrap();
+        BlockCacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+            evictions: self.counters.evictions.load(Ordering::Relaxed),
+            blocks: state.blocks.len(),
+            bytes: state.bytes,
+            capacity: self.capacity,
+        }
+    }
+}
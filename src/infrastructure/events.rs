@@ -0,0 +1,195 @@
+// Infrastructure layer job event publishing
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tokio::sync::futures::Notified;
+use tokio::sync::Notify;
+use crate::domain::video_creation::{
+    VideoCreationEventKind, VideoCreationJob, VideoCreationJobId, VideoCreationJobQuery, VideoCreationRepository,
+    VideoCreationStatus,
+};
+use crate::domain::common::DomainResult;
+use crate::infrastructure::repositories::SharedVideoCreationRepository;
+
+/// How long a finished job's events are kept for reconnecting subscribers
+pub const DEFAULT_EVENT_RETENTION: Duration = Duration::from_secs(10 * 60);
+
+/// One change in a job's lifecycle, with the job as it was after the change
+#[derive(Debug, Clone)]
+pub struct JobEvent {
+    /// Increases with every event recorded, across all jobs. Ids start from the
+    /// clock, so ids handed out before a restart are lower than any issued after it.
+    pub id: u64,
+    pub kind: VideoCreationEventKind,
+    pub job: VideoCreationJob,
+}
+
+/// In-memory log of the events of every job, for event stream subscribers.
+/// Consecutive progress events replace each other, so a job keeps only a handful
+/// of events and a reconnecting client still catches up on the latest progress.
+/// A finished job's events are dropped once the retention period has passed.
+#[derive(Clone)]
+pub struct JobEventLog {
+    state: Arc<Mutex<JobEventLogState>>,
+    changed: Arc<Notify>,
+    retention: Duration,
+}
+
+struct JobEventLogState {
+    last_id: u64,
+    jobs: HashMap<VideoCreationJobId, JobEvents>,
+}
+
+/// Events recorded for one job
+struct JobEvents {
+    first_id: u64,
+    events: Vec<JobEvent>,
+    finished_at: Option<Instant>,
+}
+
+impl JobEventLogState {
+    /// Forget the jobs that finished more than `retention` ago
+    fn evict_finished(&mut self, retention: Duration) {
+        self.jobs.retain(|_, job| job.finished_at.is_none_or(|finished_at| finished_at.elapsed() < retention));
+    }
+}
+
+impl JobEventLog {
+    pub fn new() -> Self {
+        Self::with_retention(DEFAULT_EVENT_RETENTION)
+    }
+
+    /// Keep the events of finished jobs for `retention`
+    pub fn with_retention(retention: Duration) -> Self {
+        let micros = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_micros() as u64)
+            .unwrap_or(0);
+        Self {
+            state: Arc::new(Mutex::new(JobEventLogState { last_id: micros, jobs: HashMap::new() })),
+            changed: Arc::new(Notify::new()),
+            retention,
+        }
+    }
+
+    /// Record the event that brought a job to its current state, if any, and wake subscribers
+    pub fn record(&self, job: &VideoCreationJob) -> Option<JobEvent> {
+        let event = {
+            let mut state = self.state.lock().unwrap();
+            state.evict_finished(self.retention);
+            let last = state.jobs.get(&job.id).and_then(|job| job.events.last());
+            let kind = VideoCreationEventKind::between(last.map(|event| &event.job), job)?;
+            let replaces_progress = kind == VideoCreationEventKind::Progress
+                && last.is_some_and(|event| event.kind == VideoCreationEventKind::Progress);
+
+            state.last_id += 1;
+            let event = JobEvent { id: state.last_id, kind, job: job.clone() };
+            let events = state.jobs.entry(job.id.clone()).or_insert_with(|| JobEvents {
+                first_id: event.id,
+                events: Vec::new(),
+                finished_at: None,
+            });
+            if replaces_progress {
+                events.events.pop();
+            }
+            if kind.is_terminal() {
+                events.finished_at = Some(Instant::now());
+            }
+            events.events.push(event.clone());
+            event
+        };
+
+        self.changed.notify_waiters();
+        Some(event)
+    }
+
+    /// Record a job's stored state unless events were already recorded for it,
+    /// e.g. for jobs this process has not run itself
+    pub fn record_if_unknown(&self, job: &VideoCreationJob) {
+        if !self.state.lock().unwrap().jobs.contains_key(&job.id) {
+            self.record(job);
+        }
+    }
+
+    /// Events of a job recorded after the event with id `last_event_id` (0 for all of them).
+    /// An id this log did not hand out for the job, e.g. one from before a restart,
+    /// gets the latest event instead, as a snapshot of the job's current state.
+    pub fn events_after(&self, job_id: &VideoCreationJobId, last_event_id: u64) -> Vec<JobEvent> {
+        let state = self.state.lock().unwrap();
+        let Some(job) = state.jobs.get(job_id) else {
+            return Vec::new();
+        };
+
+        let known = last_event_id == 0 || (job.first_id..=state.last_id).contains(&last_event_id);
+        if !known {
+            return job.events.last().cloned().into_iter().collect();
+        }
+        job.events.iter().filter(|event| event.id > last_event_id).cloned().collect()
+    }
+
+    /// Whether the job's last event is terminal
+    pub fn is_finished(&self, job_id: &VideoCreationJobId) -> bool {
+        self.state.lock().unwrap().jobs
+            .get(job_id)
+            .and_then(|job| job.events.last())
+            .is_some_and(|event| event.kind.is_terminal())
+    }
+
+    /// Resolves on the next recorded event. Create it before checking for events,
+    /// so an event recorded in between still wakes the caller.
+    pub fn changed(&self) -> Notified<'_> {
+        self.changed.notified()
+    }
+}
+
+impl Default for JobEventLog {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Repository decorator that records an event for every job change it writes
+pub struct PublishingVideoCreationRepository {
+    inner: SharedVideoCreationRepository,
+    events: JobEventLog,
+}
+
+impl PublishingVideoCreationRepository {
+    pub fn new(inner: SharedVideoCreationRepository, events: JobEventLog) -> Self {
+        Self { inner, events }
+    }
+}
+
+impl VideoCreationRepository for PublishingVideoCreationRepository {
+    fn save_job(&self, job: &VideoCreationJob) -> DomainResult<()> {
+        self.inner.save_job(job)?;
+        self.events.record(job);
+        Ok(())
+    }
+
+    fn find_job_by_id(&self, id: &VideoCreationJobId) -> DomainResult<Option<VideoCreationJob>> {
+        self.inner.find_job_by_id(id)
+    }
+
+    fn find_jobs_by_status(&self, status: &VideoCreationStatus) -> DomainResult<Vec<VideoCreationJob>> {
+        self.inner.find_jobs_by_status(status)
+    }
+
+    fn query_jobs(&self, query: &VideoCreationJobQuery) -> DomainResult<Vec<VideoCreationJob>> {
+        self.inner.query_jobs(query)
+    }
+
+    fn update_job(&self, job: &VideoCreationJob) -> DomainResult<()> {
+        self.inner.update_job(job)?;
+        self.events.record(job);
+        Ok(())
+    }
+
+    fn update_job_unless_finished(&self, job: &VideoCreationJob) -> DomainResult<bool> {
+        let written = self.inner.update_job_unless_finished(job)?;
+        if written {
+            self.events.record(job);
+        }
+        Ok(written)
+    }
+}
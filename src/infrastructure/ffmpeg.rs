@@ -1,11 +1,253 @@
 // Infrastructure layer FFmpeg implementation
+use std::collections::BTreeSet;
+use std::io::{BufRead, BufReader, Read, Write};
 use std::process::{Command, Stdio};
 use std::fs;
 use std::path::Path;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tempfile::NamedTempFile;
 use crate::domain::video_creation::{
-    VideoCreator, VideoCreationRequest, VideoCreationJob, VideoCreationJobId, VideoCreationRepository
+    EncoderProfile, EncoderQuality, VideoContainer, VideoCreator, VideoCreationRequest, VideoCreationJob, VideoCreationJobId,
+    VideoCreationRepository, OUTPUT_FRAME_RATE
 };
-use crate::domain::common::{DomainResult, DomainError};
+use crate::domain::common::{DomainResult, DomainError, FieldError};
+
+/// Set to ask a running job to stop; FFmpeg is killed at the next check
+pub type CancellationFlag = Arc<AtomicBool>;
+
+/// How often a running FFmpeg process is checked for exit or cancellation
+const CANCELLATION_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+/// One report from `ffmpeg -progress`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FFmpegProgress {
+    pub frame: Option<usize>,
+    /// Seconds of output encoded so far
+    pub out_time_seconds: Option<f64>,
+    /// Set on the last report (`progress=end`)
+    pub finished: bool,
+}
+
+/// Incremental parser for the key=value blocks FFmpeg writes with `-progress`;
+/// each block ends with a `progress=continue` or `progress=end` line
+pub struct FFmpegProgressParser {
+    current: FFmpegProgress,
+}
+
+impl FFmpegProgressParser {
+    pub fn new() -> Self {
+        Self {
+            current: FFmpegProgress::default(),
+        }
+    }
+
+    /// Feed one line of output; returns the report once its block is complete
+    pub fn push_line(&mut self, line: &str) -> Option<FFmpegProgress> {
+        let (key, value) = line.trim().split_once('=')?;
+        let value = value.trim();
+
+        match key.trim() {
+            "frame" => self.current.frame = value.parse().ok(),
+            // Both are microseconds (out_time_ms is misnamed by FFmpeg); "N/A" before the first frame
+            "out_time_us" | "out_time_ms" => {
+                if let Some(micros) = value.parse::<i64>().ok().filter(|&micros| micros >= 0) {
+                    self.current.out_time_seconds = Some(micros as f64 / 1_000_000.0);
+                }
+            }
+            "out_time" if self.current.out_time_seconds.is_none() => {
+                self.current.out_time_seconds = parse_timestamp(value);
+            }
+            "progress" => {
+                self.current.finished = value == "end";
+                return Some(std::mem::take(&mut self.current));
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+impl Default for FFmpegProgressParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Parse an `HH:MM:SS.micros` timestamp into seconds
+fn parse_timestamp(value: &str) -> Option<f64> {
+    let mut parts = value.splitn(3, ':');
+    let hours: f64 = parts.next()?.parse().ok()?;
+    let minutes: f64 = parts.next()?.parse().ok()?;
+    let seconds: f64 = parts.next()?.parse().ok()?;
+    let total = hours * 3600.0 + minutes * 60.0 + seconds;
+    (total >= 0.0).then_some(total)
+}
+
+/// FFmpeg binary used unless one is configured (`FFMPEG_PATH`)
+pub const DEFAULT_FFMPEG_PATH: &str = "ffmpeg";
+
+/// Filter every creation request runs its images through
+const REQUIRED_FILTER: &str = "scale";
+/// Demuxer that reads the image list of a creation request
+const REQUIRED_DEMUXER: &str = "concat";
+
+/// What an FFmpeg installation can do, from its `-version`, `-encoders`, `-filters` and `-formats` output.
+/// Probing runs FFmpeg four times, so probe once at startup and share the result.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct FFmpegCapabilities {
+    pub ffmpeg_path: String,
+    /// Whether `-version` ran; nothing else is known when it did not
+    pub available: bool,
+    pub version: Option<String>,
+    pub encoders: BTreeSet<String>,
+    pub filters: BTreeSet<String>,
+    pub muxers: BTreeSet<String>,
+    pub demuxers: BTreeSet<String>,
+}
+
+/// Capabilities probed at startup, shared by the HTTP handlers and the workers
+pub type SharedFFmpegCapabilities = Arc<FFmpegCapabilities>;
+
+impl FFmpegCapabilities {
+    /// Run the FFmpeg binary at `ffmpeg_path` and collect what it supports
+    pub fn probe(ffmpeg_path: &str) -> Self {
+        let Some(version) = run_ffmpeg_listing(ffmpeg_path, "-version") else {
+            return Self::unavailable(ffmpeg_path);
+        };
+        let listing = |flag| run_ffmpeg_listing(ffmpeg_path, flag).unwrap_or_default();
+        Self::from_listings(ffmpeg_path, &version, &listing("-encoders"), &listing("-filters"), &listing("-formats"))
+    }
+
+    /// Capabilities of an FFmpeg that could not be run
+    pub fn unavailable(ffmpeg_path: &str) -> Self {
+        Self {
+            ffmpeg_path: ffmpeg_path.to_string(),
+            ..Self::default()
+        }
+    }
+
+    /// Capabilities from the text FFmpeg printed for `-version`, `-encoders`, `-filters` and `-formats`
+    pub fn from_listings(ffmpeg_path: &str, version: &str, encoders: &str, filters: &str, formats: &str) -> Self {
+        let (muxers, demuxers) = parse_formats(formats);
+        Self {
+            ffmpeg_path: ffmpeg_path.to_string(),
+            available: true,
+            version: parse_version(version),
+            encoders: parse_encoders(encoders),
+            filters: parse_filters(filters),
+            muxers,
+            demuxers,
+        }
+    }
+
+    /// What a request encoded with `profile` needs that this FFmpeg lacks, per request field.
+    /// Empty when FFmpeg could not be probed, as nothing is known about it then.
+    pub fn unsupported(&self, profile: &EncoderProfile) -> Vec<FieldError> {
+        if !self.available {
+            return Vec::new();
+        }
+
+        let build = match &self.version {
+            Some(version) => format!("FFmpeg {}", version),
+            None => "this FFmpeg build".to_string(),
+        };
+        let mut errors = Vec::new();
+        if !self.demuxers.contains(REQUIRED_DEMUXER) {
+            errors.push(FieldError::new("images", format!("need the {} demuxer, which {} lacks", REQUIRED_DEMUXER, build)));
+        }
+        if !self.filters.contains(REQUIRED_FILTER) {
+            errors.push(FieldError::new("output", format!("needs the {} filter, which {} lacks", REQUIRED_FILTER, build)));
+        }
+        if !self.encoders.contains(&profile.codec) {
+            errors.push(FieldError::new("output.profile", format!(
+                "{} needs the {} encoder, which {} lacks", profile.name, profile.codec, build,
+            )));
+        }
+        if !self.muxers.contains(profile.container.muxer()) {
+            errors.push(FieldError::new("output.profile", format!(
+                "{} needs the {} muxer, which {} lacks", profile.name, profile.container.muxer(), build,
+            )));
+        }
+        errors
+    }
+}
+
+/// Check if the FFmpeg binary at `ffmpeg_path` runs
+pub fn check_ffmpeg_available(ffmpeg_path: &str) -> bool {
+    Command::new(ffmpeg_path)
+        .arg("-version")
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// Stdout of `ffmpeg <flag>` (the banner goes to stderr), or `None` when it did not run successfully
+fn run_ffmpeg_listing(ffmpeg_path: &str, flag: &str) -> Option<String> {
+    let output = Command::new(ffmpeg_path)
+        .arg(flag)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()
+        .ok()?;
+    output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Version from the first line of `-version`, e.g. `6.1.1` from `ffmpeg version 6.1.1 Copyright ...`
+pub fn parse_version(output: &str) -> Option<String> {
+    output.lines()
+        .find_map(|line| line.trim().strip_prefix("ffmpeg version "))
+        .and_then(|rest| rest.split_whitespace().next())
+        .map(str::to_string)
+}
+
+/// Encoder names from `-encoders`, listed below the `------` line as `V....D libx264  description`
+pub fn parse_encoders(output: &str) -> BTreeSet<String> {
+    output.lines()
+        .skip_while(|line| !line.trim().starts_with("---"))
+        .skip(1)
+        .filter_map(|line| line.split_whitespace().nth(1))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Filter names from `-filters`, listed as `TSC scale  V->V  description`
+pub fn parse_filters(output: &str) -> BTreeSet<String> {
+    output.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let (_, name, io) = (fields.next()?, fields.next()?, fields.next()?);
+            io.contains("->").then(|| name.to_string())
+        })
+        .collect()
+}
+
+/// Muxer and demuxer names from `-formats`, listed below the `--` line as `DE matroska,webm  description`
+pub fn parse_formats(output: &str) -> (BTreeSet<String>, BTreeSet<String>) {
+    let mut muxers = BTreeSet::new();
+    let mut demuxers = BTreeSet::new();
+    for line in output.lines().skip_while(|line| !line.trim().starts_with("--")).skip(1) {
+        let mut fields = line.split_whitespace();
+        let (Some(flags), Some(names)) = (fields.next(), fields.next()) else {
+            continue;
+        };
+        if !flags.chars().all(|flag| matches!(flag, 'D' | 'E' | 'd' | '.')) {
+            continue;
+        }
+        for name in names.split(',') {
+            if flags.contains('E') {
+                muxers.insert(name.to_string());
+            }
+            if flags.contains('D') {
+                demuxers.insert(name.to_string());
+            }
+        }
+    }
+    (muxers, demuxers)
+}
 
 /// FFmpeg-based video creator implementation
 pub struct FFmpegVideoCreator<R> 
@@ -13,6 +255,9 @@ where
     R: VideoCreationRepository,
 {
     repository: R,
+    cancellation: CancellationFlag,
+    ffmpeg_path: String,
+    capabilities: Option<SharedFFmpegCapabilities>,
 }
 
 impl<R> FFmpegVideoCreator<R>
@@ -20,38 +265,53 @@ where
     R: VideoCreationRepository,
 {
     pub fn new(repository: R) -> Self {
-        Self { repository }
-    }
-
-    /// Check if FFmpeg is available on the system
-    pub fn check_ffmpeg_available() -> bool {
-        Command::new("ffmpeg")
-            .arg("-version")
-            .stdout(Stdio::null())
-            .stderr(Stdio::null())
-            .status()
-            .map(|status| status.success())
-            .unwrap_or(false)
-    }
-
-    /// Create a temporary file list for FFmpeg
-    fn create_file_list(&self, request: &VideoCreationRequest) -> DomainResult<String> {
-        let temp_dir = std::env::temp_dir();
-        let list_file = temp_dir.join(format!("ffmpeg_list_{}.txt", 
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap()
-                .as_millis()
-        ));
+        Self {
+            repository,
+            cancellation: CancellationFlag::default(),
+            ffmpeg_path: DEFAULT_FFMPEG_PATH.to_string(),
+            capabilities: None,
+        }
+    }
+
+    /// Run the FFmpeg binary at `ffmpeg_path` instead of the one on `PATH`
+    pub fn with_ffmpeg_path(mut self, ffmpeg_path: impl Into<String>) -> Self {
+        self.ffmpeg_path = ffmpeg_path.into();
+        self
+    }
+
+    /// Run the probed FFmpeg, trusting the probe instead of checking the binary before every encode
+    pub fn with_capabilities(mut self, capabilities: SharedFFmpegCapabilities) -> Self {
+        self.ffmpeg_path = capabilities.ffmpeg_path.clone();
+        self.capabilities = Some(capabilities);
+        self
+    }
+
+    /// Stop encoding (and kill FFmpeg) once `cancellation` is set
+    pub fn with_cancellation(mut self, cancellation: CancellationFlag) -> Self {
+        self.cancellation = cancellation;
+        self
+    }
+
+    fn is_cancelled(&self) -> bool {
+        self.cancellation.load(Ordering::SeqCst)
+    }
+
+    /// Create a temporary file list for FFmpeg, under a fresh unique name
+    fn create_file_list(&self, request: &VideoCreationRequest) -> DomainResult<NamedTempFile> {
+        let mut list_file = tempfile::Builder::new()
+            .prefix("ffmpeg_list_")
+            .suffix(".txt")
+            .tempfile()
+            .map_err(|e| DomainError::Io(e.to_string()))?;
 
         let mut content = String::new();
-        for image_path in &request.image_paths {
+        for (index, image_path) in request.image_paths.iter().enumerate() {
             // Use absolute paths to avoid path resolution issues
             let absolute_path = if Path::new(image_path.as_str()).is_absolute() {
                 image_path.as_str().to_string()
             } else {
                 std::env::current_dir()
-                    .map_err(|e| DomainError::IoError(e.to_string()))?
+                    .map_err(|e| DomainError::Io(e.to_string()))?
                     .join(image_path.as_str())
                     .to_string_lossy()
                     .to_string()
@@ -59,7 +319,7 @@ where
             
             // FFmpeg concat format: file path and duration
             content.push_str(&format!("file '{}'\n", absolute_path));
-            content.push_str(&format!("duration {}\n", request.image_spec.duration_seconds));
+            content.push_str(&format!("duration {}\n", request.image_duration(index)));
         }
         
         // Add the last image again to ensure proper duration
@@ -68,7 +328,7 @@ where
                 last_image.as_str().to_string()
             } else {
                 std::env::current_dir()
-                    .map_err(|e| DomainError::IoError(e.to_string()))?
+                    .map_err(|e| DomainError::Io(e.to_string()))?
                     .join(last_image.as_str())
                     .to_string_lossy()
                     .to_string()
@@ -76,111 +336,233 @@ where
             content.push_str(&format!("file '{}'\n", absolute_path));
         }
 
-        fs::write(&list_file, content)
-            .map_err(|e| DomainError::IoError(e.to_string()))?;
+        list_file.write_all(content.as_bytes())
+            .and_then(|_| list_file.flush())
+            .map_err(|e| DomainError::Io(e.to_string()))?;
 
-        Ok(list_file.to_string_lossy().to_string())
+        Ok(list_file)
     }
 
-    /// Execute FFmpeg command to create video
-    fn execute_ffmpeg(&self, list_file: &str, request: &VideoCreationRequest) -> DomainResult<()> {
+    /// Execute FFmpeg command to create video, passing each `-progress` report to `on_progress`
+    fn execute_ffmpeg(
+        &self,
+        list_file: &Path,
+        request: &VideoCreationRequest,
+        on_progress: &mut dyn FnMut(&FFmpegProgress),
+    ) -> DomainResult<()> {
         // Use absolute path for output file as well
         let output_path = if Path::new(request.output_path.as_str()).is_absolute() {
             request.output_path.as_str().to_string()
         } else {
             std::env::current_dir()
-                .map_err(|e| DomainError::IoError(e.to_string()))?
+                .map_err(|e| DomainError::Io(e.to_string()))?
                 .join(request.output_path.as_str())
                 .to_string_lossy()
                 .to_string()
         };
 
         // Use concat demuxer approach (better for image sequences)
-        let output = Command::new("ffmpeg")
-            .arg("-f").arg("concat")
-            .arg("-safe").arg("0")
-            .arg("-i").arg(list_file)
-            .arg("-vf").arg(format!("scale={}:{}", request.image_spec.width, request.image_spec.height))
-            .arg("-c:v").arg("libx264")
-            .arg("-pix_fmt").arg("yuv420p")
-            .arg("-r").arg("1") // 1 fps for consistent timing
-            .arg("-y") // Overwrite output file
-            .arg(&output_path)
-            .output()
-            .map_err(|e| DomainError::IoError(format!("Failed to execute FFmpeg: {}", e)))?;
+        let mut child = FFmpegCommandBuilder::new(&self.ffmpeg_path)
+            .input_concat_file(&list_file.to_string_lossy())
+            .scale(request.image_spec.width, request.image_spec.height)
+            .encoder_profile(&request.encoder_profile)
+            .framerate(OUTPUT_FRAME_RATE) // 1 fps for consistent timing
+            .progress_to_stdout()
+            .overwrite()
+            .output(&output_path)
+            .into_command()
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| DomainError::external_tool("ffmpeg", format!("failed to execute: {}", e)))?;
+
+        // Drain stderr on its own thread so FFmpeg never blocks on a full pipe
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        let stderr_reader = std::thread::spawn(move || {
+            let mut output = Vec::new();
+            let _ = stderr.read_to_end(&mut output);
+            output
+        });
+
+        // Parse progress reports as they arrive; the loop below picks them up between checks
+        let stdout = child.stdout.take().expect("stdout is piped");
+        let (progress_sender, progress_receiver) = std::sync::mpsc::channel();
+        let progress_reader = std::thread::spawn(move || {
+            let mut parser = FFmpegProgressParser::new();
+            for line in BufReader::new(stdout).lines() {
+                let Ok(line) = line else { break };
+                if let Some(progress) = parser.push_line(&line) {
+                    if progress_sender.send(progress).is_err() {
+                        break;
+                    }
+                }
+            }
+        });
 
-        if !output.status.success() {
-            let error_msg = String::from_utf8_lossy(&output.stderr);
-            return Err(DomainError::IoError(format!("FFmpeg failed: {}", error_msg)));
+        let status = loop {
+            let exited = child.try_wait().map_err(|e| DomainError::Io(e.to_string()))?;
+            for progress in progress_receiver.try_iter() {
+                on_progress(&progress);
+            }
+            if let Some(status) = exited {
+                break status;
+            }
+            if self.is_cancelled() {
+                let _ = child.kill();
+                let _ = child.wait();
+                let _ = progress_reader.join();
+                let _ = stderr_reader.join();
+                return Err(DomainError::Conflict("Job cancelled".to_string()));
+            }
+            std::thread::sleep(CANCELLATION_POLL_INTERVAL);
+        };
+
+        // Reports written just before exit
+        let _ = progress_reader.join();
+        for progress in progress_receiver.try_iter() {
+            on_progress(&progress);
+        }
+
+        let stderr = stderr_reader.join().unwrap_or_default();
+        if !status.success() {
+            let error_msg = String::from_utf8_lossy(&stderr);
+            return Err(DomainError::external_tool("ffmpeg", error_msg));
         }
 
         Ok(())
     }
 
+    /// Apply a progress report to the job and persist it, with an ETA from the throughput so far
+    fn record_progress(&self, job: &mut VideoCreationJob, progress: &FFmpegProgress, started: Instant) {
+        let Some(encoded_seconds) = progress.out_time_seconds else {
+            return;
+        };
+        let total_seconds = job.request.total_duration() as f64;
+        let elapsed = started.elapsed().as_secs_f64();
+
+        let estimated_time_remaining = if progress.finished {
+            Some(0.0)
+        } else if encoded_seconds > 0.0 && elapsed > 0.0 {
+            let speed = encoded_seconds / elapsed; // seconds of video per second of wall time
+            Some(((total_seconds - encoded_seconds).max(0.0) / speed) as f32)
+        } else {
+            None
+        };
+
+        let current_frame = progress.frame
+            .unwrap_or((encoded_seconds * OUTPUT_FRAME_RATE as f64) as usize);
+        if job.record_encoding_progress(current_frame, encoded_seconds as f32, estimated_time_remaining).is_err() {
+            return;
+        }
+        if let Err(e) = self.store(job) {
+            eprintln!("Warning: Failed to record progress of job {}: {}", job.id.as_str(), e);
+        }
+    }
+
+    /// Persist a change to the job unless it was cancelled (or otherwise finished) meanwhile.
+    /// A finished stored job also stops the encode. Returns whether the change was written.
+    fn store(&self, job: &VideoCreationJob) -> DomainResult<bool> {
+        if self.is_cancelled() {
+            return Ok(false);
+        }
+        let written = self.repository.update_job_unless_finished(job)?;
+        if !written {
+            self.cancellation.store(true, Ordering::SeqCst);
+        }
+        Ok(written)
+    }
+
+    /// Remove whatever FFmpeg wrote for a job that will not complete
+    fn remove_output(&self, request: &VideoCreationRequest) {
+        match fs::remove_file(request.output_path.as_str()) {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => eprintln!("Warning: Failed to remove partial output {}: {}", request.output_path.as_str(), e),
+        }
+    }
+
     /// Clean up temporary files
-    fn cleanup(&self, list_file: &str) {
-        if let Err(e) = fs::remove_file(list_file) {
-            eprintln!("Warning: Failed to clean up temporary file {}: {}", list_file, e);
+    fn cleanup(&self, list_file: NamedTempFile) {
+        let path = list_file.path().display().to_string();
+        if let Err(e) = list_file.close() {
+            eprintln!("Warning: Failed to clean up temporary file {}: {}", path, e);
         }
     }
 }
 
-impl<R> VideoCreator for FFmpegVideoCreator<R>
+impl<R> FFmpegVideoCreator<R>
 where 
     R: VideoCreationRepository,
 {
-    fn create_video(&self, request: &VideoCreationRequest) -> DomainResult<VideoCreationJob> {
-        // Check if FFmpeg is available
-        if !Self::check_ffmpeg_available() {
-            return Err(DomainError::IoError("FFmpeg not found on system".to_string()));
+    /// Build the file list, run FFmpeg and check that it produced the output file
+    fn encode(&self, request: &VideoCreationRequest, on_progress: &mut dyn FnMut(&FFmpegProgress)) -> DomainResult<()> {
+        let available = match &self.capabilities {
+            Some(capabilities) => capabilities.available,
+            None => check_ffmpeg_available(&self.ffmpeg_path),
+        };
+        if !available {
+            return Err(DomainError::external_tool("ffmpeg", "not found on system"));
         }
 
-        // Create a new job
-        let job_id = VideoCreationJobId::generate();
-        let mut job = VideoCreationJob::new(job_id, request.clone());
-
-        // Save initial job state
-        self.repository.save_job(&job)?;
-
-        // Start the job
-        job.start()?;
-        self.repository.update_job(&job)?;
-
-        // Create temporary file list
         let list_file = self.create_file_list(request)?;
-
-        // Execute FFmpeg in a separate thread simulation (in real app, use tokio or thread pool)
-        match self.execute_ffmpeg(&list_file, request) {
-            Ok(_) => {
-                job.complete()?;
-                self.repository.update_job(&job)?;
-            }
-            Err(e) => {
-                job.fail(e.to_string())?;
-                self.repository.update_job(&job)?;
-            }
-        }
-
-        // Clean up
-        self.cleanup(&list_file);
+        let result = self.execute_ffmpeg(list_file.path(), request, on_progress);
+        self.cleanup(list_file);
+        result?;
 
         // Verify output file was created
         let output_path = if Path::new(request.output_path.as_str()).is_absolute() {
             request.output_path.as_str().to_string()
         } else {
             std::env::current_dir()
-                .map_err(|e| DomainError::IoError(e.to_string()))?
+                .map_err(|e| DomainError::Io(e.to_string()))?
                 .join(request.output_path.as_str())
                 .to_string_lossy()
                 .to_string()
         };
 
         if !Path::new(&output_path).exists() {
-            job.fail("Output video file was not created".to_string())?;
-            self.repository.update_job(&job)?;
+            return Err(DomainError::Io("Output video file was not created".to_string()));
         }
 
-        Ok(job)
+        Ok(())
+    }
+}
+
+impl<R> VideoCreator for FFmpegVideoCreator<R>
+where 
+    R: VideoCreationRepository,
+{
+    /// Run the job to completion, recording every state change in the repository.
+    /// FFmpeg failures end up on the job itself and a cancelled job is left `Cancelled`
+    /// without its partial output; only repository errors are returned.
+    /// A status stored as finished by someone else is never overwritten.
+    fn create_video(&self, mut job: VideoCreationJob) -> DomainResult<VideoCreationJob> {
+        job.start()?;
+        if self.store(&job)? {
+            let request = job.request.clone();
+            let started = Instant::now();
+            let result = self.encode(&request, &mut |progress| self.record_progress(&mut job, progress, started));
+            if !self.is_cancelled() {
+                match result {
+                    Ok(()) => job.complete()?,
+                    Err(e) => job.fail(e.to_string())?,
+                }
+                if self.store(&job)? {
+                    return Ok(job);
+                }
+            }
+        }
+
+        self.remove_output(&job.request);
+        match self.repository.find_job_by_id(&job.id)? {
+            // The cancel request already stored the outcome
+            Some(stored) if stored.status.is_terminal() => Ok(stored),
+            _ => {
+                job.cancel()?;
+                self.repository.update_job(&job)?;
+                Ok(job)
+            }
+        }
     }
 
     fn get_job_status(&self, job_id: &VideoCreationJobId) -> DomainResult<Option<VideoCreationJob>> {
@@ -194,9 +576,9 @@ pub struct FFmpegCommandBuilder {
 }
 
 impl FFmpegCommandBuilder {
-    pub fn new() -> Self {
+    pub fn new(ffmpeg_path: &str) -> Self {
         Self {
-            command: Command::new("ffmpeg"),
+            command: Command::new(ffmpeg_path),
         }
     }
 
@@ -220,11 +602,53 @@ impl FFmpegCommandBuilder {
         self
     }
 
+    /// Codec, rate control, preset, GOP and container of `profile`
+    pub fn encoder_profile(mut self, profile: &EncoderProfile) -> Self {
+        self = self.codec(&profile.codec);
+        if let Some(codec_profile) = &profile.codec_profile {
+            self.command.arg("-profile:v").arg(codec_profile);
+        }
+        self = self.pixel_format(&profile.pixel_format);
+
+        match profile.quality {
+            Some(EncoderQuality::Crf(crf)) => {
+                self.command.arg("-crf").arg(crf.to_string());
+                // libvpx only treats CRF as constant quality without a target bitrate
+                if profile.codec.starts_with("libvpx") {
+                    self.command.arg("-b:v").arg("0");
+                }
+            }
+            Some(EncoderQuality::BitrateKbps(kbps)) => {
+                self.command.arg("-b:v").arg(format!("{}k", kbps));
+            }
+            None => {}
+        }
+        if let Some(preset) = &profile.preset {
+            self.command.arg("-preset").arg(preset);
+        }
+        if let Some(gop_size) = profile.gop_size {
+            self.command.arg("-g").arg(gop_size.to_string());
+        }
+
+        self.command.arg("-f").arg(profile.container.muxer());
+        if profile.container == VideoContainer::Mp4 {
+            // Index up front, so playback starts before the download finishes
+            self.command.arg("-movflags").arg("+faststart");
+        }
+        self
+    }
+
     pub fn framerate(mut self, fps: u32) -> Self {
         self.command.arg("-r").arg(fps.to_string());
         self
     }
 
+    /// Write `-progress` key=value reports to stdout instead of the stats line
+    pub fn progress_to_stdout(mut self) -> Self {
+        self.command.arg("-progress").arg("pipe:1").arg("-nostats");
+        self
+    }
+
     pub fn overwrite(mut self) -> Self {
         self.command.arg("-y");
         self
@@ -235,13 +659,23 @@ impl FFmpegCommandBuilder {
         self
     }
 
+    /// The command as built, to run with custom stdio
+    pub fn into_command(self) -> Command {
+        self.command
+    }
+
+    /// Arguments passed so far, without the binary
+    pub fn args(&self) -> Vec<String> {
+        self.command.get_args().map(|arg| arg.to_string_lossy().to_string()).collect()
+    }
+
     pub fn execute(mut self) -> DomainResult<()> {
         let output = self.command.output()
-            .map_err(|e| DomainError::IoError(format!("Failed to execute FFmpeg: {}", e)))?;
+            .map_err(|e| DomainError::external_tool("ffmpeg", format!("failed to execute: {}", e)))?;
 
         if !output.status.success() {
             let error_msg = String::from_utf8_lossy(&output.stderr);
-            return Err(DomainError::IoError(format!("FFmpeg failed: {}", error_msg)));
+            return Err(DomainError::external_tool("ffmpeg", error_msg));
         }
 
         Ok(())
@@ -250,6 +684,6 @@ impl FFmpegCommandBuilder {
 
 impl Default for FFmpegCommandBuilder {
     fn default() -> Self {
-        Self::new()
+        Self::new(DEFAULT_FFMPEG_PATH)
     }
 } 
\ No newline at end of file
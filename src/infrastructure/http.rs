@@ -1,8 +1,31 @@
+use std::convert::Infallible;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use std::time::SystemTime;
 use actix_web::{HttpRequest, HttpResponse, Result, web};
-use crate::domain::video::{VideoChunk, parse_range_header, get_video_metadata, read_video_chunk, validate_range, format_content_range};
+use actix_web::body::{BodySize, MessageBody, SizedStream};
+use futures_util::StreamExt;
+use actix_web::http::{Method, StatusCode};
+use actix_web::http::header::{self, HttpDate, HeaderValue};
+use crate::domain::video::{VideoByteStream, VideoRepository, VideoStreamingService};
 use crate::shared::config::Config;
 use crate::application::services::VideoCreationAppService;
-use crate::application::dto::CreateVideoRequest;
+use crate::application::dto::{
+    CreateVideoRequest, CreateVideoImage, CreateVideoOutput, StreamVideoRequest, StreamVideoResponse,
+    ValidateImagesResponse, HealthResponse, ImageSpecResponse, BlockCacheStatsResponse, ListJobsRequest,
+    FFmpegCapabilitiesResponse, EncoderProfileSupportResponse,
+};
+use crate::application::use_cases::{StreamVideoUseCase, GetVideoMetadataUseCase};
+use crate::domain::common::{DomainError, DomainResult};
+use crate::domain::conditional::{ConditionalRequest, EntityTag, EntityTagMatch, IfRange};
+use crate::infrastructure::repositories::{SharedVideoRepository, SingleFileVideoRepository};
+use crate::infrastructure::services::FileVideoStreamingService;
+use crate::domain::video_creation::{unix_seconds, EncoderProfile, VideoCreationJobId, ENCODER_PROFILE_NAMES};
+use crate::infrastructure::cache::BlockCache;
+use crate::infrastructure::events::JobEvent;
+use crate::infrastructure::ffmpeg::{check_ffmpeg_available, FFmpegCapabilities, SharedFFmpegCapabilities};
+use crate::infrastructure::probe::SharedMediaProbe;
+use crate::infrastructure::responses::{render, render_problem};
 
 /// Extract range header from HTTP request
 pub fn extract_range_header(req: &HttpRequest) -> Option<String> {
@@ -12,176 +35,597 @@ pub fn extract_range_header(req: &HttpRequest) -> Option<String> {
         .map(|s| s.to_string())
 }
 
-/// Create HTTP response for video streaming
-pub fn create_video_response(chunk: VideoChunk, content_type: &str) -> HttpResponse {
-    HttpResponse::PartialContent()
-        .content_type(content_type)
-        .append_header(("Content-Range", format_content_range(&chunk.range)))
-        .append_header(("Accept-Ranges", "bytes"))
-        .body(chunk.data)
+/// Extract If-None-Match, If-Modified-Since and If-Range from HTTP request
+pub fn extract_conditional_request(req: &HttpRequest) -> ConditionalRequest {
+    let header_str = |name: &str| req.headers().get(name).and_then(|h| h.to_str().ok());
+
+    let if_range = header_str("If-Range").and_then(|value| {
+        if value.trim_start().starts_with('"') || value.trim_start().starts_with("W/") {
+            EntityTag::parse(value).map(IfRange::EntityTag)
+        } else {
+            value.trim().parse::<HttpDate>().ok().map(|date| IfRange::Date(date.into()))
+        }
+    });
+
+    ConditionalRequest {
+        if_none_match: header_str("If-None-Match").and_then(EntityTagMatch::parse),
+        if_modified_since: header_str("If-Modified-Since")
+            .and_then(|value| value.trim().parse::<HttpDate>().ok())
+            .map(SystemTime::from),
+        if_range,
+    }
+}
+
+/// Add ETag and Last-Modified headers to a response
+pub fn append_validator_headers(response: &mut HttpResponse, etag: &str, last_modified: Option<SystemTime>) {
+    if let Ok(value) = HeaderValue::from_str(etag) {
+        response.headers_mut().insert(header::ETAG, value);
+    }
+    if let Some(last_modified) = last_modified {
+        if let Ok(value) = HeaderValue::from_str(&HttpDate::from(last_modified).to_string()) {
+            response.headers_mut().insert(header::LAST_MODIFIED, value);
+        }
+    }
+}
+
+/// Create 304 response for a client whose cached copy is still current
+pub fn create_not_modified_response(etag: &str, last_modified: Option<SystemTime>) -> HttpResponse {
+    let mut response = HttpResponse::NotModified().finish();
+    append_validator_headers(&mut response, etag, last_modified);
+    response
+}
+
+/// Body that advertises a length but carries no bytes, used to answer HEAD requests
+pub struct HeadBody(pub u64);
+
+impl MessageBody for HeadBody {
+    type Error = Infallible;
+
+    fn size(&self) -> BodySize {
+        BodySize::Sized(self.0)
+    }
+
+    fn poll_next(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<web::Bytes, Self::Error>>> {
+        Poll::Ready(None)
+    }
+}
+
+/// Generate a multipart boundary that is unique per response
+fn generate_boundary() -> String {
+    static COUNTER: std::sync::atomic::AtomicU64 = std::sync::atomic::AtomicU64::new(0);
+    let nanos = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_nanos();
+    let sequence = COUNTER.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    format!("byteranges_{:x}_{:x}", nanos, sequence)
+}
+
+fn multipart_part_header(boundary: &str, content_type: &str, content_range: &str) -> String {
+    format!(
+        "--{}\r\nContent-Type: {}\r\nContent-Range: {}\r\n\r\n",
+        boundary, content_type, content_range
+    )
+}
+
+fn multipart_trailer(boundary: &str) -> String {
+    format!("--{}--\r\n", boundary)
+}
+
+/// Stream byte ranges as a multipart/byteranges body (RFC 9110, section 14.6)
+pub fn multipart_byteranges_stream<I>(boundary: &str, content_type: &str, parts: I) -> VideoByteStream
+where
+    I: IntoIterator<Item = (String, VideoByteStream)>,
+{
+    let mut segments = Vec::new();
+    for (content_range, body) in parts {
+        segments.push(VideoByteStream::from_bytes(
+            multipart_part_header(boundary, content_type, &content_range).into(),
+        ));
+        segments.push(body);
+        segments.push(VideoByteStream::from_bytes(web::Bytes::from_static(b"\r\n")));
+    }
+    segments.push(VideoByteStream::from_bytes(multipart_trailer(boundary).into()));
+
+    VideoByteStream::new(futures_util::stream::iter(segments).flatten())
+}
+
+/// Length of the body `multipart_byteranges_stream` produces
+pub fn multipart_byteranges_length<'a, I>(boundary: &str, content_type: &str, parts: I) -> u64
+where
+    I: IntoIterator<Item = (&'a str, u64)>,
+{
+    let parts_length: u64 = parts.into_iter()
+        .map(|(content_range, length)| {
+            multipart_part_header(boundary, content_type, content_range).len() as u64 + length + 2
+        })
+        .sum();
+    parts_length + multipart_trailer(boundary).len() as u64
+}
+
+/// Create HTTP response for a streaming result.
+/// For HEAD requests the body is omitted but its length is still advertised.
+pub fn create_stream_response(mut response: StreamVideoResponse, head: bool) -> HttpResponse {
+    if response.not_modified {
+        return create_not_modified_response(&response.etag, response.last_modified);
+    }
+
+    let mut builder = if response.partial {
+        HttpResponse::PartialContent()
+    } else {
+        HttpResponse::Ok()
+    };
+    builder.append_header(("Accept-Ranges", "bytes"));
+
+    let (length, body) = if response.partial && response.parts.len() > 1 {
+        let boundary = generate_boundary();
+        builder.content_type(format!("multipart/byteranges; boundary={}", boundary));
+
+        let length = multipart_byteranges_length(
+            &boundary,
+            &response.content_type,
+            response.parts.iter().map(|p| (p.content_range.as_str(), p.length)),
+        );
+        let body = multipart_byteranges_stream(
+            &boundary,
+            &response.content_type,
+            response.parts.into_iter().map(|p| (p.content_range, p.body)),
+        );
+        (length, Some(body))
+    } else {
+        builder.content_type(response.content_type.as_str());
+
+        match response.parts.pop() {
+            Some(part) => {
+                if response.partial {
+                    builder.append_header(("Content-Range", part.content_range));
+                }
+                (part.length, Some(part.body))
+            }
+            // Empty file: nothing to stream
+            None => (0, None),
+        }
+    };
+
+    let mut http_response = match body {
+        Some(body) if !head => builder.body(SizedStream::new(length, body)),
+        _ => builder.body(HeadBody(length)),
+    };
+
+    append_validator_headers(&mut http_response, &response.etag, response.last_modified);
+    http_response
+}
+
+/// Handle video streaming request for a catalog video (GET and HEAD)
+/// Example: GET /videos/sample/stream
+pub async fn handle_catalog_video_stream(
+    req: HttpRequest,
+    path: web::Path<String>,
+    repository: web::Data<SharedVideoRepository>,
+    config: web::Data<Config>,
+    cache: web::Data<BlockCache>,
+) -> Result<HttpResponse> {
+    let use_case = StreamVideoUseCase::new(
+        repository.get_ref().clone(),
+        FileVideoStreamingService::new().with_cache(cache.get_ref().clone()),
+    ).with_max_chunk_size(config.max_range_chunk_bytes);
+
+    run_stream_use_case(&req, use_case, path.into_inner()).await
 }
 
-/// Create error response
-pub fn create_error_response(status: actix_web::http::StatusCode, message: &str) -> HttpResponse {
-    HttpResponse::build(status)
-        .content_type("text/plain")
-        .body(message.to_string())
+/// Answer a GET or HEAD streaming request through the stream use case
+async fn run_stream_use_case<R, S>(
+    req: &HttpRequest,
+    use_case: StreamVideoUseCase<R, S>,
+    video_id: String,
+) -> Result<HttpResponse>
+where
+    R: VideoRepository + Send + 'static,
+    S: VideoStreamingService + Send + 'static,
+{
+    let head = req.method() == Method::HEAD;
+    let request = StreamVideoRequest {
+        video_id,
+        range_header: extract_range_header(req),
+        conditional: extract_conditional_request(req),
+    };
+
+    // Metadata lookups touch the file system, keep them off the async workers
+    let result = web::block(move || use_case.execute(request)).await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    match result {
+        Ok(response) => Ok(create_stream_response(response, head)),
+        Err(e) => Ok(render_problem(req, &e)),
+    }
 }
 
-/// Handle video streaming request
+/// Handle video metadata request for a catalog video
+/// Example: GET /videos/sample/metadata
+pub async fn handle_video_metadata(
+    req: HttpRequest,
+    path: web::Path<String>,
+    repository: web::Data<SharedVideoRepository>,
+    probe: web::Data<SharedMediaProbe>,
+) -> Result<HttpResponse> {
+    let use_case = GetVideoMetadataUseCase::new(
+        repository.get_ref().clone(),
+        probe.get_ref().clone(),
+    );
+    let video_id = path.into_inner();
+
+    // Probing runs an external tool, keep it off the async workers
+    let result = web::block(move || use_case.execute(&video_id)).await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    match result {
+        Ok(response) => Ok(render(&req, StatusCode::OK, &response)),
+        Err(e) => Ok(render_problem(&req, &e)),
+    }
+}
+
+/// Handle video streaming request (GET and HEAD) for the configured video file
 pub async fn handle_video_stream(
     req: HttpRequest,
-    config: web::Data<Config>
+    config: web::Data<Config>,
+    cache: web::Data<BlockCache>,
 ) -> Result<HttpResponse> {
-    // Get video metadata
-    let video_info = get_video_metadata(&config.video_path)
-        .map_err(|_| actix_web::error::ErrorInternalServerError("Failed to read video file"))?;
-    
-    // Extract and parse range header
-    let range_header = extract_range_header(&req);
-    let range = parse_range_header(range_header.as_deref(), video_info.total_size);
-    
-    // Validate range
-    if !validate_range(&range) {
-        return Ok(create_error_response(
-            actix_web::http::StatusCode::RANGE_NOT_SATISFIABLE,
-            "Invalid range request"
-        ));
+    let repository = match SingleFileVideoRepository::from_config(&config) {
+        Ok(repository) => repository,
+        Err(e) => return Ok(render_problem(&req, &e)),
+    };
+    let video_id = repository.video_id().as_str().to_string();
+    let use_case = StreamVideoUseCase::new(
+        repository,
+        FileVideoStreamingService::new().with_cache(cache.get_ref().clone()),
+    ).with_max_chunk_size(config.max_range_chunk_bytes);
+
+    run_stream_use_case(&req, use_case, video_id).await
+}
+
+/// Handle video creation from a JSON body
+/// Example: POST /videos {"video_id": "test", "images": [{"path": "img1.jpg"}, {"path": "img2.jpg", "duration": 3}], "output": {"path": "output.mp4"}}
+pub async fn handle_create_video(
+    req: HttpRequest,
+    body: web::Bytes,
+    service: web::Data<VideoCreationAppService>,
+) -> Result<HttpResponse> {
+    if !has_json_content_type(&req) {
+        return Ok(render_problem(&req, &DomainError::UnsupportedMediaType("request body must be application/json".to_string())));
     }
-    
-    // Read video chunk
-    let chunk = read_video_chunk(&config.video_path, &range)
-        .map_err(|_| actix_web::error::ErrorInternalServerError("Failed to read video chunk"))?;
-    
-    // Create response
-    Ok(create_video_response(chunk, &config.content_type))
+
+    let request = match parse_json_body::<CreateVideoRequest>(&body) {
+        Ok(request) => request,
+        Err(e) => return Ok(render_problem(&req, &e)),
+    };
+
+    run_video_creation(&req, request, service).await
 }
 
-/// Handle video creation from images using query parameters
+/// Legacy alias of `POST /videos` using query parameters
 /// Example: POST /create-video?video_id=test&output_path=output.mp4&image1=img1.jpg&image2=img2.jpg
-pub async fn handle_create_video(
+pub async fn handle_create_video_query(
+    req: HttpRequest,
     query: web::Query<std::collections::HashMap<String, String>>,
-    config: web::Data<Config>,
+    service: web::Data<VideoCreationAppService>,
 ) -> Result<HttpResponse> {
-    let service = VideoCreationAppService::new(config.get_ref().clone());
-    
     // Parse query parameters
-    let video_id = query.get("video_id")
-        .ok_or_else(|| actix_web::error::ErrorBadRequest("Missing video_id parameter"))?;
+    let Some(video_id) = query.get("video_id") else {
+        return Ok(render_problem(&req, &DomainError::validation("video_id", "is required")));
+    };
     
-    let output_path = query.get("output_path")
-        .ok_or_else(|| actix_web::error::ErrorBadRequest("Missing output_path parameter"))?;
+    let Some(output_path) = query.get("output_path") else {
+        return Ok(render_problem(&req, &DomainError::validation("output_path", "is required")));
+    };
     
     // Collect image paths from image1, image2, image3, etc.
-    let mut image_paths = Vec::new();
-    let mut i = 1;
-    while let Some(image_path) = query.get(&format!("image{}", i)) {
-        image_paths.push(image_path.clone());
-        i += 1;
-    }
+    let image_paths = collect_image_paths(&query);
     
     if image_paths.is_empty() {
-        return Ok(create_error_response(
-            actix_web::http::StatusCode::BAD_REQUEST,
-            "No image paths provided. Use image1, image2, etc. parameters"
-        ));
+        return Ok(render_problem(&req, &no_images_error()));
     }
     
     let request = CreateVideoRequest {
         video_id: video_id.clone(),
-        output_path: output_path.clone(),
-        image_paths,
-        width: query.get("width").and_then(|w| w.parse().ok()),
-        height: query.get("height").and_then(|h| h.parse().ok()),
-        duration_per_image: query.get("duration").and_then(|d| d.parse().ok()),
+        images: image_paths.into_iter()
+            .map(|path| CreateVideoImage { path, duration: None })
+            .collect(),
+        output: CreateVideoOutput {
+            path: output_path.clone(),
+            width: query.get("width").and_then(|w| w.parse().ok()),
+            height: query.get("height").and_then(|h| h.parse().ok()),
+            duration_per_image: query.get("duration").and_then(|d| d.parse().ok()),
+            profile: query.get("profile").cloned(),
+        },
     };
     
-    match service.create_video(request) {
-        Ok(response) => Ok(HttpResponse::Ok()
-            .content_type("text/plain")
-            .body(format!("Video creation job started.\nJob ID: {}\nStatus: {}\nTotal frames: {}\nEstimated duration: {:.1}s", 
-                response.job_id, response.status, response.total_frames, response.estimated_duration))),
-        Err(e) => Ok(HttpResponse::BadRequest()
-            .content_type("text/plain")
-            .body(format!("Error creating video: {}", e))),
+    run_video_creation(&req, request, service).await
+}
+
+/// Validate a creation request and queue it, shared by the JSON and query-string endpoints.
+/// Answers `202 Accepted` right away with the job to poll in `Location`.
+async fn run_video_creation(
+    req: &HttpRequest,
+    request: CreateVideoRequest,
+    service: web::Data<VideoCreationAppService>,
+) -> Result<HttpResponse> {
+    // Image checks and storing the job touch the file system and the database
+    let result = web::block(move || service.validate_request(&request).and_then(|_| service.create_video(request))).await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    match result {
+        Ok(response) => {
+            let mut http_response = render(req, StatusCode::ACCEPTED, &response);
+            if let Ok(location) = HeaderValue::from_str(&format!("/job/{}", response.job_id)) {
+                http_response.headers_mut().insert(header::LOCATION, location);
+            }
+            Ok(http_response)
+        }
+        Err(e) => Ok(render_problem(req, &e)),
     }
 }
 
+/// Accept bodies declared as JSON (`application/json`, `application/*+json`) or left undeclared
+fn has_json_content_type(req: &HttpRequest) -> bool {
+    let Some(content_type) = req.headers().get(header::CONTENT_TYPE) else {
+        return true;
+    };
+    let media_type = content_type.to_str().unwrap_or_default()
+        .split(';')
+        .next()
+        .unwrap_or_default()
+        .trim()
+        .to_ascii_lowercase();
+    media_type == "application/json" || (media_type.starts_with("application/") && media_type.ends_with("+json"))
+}
+
+/// Deserialize a JSON body, reporting the path of the field that failed (e.g. `images[1].duration`)
+pub fn parse_json_body<T: serde::de::DeserializeOwned>(body: &[u8]) -> DomainResult<T> {
+    let deserializer = &mut serde_json::Deserializer::from_slice(body);
+    serde_path_to_error::deserialize(deserializer).map_err(|e| {
+        let parent = e.path().to_string();
+        let message = e.into_inner().to_string();
+
+        // Missing fields are reported against their parent; name the field itself
+        let named = message.strip_prefix("missing field `")
+            .and_then(|rest| rest.split('`').next());
+        let field = match (parent.as_str(), named) {
+            (".", Some(name)) => name.to_string(),
+            (_, Some(name)) => format!("{}.{}", parent, name),
+            (".", None) => "body".to_string(),
+            (_, None) => parent,
+        };
+        DomainError::validation(field, message)
+    })
+}
+
 /// Handle video creation job status check
 pub async fn handle_get_job_status(
+    req: HttpRequest,
     path: web::Path<String>,
-    config: web::Data<Config>,
+    service: web::Data<VideoCreationAppService>,
 ) -> Result<HttpResponse> {
     let job_id = path.into_inner();
-    let service = VideoCreationAppService::new(config.get_ref().clone());
-    
-    match service.get_job_status(&job_id) {
-        Ok(response) => {
-            let progress_info = if let Some(progress) = &response.progress {
-                format!("\nProgress: {}/{} frames ({:.1}%)", 
-                    progress.current_frame, progress.total_frames, progress.percentage)
-            } else {
-                String::new()
-            };
-            
-            Ok(HttpResponse::Ok()
-                .content_type("text/plain")
-                .body(format!("Job ID: {}\nVideo ID: {}\nStatus: {}{}", 
-                    response.job_id, response.video_id, response.status, progress_info)))
-        },
-        Err(e) => Ok(HttpResponse::NotFound()
-            .content_type("text/plain")
-            .body(format!("Job not found: {}", e))),
+
+    // Job repositories may block on the database
+    let result = web::block(move || service.get_job_status(&job_id)).await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    match result {
+        Ok(response) => Ok(render(&req, StatusCode::OK, &response)),
+        Err(e) => Ok(render_problem(&req, &e)),
+    }
+}
+
+/// Handle the job listing
+/// Example: GET /jobs?status=failed&video_id=intro&since=1700000000&limit=50&cursor=...
+pub async fn handle_list_jobs(
+    req: HttpRequest,
+    query: web::Query<ListJobsRequest>,
+    service: web::Data<VideoCreationAppService>,
+) -> Result<HttpResponse> {
+    let result = web::block(move || service.list_jobs(&query)).await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    match result {
+        Ok(response) => Ok(render(&req, StatusCode::OK, &response)),
+        Err(e) => Ok(render_problem(&req, &e)),
+    }
+}
+
+/// How long an idle job event stream waits before sending a keep-alive comment
+const JOB_EVENTS_KEEP_ALIVE: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Handle a subscription to a job's lifecycle as Server-Sent Events: `started`, `progress`,
+/// `completed`, `failed` and `cancelled`, each carrying the job status. Clients reconnecting
+/// with `Last-Event-ID` only get what they missed; the stream ends after the terminal event.
+/// Example: GET /job/job_123/events
+pub async fn handle_job_events(
+    req: HttpRequest,
+    path: web::Path<String>,
+    service: web::Data<VideoCreationAppService>,
+) -> Result<HttpResponse> {
+    let job_id = VideoCreationJobId::new(path.into_inner());
+    let lookup = {
+        let job_id = job_id.clone();
+        web::block(move || service.job_events(job_id.as_str())).await
+            .map_err(actix_web::error::ErrorInternalServerError)?
+    };
+    let events = match lookup {
+        Ok(events) => events,
+        Err(e) => return Ok(render_problem(&req, &e)),
+    };
+    let last_event_id = req.headers()
+        .get("Last-Event-ID")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|value| value.trim().parse::<u64>().ok())
+        .unwrap_or(0);
+
+    let stream = futures_util::stream::unfold(Some(last_event_id), move |last_event_id| {
+        let events = events.clone();
+        let job_id = job_id.clone();
+        async move {
+            let mut last_event_id = last_event_id?;
+            loop {
+                let changed = events.changed();
+                let pending = events.events_after(&job_id, last_event_id);
+
+                if let Some(last) = pending.last() {
+                    last_event_id = last.id;
+                    let finished = last.kind.is_terminal();
+                    let body: String = pending.iter().map(format_job_event).collect();
+                    let next = if finished { None } else { Some(last_event_id) };
+                    return Some((Ok::<_, Infallible>(web::Bytes::from(body)), next));
+                }
+                // Reconnected after the terminal event: nothing more will come
+                if events.is_finished(&job_id) {
+                    return None;
+                }
+                if tokio::time::timeout(JOB_EVENTS_KEEP_ALIVE, changed).await.is_err() {
+                    return Some((Ok(web::Bytes::from_static(b": keep-alive\n\n")), Some(last_event_id)));
+                }
+            }
+        }
+    });
+
+    Ok(HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header((header::CACHE_CONTROL, "no-cache"))
+        .streaming(stream))
+}
+
+/// Format one job event in the `text/event-stream` wire format
+fn format_job_event(event: &JobEvent) -> String {
+    let data = serde_json::to_string(&VideoCreationAppService::job_status_response(&event.job))
+        .unwrap_or_default();
+    format!("id: {}\nevent: {}\ndata: {}\n\n", event.id, event.kind.as_str(), data)
+}
+
+/// Handle video creation job cancellation
+/// Example: DELETE /job/job_123
+pub async fn handle_cancel_job(
+    req: HttpRequest,
+    path: web::Path<String>,
+    service: web::Data<VideoCreationAppService>,
+) -> Result<HttpResponse> {
+    let job_id = path.into_inner();
+
+    let result = web::block(move || service.cancel_job(&job_id)).await
+        .map_err(actix_web::error::ErrorInternalServerError)?;
+
+    match result {
+        Ok(response) => Ok(render(&req, StatusCode::OK, &response)),
+        Err(e) => Ok(render_problem(&req, &e)),
     }
 }
 
 /// Handle image validation using query parameters
 /// Example: GET /validate-images?image1=img1.jpg&image2=img2.jpg
 pub async fn handle_validate_images(
+    req: HttpRequest,
     query: web::Query<std::collections::HashMap<String, String>>,
-    config: web::Data<Config>,
+    service: web::Data<VideoCreationAppService>,
 ) -> Result<HttpResponse> {
-    let service = VideoCreationAppService::new(config.get_ref().clone());
-    
     // Collect image paths from image1, image2, image3, etc.
-    let mut image_paths = Vec::new();
-    let mut i = 1;
-    while let Some(image_path) = query.get(&format!("image{}", i)) {
-        image_paths.push(image_path.clone());
-        i += 1;
-    }
+    let image_paths = collect_image_paths(&query);
     
     if image_paths.is_empty() {
-        return Ok(create_error_response(
-            actix_web::http::StatusCode::BAD_REQUEST,
-            "No image paths provided. Use image1, image2, etc. parameters"
-        ));
+        return Ok(render_problem(&req, &no_images_error()));
     }
     
-    match service.validate_images(&image_paths) {
-        Ok(_) => Ok(HttpResponse::Ok()
-            .content_type("text/plain")
-            .body(format!("All {} images are valid", image_paths.len()))),
-        Err(e) => Ok(HttpResponse::BadRequest()
-            .content_type("text/plain")
-            .body(format!("Validation failed: {}", e))),
+    // Every image is checked on disk, keep that off the async workers
+    let image_count = image_paths.len();
+    let result = web::block(move || service.validate_images(&image_paths)).await
+        .map_err(|e| DomainError::Io(e.to_string()))
+        .and_then(|result| result);
+
+    match result {
+        Ok(_) => Ok(render(&req, StatusCode::OK, &ValidateImagesResponse {
+            valid: true,
+            image_count,
+            message: format!("All {} images are valid", image_count),
+        })),
+        Err(e) => Ok(render_problem(&req, &e)),
     }
 }
 
 /// Health check endpoint
 pub async fn handle_health_check(
+    req: HttpRequest,
     config: web::Data<Config>,
+    cache: web::Data<BlockCache>,
+    capabilities: Option<web::Data<SharedFFmpegCapabilities>>,
 ) -> Result<HttpResponse> {
-    // Check if FFmpeg is available
-    let ffmpeg_available = crate::infrastructure::ffmpeg::FFmpegVideoCreator::<
-        crate::infrastructure::repositories::InMemoryVideoCreationRepository
-    >::check_ffmpeg_available();
+    // Use the startup probe when there is one, else check if FFmpeg runs
+    let ffmpeg_available = match &capabilities {
+        Some(capabilities) => capabilities.available,
+        None => check_ffmpeg_available(&config.ffmpeg_path),
+    };
     
     // Validate configuration
     let config_valid = config.validate().is_ok();
+    let cache_stats = cache.stats();
     
-    Ok(HttpResponse::Ok()
-        .content_type("text/plain")
-        .body(format!("Video Streaming API - Health Check\nStatus: OK\nFFmpeg Available: {}\nConfig Valid: {}\nDefault Image Spec: {}x{} ({}s per image)\nTimestamp: {:?}", 
-            ffmpeg_available, config_valid, config.default_image_width, config.default_image_height, config.default_duration_per_image, std::time::SystemTime::now())))
-} 
\ No newline at end of file
+    Ok(render(&req, StatusCode::OK, &HealthResponse {
+        status: "OK".to_string(),
+        ffmpeg_available,
+        config_valid,
+        default_image_spec: ImageSpecResponse {
+            width: config.default_image_width,
+            height: config.default_image_height,
+            duration_seconds: config.default_duration_per_image,
+        },
+        block_cache: BlockCacheStatsResponse {
+            hits: cache_stats.hits,
+            misses: cache_stats.misses,
+            evictions: cache_stats.evictions,
+            blocks: cache_stats.blocks,
+            bytes: cache_stats.bytes,
+            capacity: cache_stats.capacity,
+        },
+        ffmpeg: capabilities.map(|capabilities| capabilities_response(&capabilities)),
+        timestamp: unix_seconds(SystemTime::now()),
+    }))
+}
+
+fn capabilities_response(capabilities: &FFmpegCapabilities) -> FFmpegCapabilitiesResponse {
+    FFmpegCapabilitiesResponse {
+        path: capabilities.ffmpeg_path.clone(),
+        version: capabilities.version.clone(),
+        encoder_count: capabilities.encoders.len(),
+        filter_count: capabilities.filters.len(),
+        muxer_count: capabilities.muxers.len(),
+        encoder_profiles: ENCODER_PROFILE_NAMES.iter()
+            .filter_map(|name| EncoderProfile::builtin(name))
+            .map(|profile| {
+                let missing: Vec<String> = capabilities.unsupported(&profile)
+                    .into_iter()
+                    .map(|error| error.message)
+                    .collect();
+                EncoderProfileSupportResponse {
+                    name: profile.name,
+                    supported: capabilities.available && missing.is_empty(),
+                    missing,
+                }
+            })
+            .collect(),
+    }
+}
+
+fn no_images_error() -> DomainError {
+    DomainError::validation("image1", "No image paths provided. Use image1, image2, etc. parameters")
+}
+
+/// Collect image paths from the image1, image2, image3, ... query parameters
+fn collect_image_paths(query: &std::collections::HashMap<String, String>) -> Vec<String> {
+    let mut image_paths = Vec::new();
+    let mut i = 1;
+    while let Some(image_path) = query.get(&format!("image{}", i)) {
+        image_paths.push(image_path.clone());
+        i += 1;
+    }
+    image_paths
+}
@@ -0,0 +1,227 @@
+// Infrastructure layer background job execution
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::Ordering;
+use tokio::sync::{mpsc, Mutex as QueueMutex};
+use crate::domain::video_creation::{VideoCreationJob, VideoCreationJobId, VideoCreationRepository, VideoCreationStatus, VideoCreator};
+use crate::domain::common::{DomainResult, DomainError};
+use crate::infrastructure::events::{JobEventLog, PublishingVideoCreationRepository};
+use crate::infrastructure::ffmpeg::{CancellationFlag, FFmpegVideoCreator, SharedFFmpegCapabilities, DEFAULT_FFMPEG_PATH};
+use crate::infrastructure::repositories::SharedVideoCreationRepository;
+
+/// What startup recovery did with a job it found unfinished
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum JobRecovery {
+    /// A pending job whose queue entry was lost with the previous process
+    Resumed { job_id: VideoCreationJobId },
+    /// An interrupted job queued again for attempt number `attempt`
+    Requeued { job_id: VideoCreationJobId, attempt: u32 },
+    /// An interrupted job that used up its retries
+    Failed { job_id: VideoCreationJobId, reason: String },
+}
+
+/// Pool of tokio workers that run queued video creation jobs with FFmpeg.
+/// Encoding happens on the blocking thread pool, so HTTP workers stay free.
+#[derive(Clone)]
+pub struct VideoCreationWorkerPool {
+    sender: mpsc::UnboundedSender<VideoCreationJob>,
+    repository: SharedVideoCreationRepository,
+    events: JobEventLog,
+    running: RunningJobs,
+    workers: usize,
+}
+
+/// Cancellation flags of the jobs the workers are currently running
+type RunningJobs = Arc<Mutex<HashMap<VideoCreationJobId, CancellationFlag>>>;
+
+impl VideoCreationWorkerPool {
+    /// Spawn `workers` workers on the current tokio runtime, running `ffmpeg` from PATH
+    pub fn start(repository: SharedVideoCreationRepository, workers: usize) -> Self {
+        Self::start_with_ffmpeg(repository, workers, DEFAULT_FFMPEG_PATH)
+    }
+
+    /// Spawn `workers` workers that run the FFmpeg binary at `ffmpeg_path`, checking that it runs before each job
+    pub fn start_with_ffmpeg(repository: SharedVideoCreationRepository, workers: usize, ffmpeg_path: &str) -> Self {
+        Self::spawn(repository, workers, ffmpeg_path.to_string(), None)
+    }
+
+    /// Spawn `workers` workers that run the probed FFmpeg and trust the probe for its availability
+    pub fn start_with_capabilities(
+        repository: SharedVideoCreationRepository,
+        workers: usize,
+        capabilities: SharedFFmpegCapabilities,
+    ) -> Self {
+        Self::spawn(repository, workers, capabilities.ffmpeg_path.clone(), Some(capabilities))
+    }
+
+    /// At least one worker is always started. Every job change written through the pool
+    /// is recorded in its event log.
+    fn spawn(
+        repository: SharedVideoCreationRepository,
+        workers: usize,
+        ffmpeg_path: String,
+        capabilities: Option<SharedFFmpegCapabilities>,
+    ) -> Self {
+        let workers = workers.max(1);
+        let events = JobEventLog::new();
+        let repository: SharedVideoCreationRepository =
+            Arc::new(PublishingVideoCreationRepository::new(repository, events.clone()));
+        let (sender, receiver) = mpsc::unbounded_channel();
+        let receiver = Arc::new(QueueMutex::new(receiver));
+        let running = RunningJobs::default();
+
+        for worker in 0..workers {
+            tokio::spawn(Self::run_worker(
+                worker,
+                receiver.clone(),
+                repository.clone(),
+                running.clone(),
+                ffmpeg_path.clone(),
+                capabilities.clone(),
+            ));
+        }
+
+        Self { sender, repository, events, running, workers }
+    }
+
+    /// Repository the workers record job progress in
+    pub fn repository(&self) -> &SharedVideoCreationRepository {
+        &self.repository
+    }
+
+    /// Lifecycle events of the jobs changed through `repository()`
+    pub fn events(&self) -> &JobEventLog {
+        &self.events
+    }
+
+    pub fn worker_count(&self) -> usize {
+        self.workers
+    }
+
+    /// Store a pending job and queue it for the next free worker
+    pub fn submit(&self, job: VideoCreationJob) -> DomainResult<()> {
+        self.repository.save_job(&job)?;
+        self.sender
+            .send(job)
+            .map_err(|_| DomainError::Conflict("Video creation workers have stopped".to_string()))
+    }
+
+    /// Cancel a queued or running job. A queued job is skipped by the workers; a running
+    /// one has its FFmpeg process killed, its temporary files and partial output removed.
+    pub fn cancel(&self, job_id: &VideoCreationJobId) -> DomainResult<VideoCreationJob> {
+        let mut job = self.repository
+            .find_job_by_id(job_id)?
+            .ok_or_else(|| DomainError::not_found("job", job_id.as_str()))?;
+
+        job.cancel()?;
+
+        // Raise the flag before storing the status, so the running creator stops writing first
+        if let Some(cancellation) = self.running.lock().unwrap().get(job_id) {
+            cancellation.store(true, Ordering::SeqCst);
+        }
+        // The worker may have finished the job since it was read; its outcome stands
+        if !self.repository.update_job_unless_finished(&job)? {
+            return Err(DomainError::Conflict("Job already finished".to_string()));
+        }
+        Ok(job)
+    }
+
+    /// Reconcile jobs left unfinished by a previous process: interrupted jobs lose their partial
+    /// output and are re-queued until they have been retried `max_retries` times, then marked failed.
+    /// Call once at startup, before new jobs are accepted.
+    pub fn recover_interrupted_jobs(&self, max_retries: u32) -> DomainResult<Vec<JobRecovery>> {
+        let mut decisions = Vec::new();
+        // Taken first, so the jobs re-queued below are not queued twice
+        let pending = self.repository.find_jobs_by_status(&VideoCreationStatus::Pending)?;
+
+        for mut job in self.repository.find_jobs_by_status(&VideoCreationStatus::InProgress)? {
+            remove_partial_output(&job);
+
+            let decision = if job.attempts <= max_retries {
+                job.requeue()?;
+                let attempt = job.attempts + 1;
+                self.submit(job.clone())?;
+                JobRecovery::Requeued { job_id: job.id.clone(), attempt }
+            } else {
+                let reason = format!("Interrupted by a server restart; gave up after {} attempts", job.attempts);
+                job.fail(reason.clone())?;
+                self.repository.update_job(&job)?;
+                JobRecovery::Failed { job_id: job.id.clone(), reason }
+            };
+            log_recovery(&decision);
+            decisions.push(decision);
+        }
+
+        for job in pending {
+            let decision = JobRecovery::Resumed { job_id: job.id.clone() };
+            self.submit(job)?;
+            log_recovery(&decision);
+            decisions.push(decision);
+        }
+
+        Ok(decisions)
+    }
+
+    async fn run_worker(
+        worker: usize,
+        receiver: Arc<QueueMutex<mpsc::UnboundedReceiver<VideoCreationJob>>>,
+        repository: SharedVideoCreationRepository,
+        running: RunningJobs,
+        ffmpeg_path: String,
+        capabilities: Option<SharedFFmpegCapabilities>,
+    ) {
+        loop {
+            // Only the idle worker holding the lock waits on the queue
+            let next = receiver.lock().await.recv().await;
+            let Some(job) = next else {
+                break;
+            };
+            let job_id = job.id.clone();
+
+            // Register before looking at the stored status, so a cancel request
+            // arriving from here on always reaches the flag
+            let cancellation = CancellationFlag::default();
+            running.lock().unwrap().insert(job_id.clone(), cancellation.clone());
+
+            let cancelled = matches!(
+                repository.find_job_by_id(&job_id),
+                Ok(Some(stored)) if stored.status == VideoCreationStatus::Cancelled
+            );
+            if !cancelled {
+                let mut creator = FFmpegVideoCreator::new(repository.clone())
+                    .with_ffmpeg_path(ffmpeg_path.clone())
+                    .with_cancellation(cancellation);
+                if let Some(capabilities) = &capabilities {
+                    creator = creator.with_capabilities(capabilities.clone());
+                }
+                match tokio::task::spawn_blocking(move || creator.create_video(job)).await {
+                    Ok(Ok(_)) => {}
+                    Ok(Err(e)) => eprintln!("Worker {}: failed to record job {}: {}", worker, job_id.as_str(), e),
+                    Err(e) => eprintln!("Worker {}: job {} panicked: {}", worker, job_id.as_str(), e),
+                }
+            }
+
+            running.lock().unwrap().remove(&job_id);
+        }
+    }
+}
+
+/// Delete whatever FFmpeg wrote before the job was interrupted
+fn remove_partial_output(job: &VideoCreationJob) {
+    let output_path = job.request.output_path.as_str();
+    match std::fs::remove_file(output_path) {
+        Ok(()) => println!("Recovery: removed partial output {} of job {}", output_path, job.id.as_str()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+        Err(e) => eprintln!("Recovery: failed to remove partial output {} of job {}: {}", output_path, job.id.as_str(), e),
+    }
+}
+
+fn log_recovery(decision: &JobRecovery) {
+    match decision {
+        JobRecovery::Resumed { job_id } => println!("Recovery: re-queued pending job {}", job_id.as_str()),
+        JobRecovery::Requeued { job_id, attempt } => {
+            println!("Recovery: re-queued interrupted job {} (attempt {})", job_id.as_str(), attempt)
+        }
+        JobRecovery::Failed { job_id, reason } => println!("Recovery: marked job {} as failed: {}", job_id.as_str(), reason),
+    }
+}
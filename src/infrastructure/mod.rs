@@ -3,8 +3,20 @@ pub mod http;
 pub mod repositories;
 pub mod services;
 pub mod ffmpeg;
+pub mod cache;
+pub mod probe;
+pub mod responses;
+pub mod jobs;
+pub mod events;
+pub mod sqlite;
 
 pub use http::*;
 pub use repositories::*;
 pub use services::*;
-pub use ffmpeg::*; 
\ No newline at end of file
+pub use ffmpeg::*;
+pub use cache::*;
+pub use probe::*;
+pub use responses::*;
+pub use jobs::*;
+pub use events::*;
+pub use sqlite::*;
@@ -0,0 +1,155 @@
+// Infrastructure layer media probing
+use std::collections::HashMap;
+use std::process::Command;
+use std::sync::{Arc, Mutex};
+use serde_json::Value;
+use crate::domain::common::{DomainResult, DomainError, FilePath};
+use crate::domain::conditional::{Validators, file_inode};
+use crate::domain::media::{MediaInfo, MediaProbe, MediaStream};
+
+/// Media probe shared by all actix workers
+pub type SharedMediaProbe = Arc<dyn MediaProbe + Send + Sync>;
+
+/// ffprobe-based media probe implementation
+pub struct FFprobeMediaProbe {
+    ffprobe_path: String,
+}
+
+impl FFprobeMediaProbe {
+    pub fn new(ffprobe_path: String) -> Self {
+        Self { ffprobe_path }
+    }
+}
+
+impl Default for FFprobeMediaProbe {
+    fn default() -> Self {
+        Self::new("ffprobe".to_string())
+    }
+}
+
+impl MediaProbe for FFprobeMediaProbe {
+    fn probe(&self, file_path: &FilePath) -> DomainResult<MediaInfo> {
+        let output = Command::new(&self.ffprobe_path)
+            .args(["-v", "error", "-print_format", "json", "-show_format", "-show_streams"])
+            .arg(file_path.as_str())
+            .output()
+            .map_err(|e| DomainError::external_tool(&self.ffprobe_path, format!("failed to execute: {}", e)))?;
+
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            return Err(DomainError::external_tool(&self.ffprobe_path, stderr.trim()));
+        }
+
+        parse_ffprobe_output(&String::from_utf8_lossy(&output.stdout))
+    }
+}
+
+/// Parse the JSON printed by `ffprobe -print_format json -show_format -show_streams`
+pub fn parse_ffprobe_output(json: &str) -> DomainResult<MediaInfo> {
+    let root: Value = serde_json::from_str(json)
+        .map_err(|e| DomainError::ProbeFailed(format!("Invalid ffprobe output: {}", e)))?;
+
+    let format = root.get("format");
+    let streams = root.get("streams")
+        .and_then(Value::as_array)
+        .map(|streams| streams.iter().map(parse_stream).collect())
+        .unwrap_or_default();
+
+    Ok(MediaInfo {
+        format: format.and_then(|f| f.get("format_name")).and_then(Value::as_str).map(str::to_string),
+        duration: format.and_then(|f| number_field(f, "duration")),
+        bitrate: format.and_then(|f| number_field(f, "bit_rate")).map(|b| b as u64),
+        timescale: None,
+        streams,
+    })
+}
+
+fn parse_stream(stream: &Value) -> MediaStream {
+    // avg_frame_rate is 0/0 for streams without a constant rate, fall back to r_frame_rate
+    let frame_rate = ["avg_frame_rate", "r_frame_rate"].iter()
+        .filter_map(|field| stream.get(field).and_then(Value::as_str).and_then(parse_rational))
+        .next();
+
+    MediaStream {
+        index: stream.get("index").and_then(Value::as_u64).unwrap_or(0) as u32,
+        kind: stream.get("codec_type").and_then(Value::as_str).unwrap_or("unknown").into(),
+        codec: stream.get("codec_name").and_then(Value::as_str).map(str::to_string),
+        width: stream.get("width").and_then(Value::as_u64).map(|w| w as u32),
+        height: stream.get("height").and_then(Value::as_u64).map(|h| h as u32),
+        frame_rate,
+        bitrate: number_field(stream, "bit_rate").map(|b| b as u64),
+        duration: number_field(stream, "duration"),
+    }
+}
+
+/// ffprobe prints most numbers as strings, accept both forms
+fn number_field(object: &Value, field: &str) -> Option<f64> {
+    let value = object.get(field)?;
+    let number = match value {
+        Value::String(s) => s.trim().parse::<f64>().ok()?,
+        other => other.as_f64()?,
+    };
+    (number.is_finite() && number >= 0.0).then_some(number)
+}
+
+/// Parse a rate such as `30000/1001`; zero denominators mean unknown
+fn parse_rational(value: &str) -> Option<f64> {
+    let (numerator, denominator) = value.split_once('/')?;
+    let numerator = numerator.trim().parse::<f64>().ok()?;
+    let denominator = denominator.trim().parse::<f64>().ok()?;
+    if denominator == 0.0 || numerator == 0.0 {
+        return None;
+    }
+    Some(numerator / denominator)
+}
+
+/// Media probe decorator that remembers results per file version.
+/// Entries are keyed by path and revalidated against the file's ETag,
+/// so a replaced file is probed again.
+pub struct CachedMediaProbe<P>
+where
+    P: MediaProbe,
+{
+    inner: P,
+    entries: Arc<Mutex<HashMap<String, (String, MediaInfo)>>>,
+}
+
+impl<P> CachedMediaProbe<P>
+where
+    P: MediaProbe,
+{
+    pub fn new(inner: P) -> Self {
+        Self {
+            inner,
+            entries: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    fn file_version(file_path: &FilePath) -> DomainResult<String> {
+        let metadata = std::fs::metadata(file_path.as_str())
+            .map_err(|_| DomainError::not_found("file", file_path.as_str()))?;
+        let validators = Validators::from_file_attributes(metadata.len(), metadata.modified().ok(), file_inode(&metadata));
+        Ok(validators.etag.to_string())
+    }
+}
+
+impl<P> MediaProbe for CachedMediaProbe<P>
+where
+    P: MediaProbe,
+{
+    fn probe(&self, file_path: &FilePath) -> DomainResult<MediaInfo> {
+        let version = Self::file_version(file_path)?;
+
+        if let Some((cached_version, info)) = self.entries.lock().unwrap().get(file_path.as_str()) {
+            if *cached_version == version {
+                return Ok(info.clone());
+            }
+        }
+
+        // Probe outside the lock, ffprobe may take a while on large files
+        let info = self.inner.probe(file_path)?;
+        self.entries.lock().unwrap()
+            .insert(file_path.as_str().to_string(), (version, info.clone()));
+        Ok(info)
+    }
+}
@@ -1,10 +1,16 @@
 // Infrastructure layer repository implementations
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::sync::{Mutex, Arc};
-use crate::domain::video::{Video, VideoId, VideoRepository};
+use crate::domain::video::{Video, VideoId, VideoMetadata, VideoRepository};
+use crate::domain::conditional::file_inode;
 use crate::domain::streaming::{StreamingSession, SessionId, SessionRepository, SessionState};
-use crate::domain::video_creation::{VideoCreationJob, VideoCreationJobId, VideoCreationRepository, VideoCreationStatus};
-use crate::domain::common::DomainResult;
+use crate::domain::video_creation::{
+    VideoCreationJob, VideoCreationJobCursor, VideoCreationJobId, VideoCreationJobQuery, VideoCreationRepository, VideoCreationStatus
+};
+use crate::domain::common::{ContentType, DomainResult, DomainError, FilePath};
+use crate::infrastructure::sqlite::{SqliteDatabase, SqliteSessionRepository, SqliteVideoCreationRepository, SqliteVideoRepository};
+use crate::shared::config::{Config, StorageBackend};
 
 /// In-memory video repository implementation
 pub struct InMemoryVideoRepository {
@@ -19,6 +25,12 @@ impl InMemoryVideoRepository {
     }
 }
 
+impl Default for InMemoryVideoRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl VideoRepository for InMemoryVideoRepository {
     fn find_by_id(&self, id: &VideoId) -> DomainResult<Option<Video>> {
         let videos = self.videos.lock().unwrap();
@@ -38,6 +50,234 @@ impl VideoRepository for InMemoryVideoRepository {
     }
 }
 
+/// Video repository holding only the configured file (`VIDEO_PATH`), served by the legacy `/stream` endpoint.
+/// The file is registered under its path and keeps the configured content type.
+#[derive(Clone)]
+pub struct SingleFileVideoRepository {
+    video_id: VideoId,
+    file_path: FilePath,
+    content_type: ContentType,
+}
+
+impl SingleFileVideoRepository {
+    pub fn new(file_path: impl Into<String>, content_type: impl Into<String>) -> DomainResult<Self> {
+        let file_path = file_path.into();
+        Ok(Self {
+            video_id: VideoId::new(file_path.clone()),
+            file_path: FilePath::new(file_path),
+            content_type: ContentType::new(content_type.into())?,
+        })
+    }
+
+    pub fn from_config(config: &Config) -> DomainResult<Self> {
+        Self::new(config.video_path.clone(), config.content_type.clone())
+    }
+
+    pub fn video_id(&self) -> &VideoId {
+        &self.video_id
+    }
+}
+
+impl VideoRepository for SingleFileVideoRepository {
+    fn find_by_id(&self, id: &VideoId) -> DomainResult<Option<Video>> {
+        if id != &self.video_id {
+            return Ok(None);
+        }
+
+        // Read the file's attributes on every lookup, so validators follow replacements
+        let attributes = std::fs::metadata(self.file_path.as_str()).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => DomainError::not_found("file", self.file_path.as_str()),
+            _ => DomainError::Io(e.to_string()),
+        })?;
+        let mut metadata = VideoMetadata::new(attributes.len(), self.content_type.clone());
+        metadata.last_modified = attributes.modified().ok();
+        metadata.inode = file_inode(&attributes);
+
+        Ok(Some(Video {
+            id: self.video_id.clone(),
+            metadata,
+            file_path: self.file_path.clone(),
+        }))
+    }
+
+    fn save(&self, _video: &Video) -> DomainResult<()> {
+        Err(DomainError::Conflict("the configured video cannot be replaced".to_string()))
+    }
+
+    fn delete(&self, _id: &VideoId) -> DomainResult<()> {
+        Err(DomainError::Conflict("the configured video cannot be deleted".to_string()))
+    }
+}
+
+/// Directory-backed video repository that registers every video under a media root
+#[derive(Clone)]
+pub struct DirectoryVideoRepository {
+    root: PathBuf,
+    videos: Arc<Mutex<HashMap<VideoId, Video>>>,
+}
+
+impl DirectoryVideoRepository {
+    /// Create an empty catalog for the given media root
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            videos: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Scan the media root and register each supported video by its file stem
+    pub fn scan(root: impl Into<PathBuf>) -> DomainResult<Self> {
+        let repository = Self::new(root);
+        repository.rescan()?;
+        Ok(repository)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Re-read the media root, replacing the registered catalog
+    pub fn rescan(&self) -> DomainResult<()> {
+        if !self.root.is_dir() {
+            return Err(DomainError::not_found("media root", self.root.to_string_lossy()));
+        }
+
+        let mut files = Vec::new();
+        let root = self.root.canonicalize().map_err(|e| DomainError::Io(e.to_string()))?;
+        Self::collect_files(&self.root, &root, &mut HashSet::new(), &mut files)
+            .map_err(|e| DomainError::Io(e.to_string()))?;
+        // Sort so that id collisions resolve the same way on every start
+        files.sort();
+
+        let mut catalog = HashMap::new();
+        for file in files {
+            let stem = match file.file_stem().and_then(|s| s.to_str()) {
+                Some(stem) => stem.to_string(),
+                None => continue,
+            };
+            let video_id = VideoId::new(stem);
+
+            if catalog.contains_key(&video_id) {
+                eprintln!("Warning: Skipping {} (video id '{}' already registered)", file.display(), video_id.as_str());
+                continue;
+            }
+
+            let file_path = FilePath::new(file.to_string_lossy().to_string());
+            match Video::new(video_id.clone(), file_path) {
+                Ok(video) => {
+                    catalog.insert(video_id, video);
+                }
+                // Files that are not videos (README, thumbnails, ...) are simply not served
+                Err(DomainError::Unsupported(_)) => continue,
+                Err(e) => eprintln!("Warning: Skipping {}: {}", file.display(), e),
+            }
+        }
+
+        *self.videos.lock().unwrap() = catalog;
+        Ok(())
+    }
+
+    pub fn list_ids(&self) -> Vec<VideoId> {
+        let videos = self.videos.lock().unwrap();
+        let mut ids: Vec<VideoId> = videos.keys().cloned().collect();
+        ids.sort_by(|a, b| a.as_str().cmp(b.as_str()));
+        ids
+    }
+
+    /// Collect the files below `dir`, following directory symlinks once each.
+    /// Symlinks leading outside the canonical media `root` are skipped, so the catalog
+    /// never publishes other parts of the file system. Subdirectories that cannot be
+    /// read are logged and skipped.
+    fn collect_files(dir: &Path, root: &Path, visited: &mut HashSet<PathBuf>, files: &mut Vec<PathBuf>) -> std::io::Result<()> {
+        let canonical = dir.canonicalize()?;
+        if !canonical.starts_with(root) {
+            eprintln!("Warning: Skipping directory {} (outside the media root)", dir.display());
+            return Ok(());
+        }
+        // A symlink back up the tree leads to a directory already visited
+        if !visited.insert(canonical) {
+            return Ok(());
+        }
+
+        for entry in std::fs::read_dir(dir)?.flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                if let Err(e) = Self::collect_files(&path, root, visited, files) {
+                    eprintln!("Warning: Skipping directory {}: {}", path.display(), e);
+                }
+            } else if path.is_file() {
+                match path.canonicalize() {
+                    Ok(target) if target.starts_with(root) => files.push(path),
+                    _ => eprintln!("Warning: Skipping {} (outside the media root)", path.display()),
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl VideoRepository for DirectoryVideoRepository {
+    fn find_by_id(&self, id: &VideoId) -> DomainResult<Option<Video>> {
+        let file_path = match self.videos.lock().unwrap().get(id) {
+            Some(video) => video.file_path.clone(),
+            None => return Ok(None),
+        };
+
+        // Re-read metadata so size and validators follow files replaced since the scan
+        match Video::new(id.clone(), file_path) {
+            Ok(video) => Ok(Some(video)),
+            Err(DomainError::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn save(&self, video: &Video) -> DomainResult<()> {
+        let mut videos = self.videos.lock().unwrap();
+        videos.insert(video.id.clone(), video.clone());
+        Ok(())
+    }
+
+    fn delete(&self, id: &VideoId) -> DomainResult<()> {
+        let mut videos = self.videos.lock().unwrap();
+        videos.remove(id);
+        Ok(())
+    }
+}
+
+/// Video catalog shared by the streaming and metadata handlers
+pub type SharedVideoRepository = Arc<dyn VideoRepository + Send + Sync>;
+
+/// Video catalog for the configured storage backend, holding the videos found by `catalog`'s scan.
+/// SQLite keeps the id-to-file mapping, so videos registered by an earlier start stay servable.
+pub fn video_repository(config: &Config, catalog: &DirectoryVideoRepository) -> DomainResult<SharedVideoRepository> {
+    match config.storage_backend {
+        StorageBackend::Memory => Ok(Arc::new(catalog.clone())),
+        StorageBackend::Sqlite => {
+            let repository = SqliteVideoRepository::new(SqliteDatabase::open(&config.database_path)?);
+            for id in catalog.list_ids() {
+                if let Some(video) = catalog.find_by_id(&id)? {
+                    repository.save(&video)?;
+                }
+            }
+            Ok(Arc::new(repository))
+        }
+    }
+}
+
+/// Streaming session repository shared by the HTTP handlers
+pub type SharedSessionRepository = Arc<dyn SessionRepository + Send + Sync>;
+
+/// Session repository for the configured storage backend
+pub fn session_repository(config: &Config) -> DomainResult<SharedSessionRepository> {
+    match config.storage_backend {
+        StorageBackend::Memory => Ok(Arc::new(InMemorySessionRepository::new())),
+        StorageBackend::Sqlite => {
+            let database = SqliteDatabase::open(&config.database_path)?;
+            Ok(Arc::new(SqliteSessionRepository::new(database)))
+        }
+    }
+}
+
 /// In-memory session repository implementation
 pub struct InMemorySessionRepository {
     sessions: Mutex<HashMap<SessionId, StreamingSession>>,
@@ -51,6 +291,12 @@ impl InMemorySessionRepository {
     }
 }
 
+impl Default for InMemorySessionRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl SessionRepository for InMemorySessionRepository {
     fn find_by_id(&self, id: &SessionId) -> DomainResult<Option<StreamingSession>> {
         let sessions = self.sessions.lock().unwrap();
@@ -79,6 +325,20 @@ impl SessionRepository for InMemorySessionRepository {
     }
 }
 
+/// Job repository shared by the HTTP handlers and the background workers
+pub type SharedVideoCreationRepository = Arc<dyn VideoCreationRepository + Send + Sync>;
+
+/// Job repository for the configured storage backend
+pub fn video_creation_repository(config: &Config) -> DomainResult<SharedVideoCreationRepository> {
+    match config.storage_backend {
+        StorageBackend::Memory => Ok(Arc::new(InMemoryVideoCreationRepository::new())),
+        StorageBackend::Sqlite => {
+            let database = SqliteDatabase::open(&config.database_path)?;
+            Ok(Arc::new(SqliteVideoCreationRepository::new(database)))
+        }
+    }
+}
+
 /// In-memory video creation job repository implementation
 #[derive(Clone)]
 pub struct InMemoryVideoCreationRepository {
@@ -93,6 +353,12 @@ impl InMemoryVideoCreationRepository {
     }
 }
 
+impl Default for InMemoryVideoCreationRepository {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl VideoCreationRepository for InMemoryVideoCreationRepository {
     fn save_job(&self, job: &VideoCreationJob) -> DomainResult<()> {
         let mut jobs = self.jobs.lock().unwrap();
@@ -114,9 +380,31 @@ impl VideoCreationRepository for InMemoryVideoCreationRepository {
         Ok(filtered_jobs)
     }
 
+    fn query_jobs(&self, query: &VideoCreationJobQuery) -> DomainResult<Vec<VideoCreationJob>> {
+        let jobs = self.jobs.lock().unwrap();
+        let mut matching: Vec<VideoCreationJob> = jobs.values()
+            .filter(|j| query.matches(j))
+            .cloned()
+            .collect();
+        matching.sort_by_cached_key(VideoCreationJobCursor::for_job);
+        matching.truncate(query.limit);
+        Ok(matching)
+    }
+
     fn update_job(&self, job: &VideoCreationJob) -> DomainResult<()> {
         let mut jobs = self.jobs.lock().unwrap();
         jobs.insert(job.id.clone(), job.clone());
         Ok(())
     }
+
+    fn update_job_unless_finished(&self, job: &VideoCreationJob) -> DomainResult<bool> {
+        let mut jobs = self.jobs.lock().unwrap();
+        match jobs.get_mut(&job.id) {
+            Some(stored) if !stored.status.is_terminal() => {
+                *stored = job.clone();
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
 } 
\ No newline at end of file
@@ -0,0 +1,313 @@
+// Infrastructure layer response rendering and content negotiation
+use actix_web::{HttpRequest, HttpResponse, HttpResponseBuilder, ResponseError};
+use actix_web::http::{header, StatusCode};
+use serde::Serialize;
+use crate::application::dto::{
+    CreateVideoResponse, FieldErrorResponse, HealthResponse, ProblemDetails, ValidateImagesResponse,
+    VideoCreationJobListResponse, VideoCreationJobStatusResponse, VideoMetadataResponse,
+};
+use crate::domain::common::DomainError;
+
+/// Media type of RFC 9457 problem details
+pub const PROBLEM_JSON: &str = "application/problem+json";
+
+/// Longest summary of an external tool's stderr included in a problem body
+const MAX_SUMMARY_LENGTH: usize = 200;
+
+/// Representation chosen for an API response
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseFormat {
+    Json,
+    PlainText,
+}
+
+impl ResponseFormat {
+    /// Pick the representation from the Accept header.
+    /// JSON is the default; plain text is used when the client prefers it.
+    pub fn from_request(req: &HttpRequest) -> Self {
+        match req.headers().get(header::ACCEPT).and_then(|value| value.to_str().ok()) {
+            Some(accept) => Self::from_accept(accept),
+            None => ResponseFormat::Json,
+        }
+    }
+
+    pub fn from_accept(accept: &str) -> Self {
+        let json = Self::quality(accept, "application", "json");
+        let text = Self::quality(accept, "text", "plain");
+
+        if text > json {
+            ResponseFormat::PlainText
+        } else {
+            ResponseFormat::Json
+        }
+    }
+
+    /// Quality the Accept header gives a media type; the most specific matching range wins
+    fn quality(accept: &str, kind: &str, subtype: &str) -> f32 {
+        let mut best: Option<(u8, f32)> = None;
+
+        for range in accept.split(',') {
+            let mut params = range.split(';').map(str::trim);
+            let Some((range_kind, range_subtype)) = params.next().and_then(|media| media.split_once('/')) else {
+                continue;
+            };
+
+            let specificity = match (range_kind, range_subtype) {
+                (k, s) if k.eq_ignore_ascii_case(kind) && s.eq_ignore_ascii_case(subtype) => 3,
+                (k, "*") if k.eq_ignore_ascii_case(kind) => 2,
+                ("*", "*") => 1,
+                _ => continue,
+            };
+            let quality = params
+                .filter_map(|param| param.split_once('='))
+                .find(|(name, _)| name.trim().eq_ignore_ascii_case("q"))
+                .and_then(|(_, value)| value.trim().parse::<f32>().ok())
+                .unwrap_or(1.0)
+                .clamp(0.0, 1.0);
+
+            if best.is_none_or(|(current, _)| specificity > current) {
+                best = Some((specificity, quality));
+            }
+        }
+
+        best.map_or(0.0, |(_, quality)| quality)
+    }
+}
+
+/// Plain-text rendering of a response DTO, kept for scripts that read the old format
+pub trait PlainText {
+    fn to_plain_text(&self) -> String;
+}
+
+/// Render a DTO as JSON or plain text, depending on what the client accepts
+pub fn render<T>(req: &HttpRequest, status: StatusCode, body: &T) -> HttpResponse
+where
+    T: Serialize + PlainText,
+{
+    match ResponseFormat::from_request(req) {
+        ResponseFormat::Json => HttpResponse::build(status).json(body),
+        ResponseFormat::PlainText => HttpResponse::build(status)
+            .content_type("text/plain")
+            .body(body.to_plain_text()),
+    }
+}
+
+/// Render a domain error as problem details, or as plain text when the client prefers it
+pub fn render_problem(req: &HttpRequest, error: &DomainError) -> HttpResponse {
+    let problem = problem_details(error, Some(req.path()));
+    let mut builder = problem_response_builder(error);
+
+    match ResponseFormat::from_request(req) {
+        ResponseFormat::Json => builder.content_type(PROBLEM_JSON).json(problem),
+        ResponseFormat::PlainText => builder.content_type("text/plain").body(problem.to_plain_text()),
+    }
+}
+
+/// Problem details (RFC 9457) describing a domain error
+pub fn problem_details(error: &DomainError, instance: Option<&str>) -> ProblemDetails {
+    let status = error.status_code();
+    let mut problem = ProblemDetails {
+        problem_type: format!("/problems/{}", error.code().replace('_', "-")),
+        title: problem_title(error).to_string(),
+        status: status.as_u16(),
+        detail: error.to_string(),
+        instance: instance.map(str::to_string),
+        code: error.code().to_string(),
+        entity: None,
+        errors: Vec::new(),
+        tool: None,
+        summary: None,
+    };
+
+    match error {
+        DomainError::NotFound { entity, .. } => problem.entity = Some(entity.to_string()),
+        DomainError::Validation(errors) => {
+            problem.detail = "One or more fields are invalid".to_string();
+            problem.errors = errors.iter().cloned().map(FieldErrorResponse::from).collect();
+        }
+        DomainError::ExternalTool { tool, stderr } => {
+            // The full output names server paths and build details, so it only goes to the log
+            eprintln!("{} failed: {}", tool, stderr.trim());
+            problem.detail = format!("{} exited with an error", tool);
+            problem.tool = Some(tool.clone());
+            problem.summary = Some(stderr_summary(stderr));
+        }
+        _ => {}
+    }
+    problem
+}
+
+fn problem_title(error: &DomainError) -> &'static str {
+    match error {
+        DomainError::NotFound { .. } => "Not Found",
+        DomainError::Validation(_) => "Validation Failed",
+        DomainError::Conflict(_) => "Conflict",
+        DomainError::Unsupported(_) => "Unsupported",
+        DomainError::UnsupportedMediaType(_) => "Unsupported Media Type",
+        DomainError::ExternalTool { .. } => "External Tool Failed",
+        DomainError::Io(_) => "I/O Error",
+        DomainError::InvalidRange => "Invalid Range",
+        DomainError::RangeNotSatisfiable(_) => "Range Not Satisfiable",
+        DomainError::ProbeFailed(_) => "Media Probe Failed",
+    }
+}
+
+/// Client-safe summary of an external tool's stderr: the last line, where the actual
+/// error usually is, with absolute paths replaced by `<path>`
+fn stderr_summary(stderr: &str) -> String {
+    let line = stderr.lines().map(str::trim).rfind(|line| !line.is_empty()).unwrap_or_default();
+    let summary = line.split_whitespace()
+        .map(|word| {
+            if !word.trim_start_matches(['\'', '"']).starts_with('/') {
+                word
+            } else if word.ends_with(':') {
+                "<path>:"
+            } else {
+                "<path>"
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ");
+    summary.chars().take(MAX_SUMMARY_LENGTH).collect()
+}
+
+/// Status and headers every representation of an error carries
+fn problem_response_builder(error: &DomainError) -> HttpResponseBuilder {
+    let mut builder = HttpResponse::build(error.status_code());
+    if let DomainError::RangeNotSatisfiable(total_size) = error {
+        // RFC 9110, section 15.5.17: advertise the current size
+        builder
+            .append_header((header::CONTENT_RANGE, format!("bytes */{}", total_size)))
+            .append_header((header::ACCEPT_RANGES, "bytes"));
+    }
+    builder
+}
+
+impl ResponseError for DomainError {
+    fn status_code(&self) -> StatusCode {
+        match self {
+            DomainError::NotFound { .. } => StatusCode::NOT_FOUND,
+            DomainError::Validation(_) | DomainError::ProbeFailed(_) | DomainError::Unsupported(_) => {
+                StatusCode::UNPROCESSABLE_ENTITY
+            }
+            DomainError::Conflict(_) => StatusCode::CONFLICT,
+            DomainError::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            DomainError::ExternalTool { .. } => StatusCode::BAD_GATEWAY,
+            DomainError::Io(_) => StatusCode::INTERNAL_SERVER_ERROR,
+            DomainError::InvalidRange | DomainError::RangeNotSatisfiable(_) => StatusCode::RANGE_NOT_SATISFIABLE,
+        }
+    }
+
+    fn error_response(&self) -> HttpResponse {
+        problem_response_builder(self)
+            .content_type(PROBLEM_JSON)
+            .json(problem_details(self, None))
+    }
+}
+
+impl PlainText for ProblemDetails {
+    fn to_plain_text(&self) -> String {
+        let fields: String = self.errors.iter()
+            .map(|error| format!("\n  {}: {}", error.field, error.message))
+            .collect();
+        let summary = self.summary.as_ref()
+            .map(|summary| format!("\n{}", summary))
+            .unwrap_or_default();
+        format!("{}{}{}", self.detail, fields, summary)
+    }
+}
+
+impl PlainText for CreateVideoResponse {
+    fn to_plain_text(&self) -> String {
+        format!("Video creation job started.\nJob ID: {}\nStatus: {}\nTotal frames: {}\nEstimated duration: {:.1}s",
+            self.job_id, self.status, self.total_frames, self.estimated_duration)
+    }
+}
+
+impl PlainText for VideoCreationJobStatusResponse {
+    fn to_plain_text(&self) -> String {
+        let progress_info = if let Some(progress) = &self.progress {
+            format!("\nProgress: {}/{} frames ({:.1}%)",
+                progress.current_frame, progress.total_frames, progress.percentage)
+        } else {
+            String::new()
+        };
+
+        format!("Job ID: {}\nVideo ID: {}\nStatus: {}{}",
+            self.job_id, self.video_id, self.status, progress_info)
+    }
+}
+
+impl PlainText for VideoCreationJobListResponse {
+    fn to_plain_text(&self) -> String {
+        let mut lines: Vec<String> = self.jobs.iter()
+            .map(|job| match job.percentage {
+                Some(percentage) => format!("{}\t{}\t{}\t{:.1}%", job.job_id, job.video_id, job.status, percentage),
+                None => format!("{}\t{}\t{}", job.job_id, job.video_id, job.status),
+            })
+            .collect();
+        if let Some(cursor) = &self.next_cursor {
+            lines.push(format!("Next cursor: {}", cursor));
+        }
+        lines.join("\n")
+    }
+}
+
+impl PlainText for ValidateImagesResponse {
+    fn to_plain_text(&self) -> String {
+        self.message.clone()
+    }
+}
+
+impl PlainText for HealthResponse {
+    fn to_plain_text(&self) -> String {
+        let spec = &self.default_image_spec;
+        let cache = &self.block_cache;
+        let ffmpeg = self.ffmpeg.as_ref()
+            .map(|ffmpeg| {
+                let profiles: Vec<String> = ffmpeg.encoder_profiles.iter()
+                    .map(|profile| format!("{} ({})", profile.name, if profile.supported { "supported" } else { "unsupported" }))
+                    .collect();
+                format!("\nFFmpeg: {} {} ({} encoders, {} filters, {} muxers)\nEncoder Profiles: {}",
+                    ffmpeg.path, ffmpeg.version.as_deref().unwrap_or("unknown version"),
+                    ffmpeg.encoder_count, ffmpeg.filter_count, ffmpeg.muxer_count,
+                    profiles.join(", "))
+            })
+            .unwrap_or_default();
+        format!("Video Streaming API - Health Check\nStatus: {}\nFFmpeg Available: {}{}\nConfig Valid: {}\nDefault Image Spec: {}x{} ({}s per image)\nBlock Cache: {} hits, {} misses, {} evictions, {} blocks ({}/{} bytes)\nTimestamp: {}",
+            self.status, self.ffmpeg_available, ffmpeg, self.config_valid,
+            spec.width, spec.height, spec.duration_seconds,
+            cache.hits, cache.misses, cache.evictions, cache.blocks, cache.bytes, cache.capacity,
+            self.timestamp)
+    }
+}
+
+impl PlainText for VideoMetadataResponse {
+    fn to_plain_text(&self) -> String {
+        let optional = |value: Option<String>| value.unwrap_or_else(|| "unknown".to_string());
+        let streams: String = self.streams.iter()
+            .map(|stream| format!("\n  #{} {} {}{}{}",
+                stream.index,
+                stream.kind,
+                stream.codec.as_deref().unwrap_or("unknown"),
+                match (stream.width, stream.height) {
+                    (Some(width), Some(height)) => format!(" {}x{}", width, height),
+                    _ => String::new(),
+                },
+                stream.frame_rate.map(|fps| format!(" {:.3} fps", fps)).unwrap_or_default()))
+            .collect();
+
+        format!("Video ID: {}\nSize: {} bytes\nContent Type: {}\nDuration: {}\nBitrate: {}\nResolution: {}\nVideo Codec: {}\nAudio Codec: {}\nFrame Rate: {}\nStreams: {}{}",
+            self.video_id,
+            self.total_size,
+            self.content_type,
+            optional(self.duration.map(|d| format!("{:.3}s", d))),
+            optional(self.bitrate.map(|b| format!("{} bps", b))),
+            optional(self.width.zip(self.height).map(|(w, h)| format!("{}x{}", w, h))),
+            optional(self.video_codec.clone()),
+            optional(self.audio_codec.clone()),
+            optional(self.frame_rate.map(|fps| format!("{:.3} fps", fps))),
+            self.streams.len(),
+            streams)
+    }
+}
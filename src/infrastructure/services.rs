@@ -1,42 +1,196 @@
 // Infrastructure layer service implementations
-use std::fs::File;
-use std::io::{BufReader, Read, Seek, SeekFrom};
-use crate::domain::video::{Video, VideoStreamingService, VideoChunk, VideoMetadata};
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+use bytes::BytesMut;
+use tokio::io::{AsyncReadExt, AsyncSeekExt};
+use crate::domain::video::{Video, VideoStreamingService, VideoMetadata, VideoByteStream};
 use crate::domain::common::{DomainResult, DomainError, ByteRange};
+use crate::infrastructure::cache::{BlockCache, BlockKey};
+
+/// Size of the buffers a streamed range is read in
+pub const DEFAULT_STREAM_BUFFER_SIZE: usize = 64 * 1024;
 
 /// File-based video streaming service implementation
-pub struct FileVideoStreamingService;
+pub struct FileVideoStreamingService {
+    buffer_size: usize,
+    cache: Option<BlockCache>,
+}
 
 impl FileVideoStreamingService {
     pub fn new() -> Self {
-        Self
+        Self::with_buffer_size(DEFAULT_STREAM_BUFFER_SIZE)
+    }
+
+    pub fn with_buffer_size(buffer_size: usize) -> Self {
+        Self {
+            buffer_size: buffer_size.max(1),
+            cache: None,
+        }
+    }
+
+    /// Serve ranges through a shared block cache
+    pub fn with_cache(mut self, cache: BlockCache) -> Self {
+        self.cache = Some(cache).filter(BlockCache::is_enabled);
+        self
+    }
+}
+
+impl Default for FileVideoStreamingService {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
 impl VideoStreamingService for FileVideoStreamingService {
-    fn read_chunk(&self, video: &Video, range: &ByteRange) -> DomainResult<VideoChunk> {
-        let file = File::open(video.file_path.as_str())
-            .map_err(|e| DomainError::IoError(e.to_string()))?;
-        
-        let mut reader = BufReader::new(file);
-        
-        // Seek to start position
-        reader.seek(SeekFrom::Start(range.start))
-            .map_err(|e| DomainError::IoError(e.to_string()))?;
-        
-        // Calculate chunk size
-        let chunk_size = range.size() as usize;
-        
-        // Read the chunk
-        let mut buffer = vec![0u8; chunk_size];
-        let bytes_read = reader.take(chunk_size as u64).read(&mut buffer)
-            .map_err(|e| DomainError::IoError(e.to_string()))?;
-        buffer.truncate(bytes_read);
-        
-        Ok(VideoChunk::new(video.id.clone(), range.clone(), buffer))
+    fn open_stream(&self, video: &Video, range: &ByteRange) -> DomainResult<VideoByteStream> {
+        match &self.cache {
+            Some(cache) => Ok(cached_stream_file_range(
+                cache.clone(),
+                video.id.as_str(),
+                &video.metadata.validators().etag.to_string(),
+                video.file_path.as_str(),
+                range.start,
+                range.size(),
+            )),
+            None => Ok(stream_file_range(video.file_path.as_str(), range.start, range.size(), self.buffer_size)),
+        }
     }
 
     fn get_metadata(&self, video: &Video) -> DomainResult<VideoMetadata> {
         Ok(video.metadata.clone())
     }
-} 
\ No newline at end of file
+}
+
+/// Stream `length` bytes of a file starting at `start`, using tokio file I/O.
+/// The file is opened on first poll and at most `buffer_size` bytes are held per read,
+/// so memory stays bounded and the consumer's polling provides backpressure.
+pub fn stream_file_range(path: impl Into<PathBuf>, start: u64, length: u64, buffer_size: usize) -> VideoByteStream {
+    let path = path.into();
+    let buffer_size = buffer_size.max(1) as u64;
+
+    VideoByteStream::new(futures_util::stream::unfold(
+        (None::<tokio::fs::File>, length),
+        move |(file, remaining)| {
+            let path = path.clone();
+            async move {
+                if remaining == 0 {
+                    return None;
+                }
+
+                let mut file = match file {
+                    Some(file) => file,
+                    None => match open_at(&path, start).await {
+                        Ok(file) => file,
+                        Err(e) => return Some((Err(e), (None, 0))),
+                    },
+                };
+
+                let to_read = remaining.min(buffer_size);
+                let mut buffer = BytesMut::with_capacity(to_read as usize);
+                match (&mut file).take(to_read).read_buf(&mut buffer).await {
+                    // The file shrank underneath us: fail rather than send a short body
+                    Ok(0) => Some((
+                        Err(DomainError::Io(format!("Unexpected end of file in {}", path.display()))),
+                        (None, 0),
+                    )),
+                    Ok(read) => Some((Ok(buffer.freeze()), (Some(file), remaining - read as u64))),
+                    Err(e) => Some((Err(DomainError::Io(e.to_string())), (None, 0))),
+                }
+            }
+        },
+    ))
+}
+
+/// Stream `length` bytes of a file starting at `start` through the block cache.
+/// The range is served as slices of aligned blocks; missing blocks are read whole
+/// from the file and stored, so the same bytes of later requests come from memory.
+pub fn cached_stream_file_range(
+    cache: BlockCache,
+    video_id: &str,
+    etag: &str,
+    path: impl Into<PathBuf>,
+    start: u64,
+    length: u64,
+) -> VideoByteStream {
+    let path = path.into();
+    let video_id = video_id.to_string();
+    let etag = etag.to_string();
+    let end = start + length;
+
+    VideoByteStream::new(futures_util::stream::unfold(
+        (None::<tokio::fs::File>, start),
+        move |(file, offset)| {
+            let cache = cache.clone();
+            let path = path.clone();
+            let key = BlockKey::new(&video_id, &etag, offset / cache.block_size());
+            async move {
+                if offset >= end {
+                    return None;
+                }
+
+                let block_size = cache.block_size();
+                let block_start = key.index * block_size;
+                let (block, file) = match cache.get(&key) {
+                    Some(block) => (block, file),
+                    None => match read_block(file, &path, block_start, block_size).await {
+                        Ok((block, file)) => {
+                            cache.insert(key, block.clone());
+                            (block, Some(file))
+                        }
+                        Err(e) => return Some((Err(e), (None, end))),
+                    },
+                };
+
+                let from = (offset - block_start) as usize;
+                let to = (end - block_start).min(block.len() as u64) as usize;
+                if from >= to {
+                    // The file shrank underneath us: fail rather than send a short body
+                    return Some((
+                        Err(DomainError::Io(format!("Unexpected end of file in {}", path.display()))),
+                        (None, end),
+                    ));
+                }
+
+                let next = block_start + to as u64;
+                Some((Ok(block.slice(from..to)), (file, next)))
+            }
+        },
+    ))
+}
+
+/// Read one whole block, reusing an already open file when there is one
+async fn read_block(
+    file: Option<tokio::fs::File>,
+    path: &Path,
+    block_start: u64,
+    block_size: u64,
+) -> DomainResult<(bytes::Bytes, tokio::fs::File)> {
+    let mut file = match file {
+        Some(mut file) => {
+            file.seek(SeekFrom::Start(block_start)).await
+                .map_err(|e| DomainError::Io(e.to_string()))?;
+            file
+        }
+        None => open_at(path, block_start).await?,
+    };
+
+    let mut buffer = BytesMut::with_capacity(block_size as usize);
+    while (buffer.len() as u64) < block_size {
+        let remaining = block_size - buffer.len() as u64;
+        let read = (&mut file).take(remaining).read_buf(&mut buffer).await
+            .map_err(|e| DomainError::Io(e.to_string()))?;
+        if read == 0 {
+            break;
+        }
+    }
+
+    Ok((buffer.freeze(), file))
+}
+
+async fn open_at(path: &Path, start: u64) -> DomainResult<tokio::fs::File> {
+    let mut file = tokio::fs::File::open(path).await
+        .map_err(|e| DomainError::Io(e.to_string()))?;
+    file.seek(SeekFrom::Start(start)).await
+        .map_err(|e| DomainError::Io(e.to_string()))?;
+    Ok(file)
+}
@@ -0,0 +1,486 @@
+// Infrastructure layer SQLite persistence
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use rusqlite::types::Type;
+use crate::domain::common::{DomainResult, DomainError, FilePath};
+use crate::domain::video::{Video, VideoId, VideoRepository};
+use crate::domain::streaming::{ClientInfo, SessionId, SessionMetrics, SessionRepository, SessionState, StreamingSession};
+use crate::domain::video_creation::{
+    EncoderProfile, ImageSpec, VideoCreationJob, VideoCreationJobId, VideoCreationJobQuery, VideoCreationProgress, VideoCreationRepository,
+    VideoCreationRequest, VideoCreationStatus,
+};
+
+/// Embedded schema migrations, applied in order; `PRAGMA user_version` counts the applied ones
+const MIGRATIONS: &[&str] = &[
+    // 1: video creation jobs
+    "CREATE TABLE video_creation_jobs (
+        id TEXT PRIMARY KEY,
+        video_id TEXT NOT NULL,
+        image_paths TEXT NOT NULL,
+        image_durations TEXT NOT NULL,
+        output_path TEXT NOT NULL,
+        width INTEGER NOT NULL,
+        height INTEGER NOT NULL,
+        duration_seconds INTEGER NOT NULL,
+        status TEXT NOT NULL,
+        current_frame INTEGER,
+        total_frames INTEGER,
+        percentage REAL,
+        estimated_time_remaining_seconds REAL,
+        created_at INTEGER NOT NULL,
+        completed_at INTEGER,
+        error_message TEXT
+    );
+    CREATE INDEX video_creation_jobs_status ON video_creation_jobs (status);",
+    // 2: streaming sessions
+    "CREATE TABLE streaming_sessions (
+        id TEXT PRIMARY KEY,
+        video_id TEXT NOT NULL,
+        user_agent TEXT NOT NULL,
+        ip_address TEXT NOT NULL,
+        supported_formats TEXT NOT NULL,
+        state TEXT NOT NULL,
+        start_time INTEGER NOT NULL,
+        end_time INTEGER,
+        bytes_requested INTEGER NOT NULL,
+        chunks_requested INTEGER NOT NULL,
+        pause_count INTEGER NOT NULL,
+        total_pause_millis INTEGER NOT NULL
+    );",
+    // 3: video catalog
+    "CREATE TABLE videos (
+        id TEXT PRIMARY KEY,
+        file_path TEXT NOT NULL
+    );",
+    // 4: job attempts, for retrying jobs interrupted by a restart
+    "ALTER TABLE video_creation_jobs ADD COLUMN attempts INTEGER NOT NULL DEFAULT 0;",
+    // 5: job listing order
+    "CREATE INDEX video_creation_jobs_created_at ON video_creation_jobs (created_at, id);",
+    // 6: encoder profile of each job, as JSON; NULL for jobs stored before profiles existed
+    "ALTER TABLE video_creation_jobs ADD COLUMN encoder_profile TEXT;",
+    // 7: snake_case status names, as shown in API responses
+    "UPDATE video_creation_jobs SET status = CASE status WHEN 'InProgress' THEN 'in_progress' ELSE lower(status) END;",
+];
+
+const JOB_COLUMNS: &str = "id, video_id, image_paths, image_durations, output_path, width, height, duration_seconds, \
+    status, current_frame, total_frames, percentage, estimated_time_remaining_seconds, created_at, completed_at, error_message, attempts, \
+    encoder_profile";
+
+const SESSION_COLUMNS: &str = "id, video_id, user_agent, ip_address, supported_formats, state, start_time, end_time, \
+    bytes_requested, chunks_requested, pause_count, total_pause_millis";
+
+/// Shared SQLite connection with the schema migrated to the latest version
+#[derive(Clone)]
+pub struct SqliteDatabase {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl SqliteDatabase {
+    /// Open (or create) the database file, creating its directory when needed
+    pub fn open(path: impl AsRef<Path>) -> DomainResult<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent).map_err(|e| DomainError::Io(e.to_string()))?;
+        }
+
+        let connection = Connection::open(path).map_err(database_error)?;
+        // WAL lets readers proceed while a worker records progress
+        connection.pragma_update(None, "journal_mode", "WAL").map_err(database_error)?;
+        connection.busy_timeout(Duration::from_secs(5)).map_err(database_error)?;
+        Self::with_connection(connection)
+    }
+
+    /// Private database that disappears with the last clone
+    pub fn open_in_memory() -> DomainResult<Self> {
+        Self::with_connection(Connection::open_in_memory().map_err(database_error)?)
+    }
+
+    fn with_connection(mut connection: Connection) -> DomainResult<Self> {
+        migrate(&mut connection).map_err(database_error)?;
+        Ok(Self {
+            connection: Arc::new(Mutex::new(connection)),
+        })
+    }
+
+    /// Number of migrations applied to the schema
+    pub fn schema_version(&self) -> DomainResult<usize> {
+        let connection = self.connection.lock().unwrap();
+        connection
+            .query_row("PRAGMA user_version", [], |row| row.get(0))
+            .map_err(database_error)
+    }
+
+    pub fn latest_schema_version() -> usize {
+        MIGRATIONS.len()
+    }
+}
+
+fn migrate(connection: &mut Connection) -> rusqlite::Result<()> {
+    let applied: usize = connection.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+
+    for (index, migration) in MIGRATIONS.iter().enumerate().skip(applied) {
+        let transaction = connection.transaction()?;
+        transaction.execute_batch(migration)?;
+        transaction.pragma_update(None, "user_version", index + 1)?;
+        transaction.commit()?;
+    }
+    Ok(())
+}
+
+fn database_error(error: rusqlite::Error) -> DomainError {
+    DomainError::Io(format!("database error: {}", error))
+}
+
+/// Column value that could not be turned back into a domain value
+fn invalid_column(index: usize, error: impl std::error::Error + Send + Sync + 'static) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(index, Type::Text, Box::new(error))
+}
+
+fn to_millis(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|elapsed| elapsed.as_millis() as i64)
+        .unwrap_or(0)
+}
+
+fn from_millis(millis: i64) -> SystemTime {
+    UNIX_EPOCH + Duration::from_millis(millis.max(0) as u64)
+}
+
+fn json_column<T: serde::de::DeserializeOwned>(row: &Row, index: usize) -> rusqlite::Result<T> {
+    let text: String = row.get(index)?;
+    serde_json::from_str(&text).map_err(|e| invalid_column(index, e))
+}
+
+/// SQLite-backed video creation job repository
+#[derive(Clone)]
+pub struct SqliteVideoCreationRepository {
+    database: SqliteDatabase,
+}
+
+impl SqliteVideoCreationRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        Self { database }
+    }
+
+    fn job_from_row(row: &Row) -> rusqlite::Result<VideoCreationJob> {
+        let image_paths: Vec<String> = json_column(row, 2)?;
+        let status: String = row.get(8)?;
+        let status = VideoCreationStatus::parse(&status).map_err(|e| invalid_column(8, e))?;
+        let encoder_profile: Option<EncoderProfile> = match row.get::<_, Option<String>>(17)? {
+            Some(text) => Some(serde_json::from_str(&text).map_err(|e| invalid_column(17, e))?),
+            None => None,
+        };
+
+        let progress = match (row.get::<_, Option<i64>>(9)?, row.get::<_, Option<i64>>(10)?) {
+            (Some(current_frame), Some(total_frames)) => Some(VideoCreationProgress {
+                current_frame: current_frame as usize,
+                total_frames: total_frames as usize,
+                percentage: row.get::<_, Option<f64>>(11)?.unwrap_or(0.0) as f32,
+                estimated_time_remaining_seconds: row.get::<_, Option<f64>>(12)?.map(|eta| eta as f32),
+            }),
+            _ => None,
+        };
+
+        Ok(VideoCreationJob {
+            id: VideoCreationJobId::new(row.get(0)?),
+            request: VideoCreationRequest {
+                image_paths: image_paths.into_iter().map(FilePath::new).collect(),
+                image_durations: json_column(row, 3)?,
+                output_path: FilePath::new(row.get(4)?),
+                image_spec: ImageSpec {
+                    width: row.get(5)?,
+                    height: row.get(6)?,
+                    duration_seconds: row.get(7)?,
+                },
+                encoder_profile: encoder_profile.unwrap_or_default(),
+                video_id: VideoId::new(row.get(1)?),
+            },
+            status,
+            progress,
+            created_at: from_millis(row.get(13)?),
+            completed_at: row.get::<_, Option<i64>>(14)?.map(from_millis),
+            error_message: row.get(15)?,
+            attempts: row.get(16)?,
+        })
+    }
+
+    fn upsert(&self, job: &VideoCreationJob) -> DomainResult<()> {
+        let sql = format!("INSERT OR REPLACE INTO video_creation_jobs ({}) \
+            VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18)", JOB_COLUMNS);
+        self.write(&sql, job)?;
+        Ok(())
+    }
+
+    /// Overwrite every column of a stored job that has not finished, in one statement
+    fn update_unless_finished(&self, job: &VideoCreationJob) -> DomainResult<bool> {
+        let assignments: Vec<String> = JOB_COLUMNS
+            .split(',')
+            .enumerate()
+            .map(|(index, column)| format!("{} = ?{}", column.trim(), index + 1))
+            .collect();
+        let sql = format!(
+            "UPDATE video_creation_jobs SET {} \
+             WHERE id = ?1 AND status NOT IN ('completed', 'failed', 'cancelled')",
+            assignments.join(", "),
+        );
+        Ok(self.write(&sql, job)? > 0)
+    }
+
+    /// Run a statement binding every job column in `JOB_COLUMNS` order; returns the rows changed
+    fn write(&self, sql: &str, job: &VideoCreationJob) -> DomainResult<usize> {
+        let image_paths: Vec<&str> = job.request.image_paths.iter().map(|path| path.as_str()).collect();
+        let image_paths = serde_json::to_string(&image_paths).map_err(|e| DomainError::Io(e.to_string()))?;
+        let image_durations = serde_json::to_string(&job.request.image_durations).map_err(|e| DomainError::Io(e.to_string()))?;
+        let encoder_profile = serde_json::to_string(&job.request.encoder_profile).map_err(|e| DomainError::Io(e.to_string()))?;
+        let progress = job.progress.as_ref();
+
+        let connection = self.database.connection.lock().unwrap();
+        connection.execute(
+            sql,
+            params![
+                job.id.as_str(),
+                job.request.video_id.as_str(),
+                image_paths,
+                image_durations,
+                job.request.output_path.as_str(),
+                job.request.image_spec.width,
+                job.request.image_spec.height,
+                job.request.image_spec.duration_seconds,
+                job.status.as_str(),
+                progress.map(|p| p.current_frame as i64),
+                progress.map(|p| p.total_frames as i64),
+                progress.map(|p| p.percentage as f64),
+                progress.and_then(|p| p.estimated_time_remaining_seconds).map(|eta| eta as f64),
+                to_millis(job.created_at),
+                job.completed_at.map(to_millis),
+                job.error_message,
+                job.attempts,
+                encoder_profile,
+            ],
+        ).map_err(database_error)
+    }
+}
+
+impl VideoCreationRepository for SqliteVideoCreationRepository {
+    fn save_job(&self, job: &VideoCreationJob) -> DomainResult<()> {
+        self.upsert(job)
+    }
+
+    fn find_job_by_id(&self, id: &VideoCreationJobId) -> DomainResult<Option<VideoCreationJob>> {
+        let connection = self.database.connection.lock().unwrap();
+        connection
+            .query_row(
+                &format!("SELECT {} FROM video_creation_jobs WHERE id = ?1", JOB_COLUMNS),
+                params![id.as_str()],
+                Self::job_from_row,
+            )
+            .optional()
+            .map_err(database_error)
+    }
+
+    fn find_jobs_by_status(&self, status: &VideoCreationStatus) -> DomainResult<Vec<VideoCreationJob>> {
+        let connection = self.database.connection.lock().unwrap();
+        let mut statement = connection
+            .prepare(&format!("SELECT {} FROM video_creation_jobs WHERE status = ?1 ORDER BY created_at, id", JOB_COLUMNS))
+            .map_err(database_error)?;
+        let jobs = statement
+            .query_map(params![status.as_str()], Self::job_from_row)
+            .map_err(database_error)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(database_error)?;
+        Ok(jobs)
+    }
+
+    fn query_jobs(&self, query: &VideoCreationJobQuery) -> DomainResult<Vec<VideoCreationJob>> {
+        let connection = self.database.connection.lock().unwrap();
+        let mut statement = connection
+            .prepare(&format!(
+                "SELECT {} FROM video_creation_jobs
+                 WHERE (?1 IS NULL OR status = ?1)
+                   AND (?2 IS NULL OR video_id = ?2)
+                   AND (?3 IS NULL OR created_at >= ?3)
+                   AND (?4 IS NULL OR created_at > ?4 OR (created_at = ?4 AND id > ?5))
+                 ORDER BY created_at, id
+                 LIMIT ?6",
+                JOB_COLUMNS,
+            ))
+            .map_err(database_error)?;
+        let jobs = statement
+            .query_map(
+                params![
+                    query.status.as_ref().map(|status| status.as_str()),
+                    query.video_id.as_ref().map(|video_id| video_id.as_str()),
+                    query.since.map(to_millis),
+                    query.after.as_ref().map(|after| after.created_at_millis as i64),
+                    query.after.as_ref().map(|after| after.job_id.as_str()),
+                    query.limit.min(i64::MAX as usize) as i64,
+                ],
+                Self::job_from_row,
+            )
+            .map_err(database_error)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(database_error)?;
+        Ok(jobs)
+    }
+
+    fn update_job(&self, job: &VideoCreationJob) -> DomainResult<()> {
+        self.upsert(job)
+    }
+
+    fn update_job_unless_finished(&self, job: &VideoCreationJob) -> DomainResult<bool> {
+        self.update_unless_finished(job)
+    }
+}
+
+/// SQLite-backed streaming session repository
+#[derive(Clone)]
+pub struct SqliteSessionRepository {
+    database: SqliteDatabase,
+}
+
+impl SqliteSessionRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        Self { database }
+    }
+
+    fn session_from_row(row: &Row) -> rusqlite::Result<StreamingSession> {
+        let state: String = row.get(5)?;
+        let state = SessionState::parse(&state).map_err(|e| invalid_column(5, e))?;
+
+        Ok(StreamingSession {
+            id: SessionId::new(row.get(0)?),
+            video_id: VideoId::new(row.get(1)?),
+            client_info: ClientInfo {
+                user_agent: row.get(2)?,
+                ip_address: row.get(3)?,
+                supported_formats: json_column(row, 4)?,
+            },
+            state,
+            metrics: SessionMetrics {
+                start_time: from_millis(row.get(6)?),
+                end_time: row.get::<_, Option<i64>>(7)?.map(from_millis),
+                bytes_requested: row.get::<_, i64>(8)? as u64,
+                chunks_requested: row.get::<_, i64>(9)? as u64,
+                pause_count: row.get(10)?,
+                total_pause_duration: Duration::from_millis(row.get::<_, i64>(11)?.max(0) as u64),
+            },
+        })
+    }
+}
+
+impl SessionRepository for SqliteSessionRepository {
+    fn find_by_id(&self, id: &SessionId) -> DomainResult<Option<StreamingSession>> {
+        let connection = self.database.connection.lock().unwrap();
+        connection
+            .query_row(
+                &format!("SELECT {} FROM streaming_sessions WHERE id = ?1", SESSION_COLUMNS),
+                params![id.as_str()],
+                Self::session_from_row,
+            )
+            .optional()
+            .map_err(database_error)
+    }
+
+    fn save(&self, session: &StreamingSession) -> DomainResult<()> {
+        let supported_formats = serde_json::to_string(&session.client_info.supported_formats)
+            .map_err(|e| DomainError::Io(e.to_string()))?;
+        let metrics = &session.metrics;
+
+        let connection = self.database.connection.lock().unwrap();
+        connection.execute(
+            &format!("INSERT OR REPLACE INTO streaming_sessions ({}) \
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)", SESSION_COLUMNS),
+            params![
+                session.id.as_str(),
+                session.video_id.as_str(),
+                session.client_info.user_agent,
+                session.client_info.ip_address,
+                supported_formats,
+                session.state.as_str(),
+                to_millis(metrics.start_time),
+                metrics.end_time.map(to_millis),
+                metrics.bytes_requested as i64,
+                metrics.chunks_requested as i64,
+                metrics.pause_count,
+                metrics.total_pause_duration.as_millis() as i64,
+            ],
+        ).map_err(database_error)?;
+        Ok(())
+    }
+
+    fn delete(&self, id: &SessionId) -> DomainResult<()> {
+        let connection = self.database.connection.lock().unwrap();
+        connection
+            .execute("DELETE FROM streaming_sessions WHERE id = ?1", params![id.as_str()])
+            .map_err(database_error)?;
+        Ok(())
+    }
+
+    fn find_active_sessions(&self) -> DomainResult<Vec<StreamingSession>> {
+        let connection = self.database.connection.lock().unwrap();
+        let mut statement = connection
+            .prepare(&format!("SELECT {} FROM streaming_sessions WHERE state = ?1 ORDER BY start_time, id", SESSION_COLUMNS))
+            .map_err(database_error)?;
+        let sessions = statement
+            .query_map(params![SessionState::Active.as_str()], Self::session_from_row)
+            .map_err(database_error)?
+            .collect::<rusqlite::Result<Vec<_>>>()
+            .map_err(database_error)?;
+        Ok(sessions)
+    }
+}
+
+/// SQLite-backed video catalog; only the id-to-file mapping is stored,
+/// metadata is read from the file so it follows replaced files
+#[derive(Clone)]
+pub struct SqliteVideoRepository {
+    database: SqliteDatabase,
+}
+
+impl SqliteVideoRepository {
+    pub fn new(database: SqliteDatabase) -> Self {
+        Self { database }
+    }
+}
+
+impl VideoRepository for SqliteVideoRepository {
+    fn find_by_id(&self, id: &VideoId) -> DomainResult<Option<Video>> {
+        let file_path: Option<String> = {
+            let connection = self.database.connection.lock().unwrap();
+            connection
+                .query_row("SELECT file_path FROM videos WHERE id = ?1", params![id.as_str()], |row| row.get(0))
+                .optional()
+                .map_err(database_error)?
+        };
+        let Some(file_path) = file_path else {
+            return Ok(None);
+        };
+
+        match Video::new(id.clone(), FilePath::new(file_path)) {
+            Ok(video) => Ok(Some(video)),
+            Err(DomainError::NotFound { .. }) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    fn save(&self, video: &Video) -> DomainResult<()> {
+        let connection = self.database.connection.lock().unwrap();
+        connection
+            .execute(
+                "INSERT OR REPLACE INTO videos (id, file_path) VALUES (?1, ?2)",
+                params![video.id.as_str(), video.file_path.as_str()],
+            )
+            .map_err(database_error)?;
+        Ok(())
+    }
+
+    fn delete(&self, id: &VideoId) -> DomainResult<()> {
+        let connection = self.database.connection.lock().unwrap();
+        connection
+            .execute("DELETE FROM videos WHERE id = ?1", params![id.as_str()])
+            .map_err(database_error)?;
+        Ok(())
+    }
+}
@@ -1,10 +1,19 @@
+use std::sync::Arc;
 use actix_web::{web, App, HttpServer};
 use video_streaming_api::{
-    shared::config::Config, 
+    shared::config::{Config, StorageBackend}, 
+    application::services::VideoCreationAppService,
     infrastructure::http::{
-        handle_video_stream, handle_create_video, handle_get_job_status, 
-        handle_validate_images, handle_health_check
-    }
+        handle_video_stream, handle_catalog_video_stream, handle_video_metadata, handle_create_video, handle_create_video_query,
+        handle_list_jobs, handle_get_job_status, handle_job_events, handle_cancel_job, handle_validate_images, handle_health_check
+    },
+    infrastructure::repositories::{DirectoryVideoRepository, session_repository, video_creation_repository, video_repository},
+    infrastructure::jobs::VideoCreationWorkerPool,
+    infrastructure::cache::BlockCache,
+    infrastructure::ffmpeg::{FFmpegCapabilities, SharedFFmpegCapabilities},
+    domain::container::NativeMediaProbe,
+    domain::media::FallbackMediaProbe,
+    infrastructure::probe::{CachedMediaProbe, FFprobeMediaProbe, SharedMediaProbe},
 };
 
 #[actix_web::main]
@@ -19,28 +28,116 @@ async fn main() -> std::io::Result<()> {
     
     let server_address = config.server_address();
     
+    // Register every video under the media root, in the configured storage backend
+    let catalog = DirectoryVideoRepository::new(&config.media_root);
+    if let Err(e) = catalog.rescan() {
+        eprintln!("Warning: Failed to scan media root {}: {}", config.media_root, e);
+    }
+    let video_repository = match video_repository(&config, &catalog) {
+        Ok(repository) => web::Data::new(repository),
+        Err(e) => {
+            eprintln!("Storage error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let session_repository = match session_repository(&config) {
+        Ok(repository) => web::Data::new(repository),
+        Err(e) => {
+            eprintln!("Storage error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    
+    // One block cache shared by every worker
+    let block_cache = web::Data::new(BlockCache::new(config.block_cache_bytes));
+    
+    // Native MP4/Matroska parsing first, ffprobe for anything else;
+    // results are cached per file version and shared by every worker
+    let media_probe: SharedMediaProbe = Arc::new(CachedMediaProbe::new(FallbackMediaProbe::new(
+        NativeMediaProbe,
+        FFprobeMediaProbe::new(config.ffprobe_path.clone()),
+    )));
+    let media_probe = web::Data::new(media_probe);
+    
+    // Probe FFmpeg once; requests it cannot encode are rejected before they are queued
+    let ffmpeg_capabilities: SharedFFmpegCapabilities = Arc::new(FFmpegCapabilities::probe(&config.ffmpeg_path));
+    
+    // FFmpeg runs on background workers so creation requests return immediately
+    let job_repository = match video_creation_repository(&config) {
+        Ok(repository) => repository,
+        Err(e) => {
+            eprintln!("Storage error: {}", e);
+            std::process::exit(1);
+        }
+    };
+    let workers = VideoCreationWorkerPool::start_with_capabilities(job_repository, config.video_workers, ffmpeg_capabilities.clone());
+    let worker_count = workers.worker_count();
+    
+    // Jobs interrupted by a previous shutdown are retried or failed before new ones arrive
+    if let Err(e) = workers.recover_interrupted_jobs(config.job_max_retries) {
+        eprintln!("Warning: Failed to recover interrupted jobs: {}", e);
+    }
+    
+    // One video creation service for the whole app, so every request sees the same jobs
+    let video_creation = web::Data::new(
+        VideoCreationAppService::new(config.clone(), workers).with_ffmpeg_capabilities(ffmpeg_capabilities.clone())
+    );
+    
     println!("🚀 Starting Video Streaming API PoC");
     println!("==================================");
     println!("Server: {}", server_address);
     println!("Video Path: {}", config.video_path);
+    println!("Media Root: {} ({} videos)", config.media_root, catalog.list_ids().len());
+    println!("Block Cache: {} bytes", config.block_cache_bytes);
     println!("Default Image Spec: {}x{} ({}s per image)", 
         config.default_image_width, config.default_image_height, config.default_duration_per_image);
+    match config.storage_backend {
+        StorageBackend::Memory => println!("Storage: in-memory"),
+        StorageBackend::Sqlite => println!("Storage: SQLite ({})", config.database_path),
+    }
+    println!("Video Workers: {}", worker_count);
     println!("FFmpeg Path: {}", config.ffmpeg_path);
-    println!("FFmpeg Codec: {}", config.ffmpeg_codec);
-    println!("✅ FFmpeg Available: {}", video_streaming_api::infrastructure::ffmpeg::FFmpegVideoCreator::<
-        video_streaming_api::infrastructure::repositories::InMemoryVideoCreationRepository
-    >::check_ffmpeg_available());
+    match config.default_encoder_profile() {
+        Ok(profile) => println!("Encoder Profile: {} ({}, {})", profile.name, profile.codec, profile.pixel_format),
+        Err(e) => println!("Encoder Profile: {}", e),
+    }
+    if ffmpeg_capabilities.available {
+        println!("✅ FFmpeg Available: {} ({} encoders, {} filters)",
+            ffmpeg_capabilities.version.as_deref().unwrap_or("unknown version"),
+            ffmpeg_capabilities.encoders.len(), ffmpeg_capabilities.filters.len());
+        if let Ok(profile) = config.default_encoder_profile() {
+            for error in ffmpeg_capabilities.unsupported(&profile) {
+                println!("Warning: default encoder profile: {}", error.message);
+            }
+        }
+    } else {
+        println!("❌ FFmpeg Available: false (video creation requests will be rejected)");
+    }
     println!("==================================");
     
     // Create and run server
     HttpServer::new(move || {
         App::new()
             .app_data(web::Data::new(config.clone()))
+            .app_data(video_repository.clone())
+            .app_data(session_repository.clone())
+            .app_data(block_cache.clone())
+            .app_data(media_probe.clone())
+            .app_data(web::Data::new(ffmpeg_capabilities.clone()))
+            .app_data(video_creation.clone())
             // Video streaming endpoints
             .route("/stream", web::get().to(handle_video_stream))
+            .route("/stream", web::head().to(handle_video_stream))
+            .route("/videos/{video_id}/stream", web::get().to(handle_catalog_video_stream))
+            .route("/videos/{video_id}/stream", web::head().to(handle_catalog_video_stream))
+            .route("/videos/{video_id}/metadata", web::get().to(handle_video_metadata))
             // Video creation endpoints
-            .route("/create-video", web::post().to(handle_create_video))
+            .route("/videos", web::post().to(handle_create_video))
+            .route("/create-video", web::post().to(handle_create_video_query)) // legacy alias
+            .route("/jobs", web::get().to(handle_list_jobs))
             .route("/job/{job_id}", web::get().to(handle_get_job_status))
+            .route("/job/{job_id}", web::delete().to(handle_cancel_job))
+            .route("/job/{job_id}/events", web::get().to(handle_job_events))
             .route("/validate-images", web::get().to(handle_validate_images))
             // Health check
             .route("/health", web::get().to(handle_health_check))
@@ -1,4 +1,26 @@
 use std::env;
+use std::str::FromStr;
+use crate::domain::common::DomainResult;
+use crate::domain::video_creation::EncoderProfile;
+
+/// Where job history and sessions are kept
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StorageBackend {
+    Memory,
+    Sqlite,
+}
+
+impl FromStr for StorageBackend {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.to_ascii_lowercase().as_str() {
+            "memory" => Ok(StorageBackend::Memory),
+            "sqlite" => Ok(StorageBackend::Sqlite),
+            other => Err(format!("unknown storage backend: {}", other)),
+        }
+    }
+}
 
 /// Configuration for the video streaming service
 #[derive(Debug, Clone)]
@@ -6,6 +28,11 @@ pub struct Config {
     // Video streaming configuration
     pub video_path: String,
     pub content_type: String,
+    pub media_root: String,
+    /// Largest number of bytes served for a single requested range; `None` means unlimited
+    pub max_range_chunk_bytes: Option<u64>,
+    /// Memory budget of the shared block cache in bytes; 0 disables it
+    pub block_cache_bytes: u64,
     
     // Server configuration
     pub host: String,
@@ -15,11 +42,24 @@ pub struct Config {
     pub default_image_width: u32,
     pub default_image_height: u32,
     pub default_duration_per_image: u32,
+    /// Number of background workers running video creation jobs
+    pub video_workers: usize,
+    /// Times a job interrupted by a restart is re-queued before it is marked failed
+    pub job_max_retries: u32,
+    
+    // Storage configuration
+    pub storage_backend: StorageBackend,
+    /// SQLite database file used by the `sqlite` backend
+    pub database_path: String,
     
     // FFmpeg configuration
     pub ffmpeg_path: String,
-    pub ffmpeg_codec: String,
-    pub ffmpeg_pixel_format: String,
+    /// Encoder profile of requests that do not name one
+    pub encoder_profile: String,
+    /// Overrides of the default profile's codec and pixel format
+    pub ffmpeg_codec: Option<String>,
+    pub ffmpeg_pixel_format: Option<String>,
+    pub ffprobe_path: String,
     
     // Development configuration
     pub rust_log: String,
@@ -32,14 +72,23 @@ impl Config {
         Self {
             video_path: "assets/videos/sample.webm".to_string(),
             content_type: "video/webm".to_string(),
+            media_root: "assets/videos".to_string(),
+            max_range_chunk_bytes: None,
+            block_cache_bytes: 64 * 1024 * 1024,
             host: "127.0.0.1".to_string(),
             port: 8080,
             default_image_width: 800,
             default_image_height: 600,
             default_duration_per_image: 1,
+            video_workers: 2,
+            job_max_retries: 2,
+            storage_backend: StorageBackend::Memory,
+            database_path: "data/video_streaming.db".to_string(),
             ffmpeg_path: "ffmpeg".to_string(),
-            ffmpeg_codec: "libx264".to_string(),
-            ffmpeg_pixel_format: "yuv420p".to_string(),
+            encoder_profile: "h264-web".to_string(),
+            ffmpeg_codec: None,
+            ffmpeg_pixel_format: None,
+            ffprobe_path: "ffprobe".to_string(),
             rust_log: "info".to_string(),
             rust_backtrace: "1".to_string(),
         }
@@ -51,6 +100,15 @@ impl Config {
             // Video streaming configuration
             video_path: env::var("VIDEO_PATH").unwrap_or_else(|_| "assets/videos/sample.webm".to_string()),
             content_type: env::var("CONTENT_TYPE").unwrap_or_else(|_| "video/webm".to_string()),
+            media_root: env::var("MEDIA_ROOT").unwrap_or_else(|_| "assets/videos".to_string()),
+            max_range_chunk_bytes: env::var("MAX_RANGE_CHUNK_BYTES")
+                .ok()
+                .map(|value| value.parse::<u64>().expect("MAX_RANGE_CHUNK_BYTES must be a valid number"))
+                .filter(|&bytes| bytes > 0),
+            block_cache_bytes: env::var("BLOCK_CACHE_BYTES")
+                .unwrap_or_else(|_| "67108864".to_string())
+                .parse()
+                .expect("BLOCK_CACHE_BYTES must be a valid number"),
             
             // Server configuration
             host: env::var("HOST").unwrap_or_else(|_| "127.0.0.1".to_string()),
@@ -72,11 +130,28 @@ impl Config {
                 .unwrap_or_else(|_| "1".to_string())
                 .parse()
                 .expect("DEFAULT_DURATION_PER_IMAGE must be a valid number"),
+            video_workers: env::var("VIDEO_WORKERS")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .expect("VIDEO_WORKERS must be a valid number"),
+            job_max_retries: env::var("JOB_MAX_RETRIES")
+                .unwrap_or_else(|_| "2".to_string())
+                .parse()
+                .expect("JOB_MAX_RETRIES must be a valid number"),
+            
+            // Storage configuration
+            storage_backend: env::var("STORAGE_BACKEND")
+                .unwrap_or_else(|_| "memory".to_string())
+                .parse()
+                .expect("STORAGE_BACKEND must be memory or sqlite"),
+            database_path: env::var("DATABASE_PATH").unwrap_or_else(|_| "data/video_streaming.db".to_string()),
             
             // FFmpeg configuration
             ffmpeg_path: env::var("FFMPEG_PATH").unwrap_or_else(|_| "ffmpeg".to_string()),
-            ffmpeg_codec: env::var("FFMPEG_CODEC").unwrap_or_else(|_| "libx264".to_string()),
-            ffmpeg_pixel_format: env::var("FFMPEG_PIXEL_FORMAT").unwrap_or_else(|_| "yuv420p".to_string()),
+            encoder_profile: env::var("ENCODER_PROFILE").unwrap_or_else(|_| "h264-web".to_string()),
+            ffmpeg_codec: env::var("FFMPEG_CODEC").ok().filter(|codec| !codec.trim().is_empty()),
+            ffmpeg_pixel_format: env::var("FFMPEG_PIXEL_FORMAT").ok().filter(|format| !format.trim().is_empty()),
+            ffprobe_path: env::var("FFPROBE_PATH").unwrap_or_else(|_| "ffprobe".to_string()),
             
             // Development configuration
             rust_log: env::var("RUST_LOG").unwrap_or_else(|_| "info".to_string()),
@@ -98,6 +173,19 @@ impl Config {
         }
     }
 
+    /// Encoder profile for requests that do not name one: `encoder_profile`,
+    /// with `FFMPEG_CODEC` and `FFMPEG_PIXEL_FORMAT` applied when set
+    pub fn default_encoder_profile(&self) -> DomainResult<EncoderProfile> {
+        let mut profile = EncoderProfile::named(&self.encoder_profile)?;
+        if let Some(codec) = &self.ffmpeg_codec {
+            profile = profile.with_codec(codec);
+        }
+        if let Some(pixel_format) = &self.ffmpeg_pixel_format {
+            profile = profile.with_pixel_format(pixel_format);
+        }
+        Ok(profile)
+    }
+
     /// Validate configuration
     pub fn validate(&self) -> Result<(), String> {
         if self.port == 0 {
@@ -108,10 +196,29 @@ impl Config {
             return Err("Image dimensions cannot be 0".to_string());
         }
         
-        if self.default_duration_per_image <= 0 {
+        if self.default_duration_per_image == 0 {
             return Err("Duration per image must be positive".to_string());
         }
         
+        if self.video_workers == 0 {
+            return Err("VIDEO_WORKERS must be at least 1".to_string());
+        }
+        
+        if EncoderProfile::builtin(&self.encoder_profile).is_none() {
+            return Err(format!("ENCODER_PROFILE {} is not a known encoder profile", self.encoder_profile));
+        }
+        
+        if let Ok(profile) = self.default_encoder_profile() {
+            if !profile.container.accepts_codec(&profile.codec) {
+                return Err(format!("FFMPEG_CODEC {} cannot be written to the {} container of ENCODER_PROFILE {}",
+                    profile.codec, profile.container.muxer(), profile.name));
+            }
+        }
+        
+        if self.storage_backend == StorageBackend::Sqlite && self.database_path.trim().is_empty() {
+            return Err("DATABASE_PATH cannot be empty with the sqlite backend".to_string());
+        }
+        
         Ok(())
     }
 }
@@ -1,6 +1,4 @@
 // Shared Kernel - Cross-cutting concerns, configuration, utilities
 pub mod config;
-pub mod error;
 
 pub use config::*;
-pub use error::*; 
\ No newline at end of file
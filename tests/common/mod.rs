@@ -0,0 +1,52 @@
+//! Fixtures shared by the integration tests. Each test binary compiles its own copy,
+//! so not every helper is used everywhere.
+#![allow(dead_code)]
+
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+/// Helper function to create an empty scratch directory, named after the test binary and `name`
+pub fn empty_scratch_dir(name: &str) -> PathBuf {
+    // Inside this module the path starts with the name of the test binary
+    let binary = module_path!().split("::").next().unwrap_or("test");
+    let dir = std::env::temp_dir().join(format!("{}_{}_{}", binary, name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("Failed to create scratch directory");
+    dir
+}
+
+/// Helper function to create a scratch directory with one (fake) image
+pub fn scratch_dir(name: &str) -> (PathBuf, String) {
+    let dir = empty_scratch_dir(name);
+    let image = dir.join("image.png");
+    std::fs::write(&image, b"not really a png").expect("Failed to write image");
+    (dir, image.to_string_lossy().to_string())
+}
+
+/// Write `count` (fake) images into `dir`
+pub fn write_images(dir: &Path, count: usize) -> Vec<String> {
+    (0..count)
+        .map(|i| {
+            let image = dir.join(format!("image{}.png", i));
+            std::fs::write(&image, b"not really a png").expect("Failed to write image");
+            image.to_string_lossy().to_string()
+        })
+        .collect()
+}
+
+/// Write an executable shell script to `path`; returns the path as a string
+pub fn write_script(path: &Path, script: &str) -> String {
+    std::fs::write(path, script).expect("Failed to write script");
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o755)).expect("Failed to make script executable");
+    path.to_string_lossy().to_string()
+}
+
+/// Write an FFmpeg stand-in into `dir` that answers `-version` and otherwise runs `body`,
+/// with `$output` set to its last argument. Pass the returned path to the code under test,
+/// the stand-in is never put on PATH.
+pub fn write_fake_ffmpeg(dir: &Path, body: &str) -> String {
+    write_script(&dir.join("ffmpeg"), &format!("#!/bin/sh\n\
+        if [ \"$1\" = \"-version\" ]; then exit 0; fi\n\
+        for output; do :; done\n\
+        {}", body))
+}
@@ -0,0 +1,178 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use actix_web::{test, web, App};
+use video_streaming_api::{
+    application::dto::{CreateVideoResponse, VideoCreationJobStatusResponse},
+    application::services::VideoCreationAppService,
+    domain::video::VideoId,
+    domain::video_creation::{VideoCreationJob, VideoCreationJobId, VideoCreationManager, VideoCreationRepository, VideoCreationStatus},
+    infrastructure::http::{handle_create_video, handle_create_video_query, handle_get_job_status},
+    infrastructure::jobs::VideoCreationWorkerPool,
+    infrastructure::repositories::{InMemoryVideoCreationRepository, SharedVideoCreationRepository},
+    shared::config::Config,
+};
+
+macro_rules! init_app {
+    ($repository:expr) => {
+        test::init_service(
+            App::new()
+                .app_data(web::Data::new(VideoCreationAppService::new(
+                    Config::new(),
+                    VideoCreationWorkerPool::start($repository, 1),
+                )))
+                .route("/videos", web::post().to(handle_create_video))
+                .route("/create-video", web::post().to(handle_create_video_query))
+                .route("/job/{job_id}", web::get().to(handle_get_job_status))
+        ).await
+    };
+}
+
+/// Helper function to create a directory with a few (fake) images
+fn create_images(name: &str, count: usize) -> (PathBuf, Vec<String>) {
+    let dir = std::env::temp_dir().join(format!("background_jobs_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("Failed to create image directory");
+
+    let paths = (0..count)
+        .map(|i| {
+            let path = dir.join(format!("image{}.png", i));
+            std::fs::write(&path, b"not really a png").expect("Failed to write image");
+            path.to_string_lossy().to_string()
+        })
+        .collect();
+    (dir, paths)
+}
+
+/// Poll the repository until the job leaves Pending/InProgress
+async fn wait_for_finished_job(repository: &SharedVideoCreationRepository, job_id: &str) -> VideoCreationJob {
+    let job_id = VideoCreationJobId::new(job_id.to_string());
+    for _ in 0..200 {
+        if let Some(job) = repository.find_job_by_id(&job_id).expect("Failed to read job") {
+            if matches!(job.status, VideoCreationStatus::Completed | VideoCreationStatus::Failed) {
+                return job;
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("job {} did not finish in time", job_id.as_str());
+}
+
+#[actix_web::test]
+async fn test_create_video_returns_accepted_with_location() {
+    let repository: SharedVideoCreationRepository = Arc::new(InMemoryVideoCreationRepository::new());
+    let app = init_app!(repository.clone());
+    let (dir, images) = create_images("accepted", 2);
+
+    let request = serde_json::json!({
+        "video_id": "clip",
+        "images": [{"path": images[0]}, {"path": images[1]}],
+        "output": {"path": dir.join("out.mp4").to_string_lossy()},
+    });
+    let req = test::TestRequest::post().uri("/videos").set_json(&request).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 202);
+    let location = resp.headers().get("Location").unwrap().to_str().unwrap().to_string();
+    let body: CreateVideoResponse = test::read_body_json(resp).await;
+    assert_eq!(location, format!("/job/{}", body.job_id));
+    assert_eq!(body.status, "pending");
+    assert_eq!(body.total_frames, 2);
+    let now = std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).unwrap().as_secs();
+    assert!(body.created_at <= now && body.created_at + 60 > now);
+
+    // The worker records the outcome; the fake images never encode successfully
+    let job = wait_for_finished_job(&repository, &body.job_id).await;
+    assert_eq!(job.status, VideoCreationStatus::Failed);
+    assert!(job.error_message.unwrap().starts_with("ffmpeg failed"));
+
+    // The legacy alias is queued the same way
+    let req = test::TestRequest::post()
+        .uri(&format!("/create-video?video_id=legacy&output_path=out.mp4&image1={}", images[0]))
+        .insert_header(("Accept", "text/plain"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 202);
+    assert!(resp.headers().get("Location").unwrap().to_str().unwrap().starts_with("/job/"));
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert!(body.starts_with("Video creation job started.\n"));
+    assert!(body.contains("Status: pending"));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_job_status_is_visible_after_creation() {
+    let repository: SharedVideoCreationRepository = Arc::new(InMemoryVideoCreationRepository::new());
+    let app = init_app!(repository.clone());
+    let (dir, images) = create_images("status", 3);
+
+    let request = serde_json::json!({
+        "video_id": "status_clip",
+        "images": [{"path": images[0]}, {"path": images[1]}, {"path": images[2], "duration": 3}],
+        "output": {"path": dir.join("out.mp4").to_string_lossy()},
+    });
+    let req = test::TestRequest::post().uri("/videos").set_json(&request).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 202);
+    let location = resp.headers().get("Location").unwrap().to_str().unwrap().to_string();
+    let created: CreateVideoResponse = test::read_body_json(resp).await;
+
+    // Exactly one job exists for the request, under the id that was handed out
+    let job = wait_for_finished_job(&repository, &created.job_id).await;
+    let stored = repository.find_jobs_by_status(&job.status).unwrap();
+    assert_eq!(stored.len(), 1);
+    assert_eq!(stored[0].id.as_str(), created.job_id);
+
+    let req = test::TestRequest::get().uri(&location).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let status: VideoCreationJobStatusResponse = test::read_body_json(resp).await;
+    assert_eq!(status.job_id, created.job_id);
+    assert_eq!(status.video_id, "status_clip");
+    assert_eq!(status.status, job.status.as_str());
+    assert_eq!(status.created_at, created.created_at);
+    assert!(status.completed_at.is_some());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_worker_pool_runs_every_submitted_job() {
+    let repository: SharedVideoCreationRepository = Arc::new(InMemoryVideoCreationRepository::new());
+    let workers = VideoCreationWorkerPool::start(repository.clone(), 2);
+    assert_eq!(workers.worker_count(), 2);
+    let (dir, images) = create_images("pool", 1);
+
+    let job_ids: Vec<String> = (0..4)
+        .map(|i| {
+            let mut job = VideoCreationManager::create_job(
+                images.clone(),
+                dir.join(format!("out{}.mp4", i)).to_string_lossy().to_string(),
+                VideoId::new(format!("clip{}", i)),
+                None,
+            ).expect("Failed to create job");
+            job.id = VideoCreationJobId::new(format!("pool_job_{}", i));
+
+            workers.submit(job).expect("Failed to submit job");
+            format!("pool_job_{}", i)
+        })
+        .collect();
+
+    // Submitted jobs are visible right away, before any worker finishes them
+    for job_id in &job_ids {
+        let job = repository.find_job_by_id(&VideoCreationJobId::new(job_id.clone())).unwrap();
+        assert!(job.is_some());
+    }
+
+    for job_id in &job_ids {
+        let job = wait_for_finished_job(&repository, job_id).await;
+        assert!(job.completed_at.is_some());
+        assert!(job.progress.is_some());
+    }
+
+    // A pool asked for no workers still runs one
+    let single = VideoCreationWorkerPool::start(repository, 0);
+    assert_eq!(single.worker_count(), 1);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,167 @@
+use std::sync::Arc;
+use actix_web::{test, web, App};
+use bytes::Bytes;
+use futures_util::StreamExt;
+use video_streaming_api::{
+    domain::video::VideoByteStream,
+    infrastructure::cache::{BlockCache, BlockKey},
+    infrastructure::http::handle_catalog_video_stream,
+    infrastructure::repositories::{DirectoryVideoRepository, SharedVideoRepository},
+    infrastructure::services::cached_stream_file_range,
+    shared::config::Config,
+};
+
+/// Helper function to drain a byte stream into memory
+async fn collect(mut stream: VideoByteStream) -> Vec<u8> {
+    let mut data = Vec::new();
+    while let Some(buffer) = stream.next().await {
+        data.extend_from_slice(&buffer.expect("Read failed"));
+    }
+    data
+}
+
+#[actix_web::test]
+async fn test_lru_eviction_within_budget() {
+    let cache = BlockCache::with_block_size(300, 100);
+    let key = |index| BlockKey::new("clip", "\"v1\"", index);
+
+    cache.insert(key(0), Bytes::from(vec![0u8; 100]));
+    cache.insert(key(1), Bytes::from(vec![1u8; 100]));
+    cache.insert(key(2), Bytes::from(vec![2u8; 100]));
+
+    // Touch block 0 so block 1 becomes the least recently used
+    assert!(cache.get(&key(0)).is_some());
+
+    // The cache is full, so block 3 is only admitted on its second miss
+    cache.insert(key(3), Bytes::from(vec![3u8; 100]));
+    assert_eq!(cache.stats().evictions, 0);
+    cache.insert(key(3), Bytes::from(vec![3u8; 100]));
+
+    assert!(cache.get(&key(1)).is_none());
+    assert_eq!(cache.get(&key(0)).unwrap()[0], 0);
+    assert_eq!(cache.get(&key(3)).unwrap()[0], 3);
+
+    let stats = cache.stats();
+    assert_eq!(stats.hits, 3);
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.evictions, 1);
+    assert_eq!(stats.blocks, 3);
+    assert_eq!(stats.bytes, 300);
+
+    // A different file version is a different key
+    assert!(cache.get(&BlockKey::new("clip", "\"v2\"", 0)).is_none());
+    assert!(!BlockCache::new(0).is_enabled());
+}
+
+#[actix_web::test]
+async fn test_admitted_block_frees_its_candidate_slot() {
+    // Two blocks of room, so at most two keys wait for their second miss
+    let cache = BlockCache::with_block_size(200, 100);
+    let key = |index| BlockKey::new("clip", "\"v1\"", index);
+    let block = |index: u8| Bytes::from(vec![index; 100]);
+
+    cache.insert(key(0), block(0));
+    cache.insert(key(1), block(1));
+
+    cache.insert(key(10), block(10));
+    cache.insert(key(11), block(11));
+    cache.insert(key(11), block(11));
+    assert_eq!(cache.get(&key(11)).unwrap()[0], 11);
+
+    // Block 11 no longer waits, so a new candidate leaves block 10 in line
+    cache.insert(key(12), block(12));
+    cache.insert(key(10), block(10));
+    assert_eq!(cache.get(&key(10)).unwrap()[0], 10);
+}
+
+#[actix_web::test]
+async fn test_cached_stream_spans_blocks() {
+    let path = std::env::temp_dir().join(format!("block_cache_{}.webm", std::process::id()));
+    let data: Vec<u8> = (0..1000).map(|i| (i % 251) as u8).collect();
+    std::fs::write(&path, &data).expect("Failed to write test video");
+
+    let cache = BlockCache::with_block_size(10_000, 128);
+    let first = collect(cached_stream_file_range(cache.clone(), "clip", "\"v1\"", &path, 100, 500)).await;
+    assert_eq!(first, &data[100..600]);
+    let misses = cache.stats().misses;
+    assert_eq!(misses, 5);
+
+    // The same bytes are now served from memory, even with the file gone
+    std::fs::remove_file(&path).expect("Failed to remove test video");
+    let second = collect(cached_stream_file_range(cache.clone(), "clip", "\"v1\"", &path, 150, 400)).await;
+    assert_eq!(second, &data[150..550]);
+    assert_eq!(cache.stats().misses, misses);
+    assert!(cache.stats().hits >= 4);
+}
+
+#[actix_web::test]
+async fn test_large_sequential_read_keeps_hot_blocks() {
+    let dir = std::env::temp_dir().join(format!("block_cache_scan_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("Failed to create scratch directory");
+    let hot = dir.join("hot.webm");
+    let large = dir.join("large.webm");
+    let data: Vec<u8> = (0..10_000).map(|i| (i % 251) as u8).collect();
+    std::fs::write(&hot, &data[..256]).expect("Failed to write hot video");
+    std::fs::write(&large, &data).expect("Failed to write large video");
+
+    // Room for four blocks: two hot ones and two to spare
+    let cache = BlockCache::with_block_size(512, 128);
+    collect(cached_stream_file_range(cache.clone(), "hot", "\"v1\"", &hot, 0, 256)).await;
+
+    let full = collect(cached_stream_file_range(cache.clone(), "large", "\"v1\"", &large, 0, 10_000)).await;
+    assert_eq!(full, data);
+    assert_eq!(cache.stats().evictions, 0);
+
+    // The hot blocks survived the scan and are served from memory
+    let misses = cache.stats().misses;
+    let again = collect(cached_stream_file_range(cache.clone(), "hot", "\"v1\"", &hot, 0, 256)).await;
+    assert_eq!(again, &data[..256]);
+    assert_eq!(cache.stats().misses, misses);
+
+    // A range that is requested again is admitted on its second miss
+    for _ in 0..2 {
+        collect(cached_stream_file_range(cache.clone(), "large", "\"v1\"", &large, 5000, 200)).await;
+    }
+    assert!(cache.stats().evictions > 0);
+    let misses = cache.stats().misses;
+    collect(cached_stream_file_range(cache.clone(), "large", "\"v1\"", &large, 5000, 200)).await;
+    assert_eq!(cache.stats().misses, misses);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_catalog_stream_uses_shared_cache() {
+    let root = std::env::temp_dir().join(format!("block_cache_http_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).expect("Failed to create media root");
+    let data: Vec<u8> = (0..200_000).map(|i| (i % 251) as u8).collect();
+    std::fs::write(root.join("clip.webm"), &data).expect("Failed to write clip.webm");
+
+    let cache = BlockCache::new(1024 * 1024);
+    let repository: SharedVideoRepository = Arc::new(DirectoryVideoRepository::scan(&root).expect("Failed to scan media root"));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(repository))
+            .app_data(web::Data::new(Config::new()))
+            .app_data(web::Data::new(cache.clone()))
+            .route("/videos/{video_id}/stream", web::get().to(handle_catalog_video_stream))
+    ).await;
+
+    for _ in 0..2 {
+        let req = test::TestRequest::get()
+            .uri("/videos/clip/stream")
+            .insert_header(("Range", "bytes=1000-150000"))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 206);
+        assert_eq!(test::read_body(resp).await.as_ref(), &data[1000..=150000]);
+    }
+
+    let stats = cache.stats();
+    assert_eq!(stats.misses, 3);
+    assert_eq!(stats.hits, 3);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
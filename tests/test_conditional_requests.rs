@@ -0,0 +1,154 @@
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+use actix_web::{test, web, App};
+use actix_web::http::header::HttpDate;
+use video_streaming_api::{
+    domain::conditional::{
+        ConditionalOutcome, ConditionalRequest, EntityTag, EntityTagMatch, IfRange, Validators,
+    },
+    infrastructure::http::handle_catalog_video_stream,
+    infrastructure::cache::BlockCache,
+    infrastructure::repositories::{DirectoryVideoRepository, SharedVideoRepository},
+    shared::config::Config,
+};
+
+/// Helper function to build validators for a file modified at the given second
+fn validators(modified_secs: u64) -> Validators {
+    Validators::from_file_attributes(1000, Some(UNIX_EPOCH + Duration::from_millis(modified_secs * 1000 + 250)), Some(42))
+}
+
+#[actix_web::test]
+async fn test_entity_tag_parsing_and_comparison() {
+    let strong = EntityTag::parse("\"abc\"").unwrap();
+    let weak = EntityTag::parse("W/\"abc\"").unwrap();
+    assert!(!strong.weak);
+    assert!(weak.weak);
+    assert_eq!(weak.to_string(), "W/\"abc\"");
+
+    assert!(strong.weak_eq(&weak));
+    assert!(!strong.strong_eq(&weak));
+    assert!(strong.strong_eq(&EntityTag::strong("abc".to_string())));
+
+    assert!(EntityTag::parse("abc").is_none());
+    assert_eq!(EntityTagMatch::parse("*"), Some(EntityTagMatch::Any));
+    assert_eq!(
+        EntityTagMatch::parse("\"a\", W/\"b\""),
+        Some(EntityTagMatch::Tags(vec![EntityTag::strong("a".to_string()), EntityTag::weak("b".to_string())]))
+    );
+}
+
+#[actix_web::test]
+async fn test_etag_changes_with_file_version() {
+    assert_eq!(validators(100), validators(100));
+    assert_ne!(validators(100).etag, validators(101).etag);
+    assert_ne!(
+        validators(100).etag,
+        Validators::from_file_attributes(1001, validators(100).last_modified, Some(42)).etag
+    );
+    assert!(!validators(100).etag.weak);
+}
+
+#[actix_web::test]
+async fn test_conditional_evaluation() {
+    let current = validators(100);
+    let proceed = ConditionalOutcome::Proceed { honor_range: true };
+
+    let request = ConditionalRequest {
+        if_none_match: Some(EntityTagMatch::Tags(vec![EntityTag::weak(current.etag.tag.clone())])),
+        ..Default::default()
+    };
+    assert_eq!(request.evaluate(&current), ConditionalOutcome::NotModified);
+
+    let request = ConditionalRequest {
+        if_modified_since: Some(UNIX_EPOCH + Duration::from_secs(100)),
+        ..Default::default()
+    };
+    assert_eq!(request.evaluate(&current), ConditionalOutcome::NotModified);
+
+    let request = ConditionalRequest {
+        if_modified_since: Some(UNIX_EPOCH + Duration::from_secs(99)),
+        ..Default::default()
+    };
+    assert_eq!(request.evaluate(&current), proceed);
+
+    // If-None-Match wins over If-Modified-Since
+    let request = ConditionalRequest {
+        if_none_match: Some(EntityTagMatch::Tags(vec![EntityTag::strong("other".to_string())])),
+        if_modified_since: Some(UNIX_EPOCH + Duration::from_secs(100)),
+        ..Default::default()
+    };
+    assert_eq!(request.evaluate(&current), proceed);
+
+    // If-Range requires a strong match or the exact modification date
+    let if_range = |if_range| ConditionalRequest { if_range: Some(if_range), ..Default::default() };
+    assert_eq!(if_range(IfRange::EntityTag(current.etag.clone())).evaluate(&current), proceed);
+    assert_eq!(
+        if_range(IfRange::EntityTag(EntityTag::weak(current.etag.tag.clone()))).evaluate(&current),
+        ConditionalOutcome::Proceed { honor_range: false }
+    );
+    assert_eq!(if_range(IfRange::Date(UNIX_EPOCH + Duration::from_secs(100))).evaluate(&current), proceed);
+    assert_eq!(
+        if_range(IfRange::Date(UNIX_EPOCH + Duration::from_secs(50))).evaluate(&current),
+        ConditionalOutcome::Proceed { honor_range: false }
+    );
+}
+
+#[actix_web::test]
+async fn test_conditional_stream_responses() {
+    let root = std::env::temp_dir().join(format!("conditional_requests_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).expect("Failed to create media root");
+    std::fs::write(root.join("clip.webm"), [7u8; 100]).expect("Failed to write clip.webm");
+
+    let repository: SharedVideoRepository = Arc::new(DirectoryVideoRepository::scan(&root).expect("Failed to scan media root"));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(repository))
+            .app_data(web::Data::new(Config::new()))
+            .app_data(web::Data::new(BlockCache::new(1024 * 1024)))
+            .route("/videos/{video_id}/stream", web::get().to(handle_catalog_video_stream))
+    ).await;
+
+    let req = test::TestRequest::get().uri("/videos/clip/stream").to_request();
+    let resp = test::call_service(&app, req).await;
+    let etag = resp.headers().get("ETag").expect("Missing ETag").to_str().unwrap().to_string();
+    let last_modified = resp.headers().get("Last-Modified").expect("Missing Last-Modified").to_str().unwrap().to_string();
+    assert!(etag.starts_with('"'));
+    assert!(last_modified.parse::<HttpDate>().is_ok());
+
+    let req = test::TestRequest::get()
+        .uri("/videos/clip/stream")
+        .insert_header(("If-None-Match", etag.as_str()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 304);
+    assert_eq!(resp.headers().get("ETag").unwrap(), etag.as_str());
+    assert!(test::read_body(resp).await.is_empty());
+
+    let req = test::TestRequest::get()
+        .uri("/videos/clip/stream")
+        .insert_header(("If-Modified-Since", last_modified.as_str()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 304);
+
+    // Matching If-Range keeps the requested range
+    let req = test::TestRequest::get()
+        .uri("/videos/clip/stream")
+        .insert_header(("Range", "bytes=0-9"))
+        .insert_header(("If-Range", etag.as_str()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(test::read_body(resp).await.len(), 10);
+
+    // Stale If-Range sends the whole file
+    let req = test::TestRequest::get()
+        .uri("/videos/clip/stream")
+        .insert_header(("Range", "bytes=0-9"))
+        .insert_header(("If-Range", "\"stale\""))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(test::read_body(resp).await.len(), 100);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
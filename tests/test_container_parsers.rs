@@ -0,0 +1,199 @@
+use std::io::Cursor;
+use video_streaming_api::{
+    domain::common::FilePath,
+    domain::container::{NativeMediaProbe, parse_container, parse_matroska, parse_mp4},
+    domain::media::{MediaProbe, MediaStreamKind},
+    domain::video::VideoMetadata,
+};
+
+/// Helper function to build an ISO-BMFF box
+fn mp4_box(box_type: &[u8; 4], body: &[u8]) -> Vec<u8> {
+    let mut data = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+    data.extend_from_slice(box_type);
+    data.extend_from_slice(body);
+    data
+}
+
+fn full_box(box_type: &[u8; 4], version: u8, body: &[u8]) -> Vec<u8> {
+    let mut data = vec![version, 0, 0, 0];
+    data.extend_from_slice(body);
+    mp4_box(box_type, &data)
+}
+
+/// Timescale and duration fields of a version 0 `mvhd`/`mdhd` box
+fn media_header(box_type: &[u8; 4], timescale: u32, duration: u32) -> Vec<u8> {
+    let mut body = vec![0u8; 8];
+    body.extend_from_slice(&timescale.to_be_bytes());
+    body.extend_from_slice(&duration.to_be_bytes());
+    body.extend_from_slice(&[0u8; 4]);
+    full_box(box_type, 0, &body)
+}
+
+fn mp4_track(handler: &[u8; 4], codec: &[u8; 4], width: u16, height: u16, timescale: u32, samples: u32, delta: u32) -> Vec<u8> {
+    let mut tkhd = vec![0u8; 20 + 8 + 36];
+    tkhd.extend_from_slice(&((width as u32) << 16).to_be_bytes());
+    tkhd.extend_from_slice(&((height as u32) << 16).to_be_bytes());
+
+    let mut hdlr = vec![0u8; 4];
+    hdlr.extend_from_slice(handler);
+    hdlr.extend_from_slice(&[0u8; 13]);
+
+    let mut sample_entry = vec![0u8; 24];
+    sample_entry.extend_from_slice(&width.to_be_bytes());
+    sample_entry.extend_from_slice(&height.to_be_bytes());
+    let mut stsd = 1u32.to_be_bytes().to_vec();
+    stsd.extend_from_slice(&mp4_box(codec, &sample_entry));
+
+    let mut stts = 1u32.to_be_bytes().to_vec();
+    stts.extend_from_slice(&samples.to_be_bytes());
+    stts.extend_from_slice(&delta.to_be_bytes());
+
+    let stbl = [full_box(b"stsd", 0, &stsd), full_box(b"stts", 0, &stts)].concat();
+    let minf = mp4_box(b"stbl", &stbl);
+    let mdia = [
+        media_header(b"mdhd", timescale, samples * delta),
+        full_box(b"hdlr", 0, &hdlr),
+        mp4_box(b"minf", &minf),
+    ].concat();
+
+    mp4_box(b"trak", &[full_box(b"tkhd", 0, &tkhd), mp4_box(b"mdia", &mdia)].concat())
+}
+
+/// A 10 second MP4 with an H.264 and an AAC track; `moov` after `mdat` like a non-faststart file
+fn mp4_fixture() -> Vec<u8> {
+    let moov = [
+        media_header(b"mvhd", 1000, 10_000),
+        mp4_track(b"vide", b"avc1", 1280, 720, 30_000, 300, 1000),
+        mp4_track(b"soun", b"mp4a", 0, 0, 48_000, 469, 1024),
+    ].concat();
+
+    let mut ftyp = b"isom".to_vec();
+    ftyp.extend_from_slice(&[0, 0, 2, 0]);
+    ftyp.extend_from_slice(b"isomavc1");
+
+    [mp4_box(b"ftyp", &ftyp), mp4_box(b"mdat", &[0u8; 4096]), mp4_box(b"moov", &moov)].concat()
+}
+
+/// Helper function to build an EBML element with a one-byte-marker 8-byte size
+fn ebml(id: u32, body: &[u8]) -> Vec<u8> {
+    let mut data: Vec<u8> = id.to_be_bytes().iter().copied().skip_while(|&b| b == 0).collect();
+    data.push(0x01);
+    data.extend_from_slice(&(body.len() as u64).to_be_bytes()[1..]);
+    data.extend_from_slice(body);
+    data
+}
+
+fn ebml_uint(id: u32, value: u64) -> Vec<u8> {
+    ebml(id, &value.to_be_bytes())
+}
+
+/// A 12.5 second WebM with a VP9 and an Opus track, followed by a cluster
+fn webm_fixture() -> Vec<u8> {
+    let header = ebml(0x1A45DFA3, &[ebml_uint(0x4286, 1), ebml(0x4282, b"webm")].concat());
+    let info = ebml(0x1549A966, &[
+        ebml_uint(0x2AD7B1, 1_000_000),
+        ebml(0x4489, &12_500.0f64.to_be_bytes()),
+    ].concat());
+    let video = ebml(0xAE, &[
+        ebml_uint(0xD7, 1),
+        ebml_uint(0x83, 1),
+        ebml(0x86, b"V_VP9"),
+        ebml_uint(0x23E383, 40_000_000),
+        ebml(0xE0, &[ebml_uint(0xB0, 640), ebml_uint(0xBA, 360)].concat()),
+    ].concat());
+    let audio = ebml(0xAE, &[ebml_uint(0xD7, 2), ebml_uint(0x83, 2), ebml(0x86, b"A_OPUS")].concat());
+    let tracks = ebml(0x1654AE6B, &[video, audio].concat());
+    let cluster = ebml(0x1F43B675, &[0u8; 2048]);
+
+    [header, ebml(0x18538067, &[info, tracks, cluster].concat())].concat()
+}
+
+/// Deterministic pseudo-random generator so fuzz-style tests are reproducible
+fn lcg(seed: &mut u64) -> u64 {
+    *seed = seed.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+    *seed >> 33
+}
+
+#[test]
+fn test_parse_mp4_fixture() {
+    let data = mp4_fixture();
+    let info = parse_mp4(&mut Cursor::new(&data), data.len() as u64).expect("Failed to parse MP4");
+
+    assert_eq!(info.format.as_deref(), Some("mp4"));
+    assert_eq!(info.duration, Some(10.0));
+    assert_eq!(info.timescale, Some(1000));
+    assert_eq!(info.bitrate, Some(data.len() as u64 * 8 / 10));
+    assert_eq!(info.streams.len(), 2);
+
+    let video = &info.streams[0];
+    assert_eq!(video.kind, MediaStreamKind::Video);
+    assert_eq!(video.codec.as_deref(), Some("avc1"));
+    assert_eq!((video.width, video.height), (Some(1280), Some(720)));
+    assert_eq!(video.frame_rate, Some(30.0));
+    assert_eq!(video.duration, Some(10.0));
+
+    let audio = &info.streams[1];
+    assert_eq!(audio.kind, MediaStreamKind::Audio);
+    assert_eq!(audio.codec.as_deref(), Some("mp4a"));
+    assert_eq!((audio.width, audio.height), (None, None));
+    assert_eq!(info.audio_codec(), Some("mp4a"));
+}
+
+#[test]
+fn test_parse_matroska_fixture() {
+    let data = webm_fixture();
+    let info = parse_matroska(&mut Cursor::new(&data), data.len() as u64).expect("Failed to parse WebM");
+
+    assert_eq!(info.format.as_deref(), Some("webm"));
+    assert_eq!(info.duration, Some(12.5));
+    assert_eq!(info.timescale, Some(1000));
+    assert_eq!(info.streams.len(), 2);
+    assert_eq!(info.video_codec(), Some("V_VP9"));
+    assert_eq!(info.audio_codec(), Some("A_OPUS"));
+    assert_eq!((info.width(), info.height()), (Some(640), Some(360)));
+    assert_eq!(info.frame_rate(), Some(25.0));
+}
+
+#[test]
+fn test_container_detection_and_metadata() {
+    for (name, data) in [("clip.mp4", mp4_fixture()), ("clip.webm", webm_fixture())] {
+        let info = parse_container(&mut Cursor::new(&data), data.len() as u64).expect("Failed to detect container");
+        assert!(info.duration.is_some());
+
+        let path = std::env::temp_dir().join(format!("container_{}_{}", std::process::id(), name));
+        std::fs::write(&path, &data).expect("Failed to write fixture");
+        let file_path = FilePath::new(path.to_string_lossy().to_string());
+
+        let media = NativeMediaProbe.probe(&file_path).expect("Failed to probe fixture");
+        let metadata = VideoMetadata::from_path(&file_path).unwrap().with_media_info(media);
+        assert_eq!(metadata.duration, info.duration);
+        assert!(metadata.bitrate.is_some());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    let garbage = vec![0x42u8; 64];
+    assert!(parse_container(&mut Cursor::new(&garbage), 64).is_err());
+}
+
+#[test]
+fn test_parsers_survive_truncated_and_corrupted_input() {
+    for data in [mp4_fixture(), webm_fixture()] {
+        // Every prefix must parse or fail cleanly
+        for length in 0..data.len() {
+            let prefix = &data[..length];
+            let _ = parse_container(&mut Cursor::new(prefix), length as u64);
+            // A wrong length hint must not break the parsers either
+            let _ = parse_container(&mut Cursor::new(prefix), data.len() as u64);
+        }
+
+        let mut seed = 42;
+        for _ in 0..2000 {
+            let mut mutated = data.clone();
+            for _ in 0..1 + lcg(&mut seed) % 8 {
+                let index = (lcg(&mut seed) as usize) % mutated.len();
+                mutated[index] = lcg(&mut seed) as u8;
+            }
+            let _ = parse_container(&mut Cursor::new(&mutated), mutated.len() as u64);
+        }
+    }
+}
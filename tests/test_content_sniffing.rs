@@ -0,0 +1,122 @@
+use std::path::Path;
+use std::sync::Arc;
+use actix_web::{test, web, App};
+use video_streaming_api::{
+    domain::common::{ContentType, DomainError, FilePath},
+    domain::container::{content_type_from_extension, detect_content_type, sniff_content_type},
+    domain::video::{VideoMetadata, get_video_metadata},
+    infrastructure::cache::BlockCache,
+    infrastructure::http::handle_catalog_video_stream,
+    infrastructure::repositories::{DirectoryVideoRepository, SharedVideoRepository},
+    shared::config::Config,
+};
+
+/// Helper function to build an EBML header declaring the given DocType
+fn ebml_header(doc_type: &str) -> Vec<u8> {
+    let mut doc_type_element = vec![0x42, 0x82, 0x80 | doc_type.len() as u8];
+    doc_type_element.extend_from_slice(doc_type.as_bytes());
+
+    let mut header = vec![0x1A, 0x45, 0xDF, 0xA3, 0x80 | doc_type_element.len() as u8];
+    header.extend_from_slice(&doc_type_element);
+    header.extend_from_slice(&[0x18, 0x53, 0x80, 0x67, 0x01, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+    header
+}
+
+fn ftyp(brand: &[u8; 4]) -> Vec<u8> {
+    let mut data = vec![0, 0, 0, 16];
+    data.extend_from_slice(b"ftyp");
+    data.extend_from_slice(brand);
+    data.extend_from_slice(&[0, 0, 0, 0]);
+    data
+}
+
+fn mpeg_ts(packet_size: usize, offset: usize) -> Vec<u8> {
+    let mut data = vec![0xFFu8; packet_size * 4];
+    for packet in 0..4 {
+        data[packet * packet_size + offset] = 0x47;
+    }
+    data
+}
+
+#[actix_web::test]
+async fn test_sniff_container_signatures() {
+    assert_eq!(sniff_content_type(&ftyp(b"isom")), Some("video/mp4"));
+    assert_eq!(sniff_content_type(&ftyp(b"qt  ")), Some("video/quicktime"));
+    assert_eq!(sniff_content_type(&ebml_header("webm")), Some("video/webm"));
+    assert_eq!(sniff_content_type(&ebml_header("matroska")), Some("video/x-matroska"));
+    assert_eq!(sniff_content_type(b"RIFF\x00\x10\x00\x00AVI LIST"), Some("video/x-msvideo"));
+    assert_eq!(sniff_content_type(b"RIFF\x00\x10\x00\x00WAVEfmt "), None);
+    assert_eq!(sniff_content_type(b"OggS\x00\x02"), Some("video/ogg"));
+    assert_eq!(sniff_content_type(&mpeg_ts(188, 0)), Some("video/mp2t"));
+    assert_eq!(sniff_content_type(&mpeg_ts(192, 4)), Some("video/mp2t"));
+
+    // A single sync byte is not enough to call it a transport stream
+    assert_eq!(sniff_content_type(&[0x47, 0, 0, 0]), None);
+    assert_eq!(sniff_content_type(&[]), None);
+    assert_eq!(sniff_content_type(b"plain text, not a video"), None);
+
+    assert_eq!(content_type_from_extension(Path::new("clip.MKV")), Some("video/x-matroska"));
+    assert_eq!(content_type_from_extension(Path::new("clip")), None);
+    assert!(ContentType::new("video/mp2t".to_string()).is_ok());
+    assert!(ContentType::new("video/ogg".to_string()).is_ok());
+}
+
+#[actix_web::test]
+async fn test_signature_wins_over_extension() {
+    let dir = std::env::temp_dir().join(format!("content_sniffing_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("Failed to create test directory");
+
+    let misnamed = dir.join("really_mp4.webm");
+    std::fs::write(&misnamed, ftyp(b"isom")).unwrap();
+    let extensionless = dir.join("movie");
+    std::fs::write(&extensionless, ebml_header("webm")).unwrap();
+    let unknown = dir.join("notes");
+    std::fs::write(&unknown, b"plain text").unwrap();
+    let fallback = dir.join("clip.mov");
+    std::fs::write(&fallback, [0u8; 32]).unwrap();
+
+    assert_eq!(detect_content_type(&misnamed), Some("video/mp4"));
+    assert_eq!(detect_content_type(&extensionless), Some("video/webm"));
+    assert_eq!(detect_content_type(&unknown), None);
+    assert_eq!(detect_content_type(&fallback), Some("video/quicktime"));
+
+    let metadata = VideoMetadata::from_path(&FilePath::new(misnamed.to_string_lossy().to_string())).unwrap();
+    assert_eq!(metadata.content_type.as_str(), "video/mp4");
+    assert!(matches!(
+        VideoMetadata::from_path(&FilePath::new(unknown.to_string_lossy().to_string())),
+        Err(DomainError::Unsupported(_))
+    ));
+
+    assert_eq!(get_video_metadata(extensionless.to_str().unwrap()).unwrap().content_type, "video/webm");
+    assert_eq!(get_video_metadata(unknown.to_str().unwrap()).unwrap().content_type, "application/octet-stream");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_catalog_serves_sniffed_content_type() {
+    let root = std::env::temp_dir().join(format!("content_sniffing_http_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).expect("Failed to create media root");
+    std::fs::write(root.join("clip.webm"), ftyp(b"isom")).unwrap();
+    std::fs::write(root.join("movie"), ebml_header("matroska")).unwrap();
+
+    let repository: SharedVideoRepository = Arc::new(DirectoryVideoRepository::scan(&root).expect("Failed to scan media root"));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(repository))
+            .app_data(web::Data::new(Config::new()))
+            .app_data(web::Data::new(BlockCache::new(0)))
+            .route("/videos/{video_id}/stream", web::get().to(handle_catalog_video_stream))
+    ).await;
+
+    for (uri, content_type) in [("/videos/clip/stream", "video/mp4"), ("/videos/movie/stream", "video/x-matroska")] {
+        let req = test::TestRequest::get().uri(uri).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 200);
+        assert_eq!(resp.headers().get("Content-Type").unwrap(), content_type);
+    }
+
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,199 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use actix_web::{test, web, App};
+use video_streaming_api::{
+    application::services::VideoCreationAppService,
+    application::dto::ProblemDetails,
+    domain::common::DomainError,
+    domain::video::VideoId,
+    domain::video_creation::VideoCreationManager,
+    infrastructure::http::{handle_create_video, handle_create_video_query, parse_json_body},
+    application::dto::CreateVideoRequest,
+    infrastructure::jobs::VideoCreationWorkerPool,
+    infrastructure::repositories::InMemoryVideoCreationRepository,
+    shared::config::Config,
+};
+
+macro_rules! init_app {
+    () => {
+        test::init_service(
+            App::new()
+                .app_data(web::Data::new(VideoCreationAppService::new(
+                    Config::new(),
+                    VideoCreationWorkerPool::start(Arc::new(InMemoryVideoCreationRepository::new()), 1),
+                )))
+                .route("/videos", web::post().to(handle_create_video))
+                .route("/create-video", web::post().to(handle_create_video_query))
+        ).await
+    };
+}
+
+/// Helper function to create a directory with a few (fake) images
+fn create_images(name: &str, count: usize) -> (PathBuf, Vec<String>) {
+    let dir = std::env::temp_dir().join(format!("create_video_api_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("Failed to create image directory");
+
+    let paths = (0..count)
+        .map(|i| {
+            let path = dir.join(format!("image{}.png", i));
+            std::fs::write(&path, b"not really a png").expect("Failed to write image");
+            path.to_string_lossy().to_string()
+        })
+        .collect();
+    (dir, paths)
+}
+
+fn field_errors(body: &ProblemDetails) -> Vec<&str> {
+    body.errors.iter().map(|f| f.field.as_str()).collect()
+}
+
+#[actix_web::test]
+async fn test_parse_json_body_reports_field_paths() {
+    let parsed: CreateVideoRequest = parse_json_body(br#"{
+        "video_id": "clip",
+        "images": [{"path": "a.png"}, {"path": "b.png", "duration": 3}],
+        "output": {"path": "out.mp4", "width": 640}
+    }"#).expect("Failed to parse request");
+    assert_eq!(parsed.images.len(), 2);
+    assert_eq!(parsed.images[1].duration, Some(3));
+    assert_eq!(parsed.output.width, Some(640));
+    assert_eq!(parsed.output.height, None);
+
+    let cases: [(&[u8], &str); 5] = [
+        (br#"{"video_id": "clip", "images": [{"path": "a.png"}, {"path": "b.png", "duration": "two"}], "output": {"path": "out.mp4"}}"#, "images[1].duration"),
+        (br#"{"video_id": "clip", "images": []}"#, "output"),
+        (br#"{"video_id": "clip", "images": [{"duration": 1}], "output": {"path": "out.mp4"}}"#, "images[0].path"),
+        (br#"{"video_id": "clip", "images": [], "output": {"path": "out.mp4", "fps": 30}}"#, "output.fps"),
+        (b"not json", "body"),
+    ];
+    for (body, field) in cases {
+        match parse_json_body::<CreateVideoRequest>(body) {
+            Err(DomainError::Validation(errors)) => {
+                assert_eq!(errors[0].field, field, "unexpected field for {}", String::from_utf8_lossy(body));
+            }
+            other => panic!("expected a validation error, got {:?}", other),
+        }
+    }
+}
+
+#[actix_web::test]
+async fn test_create_video_rejects_malformed_body() {
+    let app = init_app!();
+
+    let req = test::TestRequest::post()
+        .uri("/videos")
+        .insert_header(("Content-Type", "application/json"))
+        .set_payload(r#"{"video_id": "clip", "images": [{"path": "a.png", "duration": -1}], "output": {"path": "out.mp4"}}"#)
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    assert_eq!(resp.headers().get("Content-Type").unwrap(), "application/problem+json");
+    let body: ProblemDetails = test::read_body_json(resp).await;
+    assert_eq!(body.code, "validation_failed");
+    assert_eq!(field_errors(&body), vec!["images[0].duration"]);
+
+    let req = test::TestRequest::post()
+        .uri("/videos")
+        .insert_header(("Content-Type", "application/x-www-form-urlencoded"))
+        .set_payload("video_id=clip")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 415);
+    let body: ProblemDetails = test::read_body_json(resp).await;
+    assert_eq!(body.code, "unsupported_media_type");
+}
+
+#[actix_web::test]
+async fn test_create_video_validates_fields() {
+    let app = init_app!();
+    let (dir, images) = create_images("validate", 2);
+
+    let request = serde_json::json!({
+        "video_id": " ",
+        "images": [
+            {"path": images[0]},
+            {"path": images[1], "duration": 0},
+            {"path": dir.join("missing.png").to_string_lossy()},
+            {"path": dir.join("notes.txt").to_string_lossy()},
+        ],
+        "output": {"path": "", "width": 0},
+    });
+    std::fs::write(dir.join("notes.txt"), b"text").unwrap();
+
+    let req = test::TestRequest::post().uri("/videos").set_json(&request).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    let body: ProblemDetails = test::read_body_json(resp).await;
+    assert_eq!(body.instance.as_deref(), Some("/videos"));
+    assert_eq!(field_errors(&body), vec![
+        "video_id",
+        "images[1].duration",
+        "images[2].path",
+        "images[3].path",
+        "output.path",
+        "output.width",
+    ]);
+    assert_eq!(body.errors[2].message, "image file does not exist");
+    assert_eq!(body.errors[3].message, "unsupported image format");
+
+    // A valid request gets past validation and is queued; FFmpeg decides the rest
+    let request = serde_json::json!({
+        "video_id": "clip",
+        "images": [{"path": images[0]}, {"path": images[1], "duration": 2}],
+        "output": {"path": dir.join("out.mp4").to_string_lossy()},
+    });
+    let req = test::TestRequest::post().uri("/videos").set_json(&request).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 202);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_legacy_query_alias_uses_same_validation() {
+    let app = init_app!();
+
+    let req = test::TestRequest::post()
+        .uri("/create-video?video_id=clip&output_path=out.mp4&image1=does/not/exist.png")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    let body: ProblemDetails = test::read_body_json(resp).await;
+    assert_eq!(field_errors(&body), vec!["images[0].path"]);
+
+    let req = test::TestRequest::post()
+        .uri("/create-video?video_id=clip&output_path=out.mp4&image1=does/not/exist.png")
+        .insert_header(("Accept", "text/plain"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert_eq!(body, "One or more fields are invalid\n  images[0].path: image file does not exist");
+}
+
+#[actix_web::test]
+async fn test_per_image_durations() {
+    let (dir, images) = create_images("durations", 3);
+
+    let job = VideoCreationManager::create_job_with_durations(
+        images.clone(),
+        vec![None, Some(4), None],
+        "out.mp4".to_string(),
+        VideoId::new("clip".to_string()),
+        None,
+    ).expect("Failed to create job");
+    assert_eq!(job.request.image_duration(0), 1);
+    assert_eq!(job.request.image_duration(1), 4);
+    assert_eq!(job.request.total_duration(), 6);
+
+    let mismatched = VideoCreationManager::create_job_with_durations(
+        images,
+        vec![Some(1)],
+        "out.mp4".to_string(),
+        VideoId::new("clip".to_string()),
+        None,
+    );
+    assert!(mismatched.is_err());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,188 @@
+use std::sync::Arc;
+use std::time::Duration;
+use actix_web::{test, web, App};
+use video_streaming_api::{
+    application::dto::{CreateVideoResponse, ProblemDetails},
+    application::services::VideoCreationAppService,
+    domain::video::VideoId,
+    domain::video_creation::{
+        EncoderProfile, EncoderQuality, VideoContainer, VideoCreationJobId, VideoCreationManager, VideoCreationRepository,
+        VideoCreationStatus, ENCODER_PROFILE_NAMES,
+    },
+    infrastructure::ffmpeg::FFmpegCommandBuilder,
+    infrastructure::http::handle_create_video,
+    infrastructure::jobs::VideoCreationWorkerPool,
+    infrastructure::repositories::{InMemoryVideoCreationRepository, SharedVideoCreationRepository},
+    infrastructure::sqlite::{SqliteDatabase, SqliteVideoCreationRepository},
+    shared::config::Config,
+};
+
+mod common;
+use common::{scratch_dir, write_fake_ffmpeg};
+
+fn profile_args(profile: &EncoderProfile) -> String {
+    FFmpegCommandBuilder::default().encoder_profile(profile).args().join(" ")
+}
+
+#[actix_web::test]
+async fn test_builtin_profiles_translate_to_ffmpeg_arguments() {
+    for name in ENCODER_PROFILE_NAMES {
+        assert_eq!(EncoderProfile::named(name).unwrap().name, *name);
+    }
+    assert!(EncoderProfile::named("mpeg1").is_err());
+    assert_eq!(EncoderProfile::default().name, "h264-web");
+
+    assert_eq!(
+        profile_args(&EncoderProfile::named("h264-web").unwrap()),
+        "-c:v libx264 -pix_fmt yuv420p -crf 23 -preset veryfast -g 2 -f mp4 -movflags +faststart",
+    );
+    assert_eq!(
+        profile_args(&EncoderProfile::named("vp9-webm").unwrap()),
+        "-c:v libvpx-vp9 -pix_fmt yuv420p -crf 33 -b:v 0 -g 2 -f webm",
+    );
+    assert_eq!(
+        profile_args(&EncoderProfile::named("av1-archive").unwrap()),
+        "-c:v libsvtav1 -pix_fmt yuv420p10le -crf 28 -preset 4 -g 10 -f matroska",
+    );
+    assert_eq!(
+        profile_args(&EncoderProfile::named("prores-proxy").unwrap()),
+        "-c:v prores_ks -profile:v 0 -pix_fmt yuv422p10le -f mov",
+    );
+
+    let bitrate = EncoderProfile { quality: Some(EncoderQuality::BitrateKbps(2500)), ..EncoderProfile::default() };
+    assert!(profile_args(&bitrate).contains("-b:v 2500k"));
+
+    assert!(VideoContainer::Mp4.accepts_path("/videos/out.MP4"));
+    assert!(VideoContainer::Mp4.accepts_path("/videos/out"));
+    assert!(!VideoContainer::WebM.accepts_path("/videos/out.mp4"));
+}
+
+#[actix_web::test]
+async fn test_config_defaults_and_overrides() {
+    let mut config = Config::new();
+    assert_eq!(config.default_encoder_profile().unwrap(), EncoderProfile::default());
+
+    // FFMPEG_CODEC and FFMPEG_PIXEL_FORMAT adjust the default profile only
+    config.encoder_profile = "av1-archive".to_string();
+    config.ffmpeg_codec = Some("libaom-av1".to_string());
+    config.ffmpeg_pixel_format = Some("yuv420p".to_string());
+    let profile = config.default_encoder_profile().unwrap();
+    assert_eq!((profile.name.as_str(), profile.codec.as_str()), ("av1-archive", "libaom-av1"));
+    assert_eq!(profile.pixel_format, "yuv420p");
+    assert_eq!(profile.container, VideoContainer::Matroska);
+    assert!(config.validate().is_ok());
+
+    // Options of the profile's own encoder are dropped with it
+    assert_eq!(profile.preset, None);
+    config.encoder_profile = "h264-web".to_string();
+    config.ffmpeg_codec = Some("libvpx-vp9".to_string());
+    assert_eq!(
+        profile_args(&config.default_encoder_profile().unwrap()),
+        "-c:v libvpx-vp9 -pix_fmt yuv420p -crf 23 -b:v 0 -g 2 -f mp4 -movflags +faststart",
+    );
+    assert!(config.validate().is_ok());
+    config.encoder_profile = "prores-proxy".to_string();
+    config.ffmpeg_codec = Some("prores_aw".to_string());
+    assert_eq!(config.default_encoder_profile().unwrap().codec_profile, None);
+
+    // Codecs the profile's container cannot hold are refused
+    config.encoder_profile = "vp9-webm".to_string();
+    config.ffmpeg_codec = Some("libx264".to_string());
+    assert!(config.validate().unwrap_err().contains("FFMPEG_CODEC libx264"));
+    assert!(VideoContainer::QuickTime.accepts_codec("prores_ks"));
+    assert!(!VideoContainer::Mp4.accepts_codec("prores_ks"));
+    assert!(VideoContainer::WebM.accepts_codec("some_future_codec"));
+    config.ffmpeg_codec = None;
+
+    config.encoder_profile = "mpeg1".to_string();
+    assert!(config.default_encoder_profile().is_err());
+    assert!(config.validate().unwrap_err().contains("ENCODER_PROFILE"));
+}
+
+#[actix_web::test]
+async fn test_profiles_survive_a_sqlite_round_trip() {
+    let (dir, image) = scratch_dir("sqlite");
+    let repository = SqliteVideoCreationRepository::new(SqliteDatabase::open_in_memory().unwrap());
+
+    let mut job = VideoCreationManager::create_job(
+        vec![image],
+        dir.join("out.mov").to_string_lossy().to_string(),
+        VideoId::new("proxy".to_string()),
+        None,
+    ).unwrap();
+    job.request = job.request.with_encoder_profile(EncoderProfile::named("prores-proxy").unwrap());
+    repository.save_job(&job).unwrap();
+
+    let stored = repository.find_job_by_id(&job.id).unwrap().unwrap();
+    assert_eq!(stored.request.encoder_profile, job.request.encoder_profile);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_requests_encode_with_the_configured_binary_and_chosen_profile() {
+    let (dir, image) = scratch_dir("http");
+    let mut config = Config::new();
+    // An FFmpeg stand-in that is not on PATH and records its arguments next to its output
+    config.ffmpeg_path = write_fake_ffmpeg(&dir, "echo \"$@\" > \"$output.args\"\n\
+        echo done > \"$output\"\n");
+    config.ffmpeg_codec = Some("libx265".to_string());
+
+    let repository: SharedVideoCreationRepository = Arc::new(InMemoryVideoCreationRepository::new());
+    let workers = VideoCreationWorkerPool::start_with_ffmpeg(repository.clone(), 2, &config.ffmpeg_path);
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(VideoCreationAppService::new(config, workers)))
+            .route("/videos", web::post().to(handle_create_video))
+    ).await;
+
+    let mut outputs = Vec::new();
+    for (output, profile) in [("default.mp4", None), ("chosen.webm", Some("vp9-webm"))] {
+        let output = dir.join(output);
+        let request = serde_json::json!({
+            "video_id": "profiles",
+            "images": [{"path": image}],
+            "output": {"path": output.to_string_lossy(), "profile": profile},
+        });
+        let req = test::TestRequest::post().uri("/videos").set_json(&request).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 202);
+        let created: CreateVideoResponse = test::read_body_json(resp).await;
+        outputs.push((VideoCreationJobId::new(created.job_id), output));
+    }
+
+    for (job_id, _) in &outputs {
+        for _ in 0..100 {
+            if repository.find_job_by_id(job_id).unwrap().unwrap().status == VideoCreationStatus::Completed {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert_eq!(repository.find_job_by_id(job_id).unwrap().unwrap().status, VideoCreationStatus::Completed);
+    }
+
+    // The binary is not on PATH, so these only exist if the configured path was run
+    let default_args = std::fs::read_to_string(outputs[0].1.with_extension("mp4.args")).unwrap();
+    assert!(default_args.contains("-c:v libx265 -pix_fmt yuv420p -crf 23"));
+    assert!(default_args.contains("-f mp4"));
+    let chosen_args = std::fs::read_to_string(outputs[1].1.with_extension("webm.args")).unwrap();
+    assert!(chosen_args.contains("-c:v libvpx-vp9 -pix_fmt yuv420p -crf 33 -b:v 0"));
+    assert!(chosen_args.contains("-f webm"));
+
+    // Unknown profiles and outputs the profile's container cannot hold are rejected
+    for (output, profile, field) in [("bad.mp4", "mpeg1", "output.profile"), ("bad.mp4", "vp9-webm", "output.path")] {
+        let request = serde_json::json!({
+            "video_id": "profiles",
+            "images": [{"path": image}],
+            "output": {"path": dir.join(output).to_string_lossy(), "profile": profile},
+        });
+        let req = test::TestRequest::post().uri("/videos").set_json(&request).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 422);
+        let problem: ProblemDetails = test::read_body_json(resp).await;
+        let fields: Vec<String> = problem.errors.into_iter().map(|error| error.field).collect();
+        assert_eq!(fields, vec![field]);
+    }
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,90 @@
+use actix_web::{test, ResponseError};
+use actix_web::http::StatusCode;
+use video_streaming_api::{
+    application::dto::ProblemDetails,
+    domain::common::{DomainError, FieldError},
+    infrastructure::responses::{problem_details, render_problem},
+};
+
+#[actix_web::test]
+async fn test_error_codes_and_statuses() {
+    let cases = [
+        (DomainError::not_found("video", "clip"), StatusCode::NOT_FOUND, "not_found"),
+        (DomainError::validation("images", "must not be empty"), StatusCode::UNPROCESSABLE_ENTITY, "validation_failed"),
+        (DomainError::Conflict("Job already started".to_string()), StatusCode::CONFLICT, "conflict"),
+        (DomainError::Unsupported("image format: a.gif".to_string()), StatusCode::UNPROCESSABLE_ENTITY, "unsupported"),
+        (
+            DomainError::UnsupportedMediaType("request body must be application/json".to_string()),
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "unsupported_media_type",
+        ),
+        (DomainError::external_tool("ffmpeg", "Unknown encoder"), StatusCode::BAD_GATEWAY, "external_tool_failed"),
+        (DomainError::Io("disk full".to_string()), StatusCode::INTERNAL_SERVER_ERROR, "io_error"),
+        (DomainError::RangeNotSatisfiable(100), StatusCode::RANGE_NOT_SATISFIABLE, "range_not_satisfiable"),
+        (DomainError::ProbeFailed("truncated".to_string()), StatusCode::UNPROCESSABLE_ENTITY, "probe_failed"),
+    ];
+
+    for (error, status, code) in cases {
+        assert_eq!(error.status_code(), status, "{:?}", error);
+        assert_eq!(error.code(), code);
+
+        let problem = problem_details(&error, None);
+        assert_eq!(problem.status, status.as_u16());
+        assert_eq!(problem.code, code);
+        assert_eq!(problem.problem_type, format!("/problems/{}", code.replace('_', "-")));
+    }
+}
+
+#[actix_web::test]
+async fn test_problem_details_extensions() {
+    let problem = problem_details(&DomainError::not_found("job", "job_1"), Some("/job/job_1"));
+    assert_eq!(problem.entity.as_deref(), Some("job"));
+    assert_eq!(problem.detail, "job not found: job_1");
+    assert_eq!(problem.instance.as_deref(), Some("/job/job_1"));
+
+    let problem = problem_details(&DomainError::Validation(vec![
+        FieldError::new("video_id", "must not be empty"),
+        FieldError::new("images[0].path", "image file does not exist"),
+    ]), None);
+    assert_eq!(problem.errors.len(), 2);
+    assert_eq!(problem.errors[1].field, "images[0].path");
+
+    // Clients only get the last line of the tool's output, without server paths
+    let stderr = format!("{}\n  configuration: --prefix=/usr --enable-gpl\n'/srv/media/in.png': No such file or directory\n", "x".repeat(10_000));
+    let problem = problem_details(&DomainError::external_tool("ffmpeg", stderr), None);
+    assert_eq!(problem.tool.as_deref(), Some("ffmpeg"));
+    assert_eq!(problem.detail, "ffmpeg exited with an error");
+    assert_eq!(problem.summary.as_deref(), Some("<path>: No such file or directory"));
+
+    let problem = problem_details(&DomainError::external_tool("ffmpeg", "y".repeat(10_000)), None);
+    assert_eq!(problem.summary.unwrap().len(), 200);
+
+    // Optional members are left out of the JSON entirely
+    let json = serde_json::to_value(problem_details(&DomainError::Conflict("busy".to_string()), None)).unwrap();
+    assert!(json.get("errors").is_none());
+    assert!(json.get("instance").is_none());
+}
+
+#[actix_web::test]
+async fn test_response_error_renders_problem_json() {
+    let resp = DomainError::RangeNotSatisfiable(1000).error_response();
+    assert_eq!(resp.status(), 416);
+    assert_eq!(resp.headers().get("Content-Type").unwrap(), "application/problem+json");
+    assert_eq!(resp.headers().get("Content-Range").unwrap(), "bytes */1000");
+
+    let req = test::TestRequest::get().uri("/videos/missing/stream").to_http_request();
+    let resp = render_problem(&req, &DomainError::not_found("video", "missing"));
+    assert_eq!(resp.status(), 404);
+    let body = actix_web::body::to_bytes(resp.into_body()).await.unwrap();
+    let problem: ProblemDetails = serde_json::from_slice(&body).unwrap();
+    assert_eq!(problem.instance.as_deref(), Some("/videos/missing/stream"));
+
+    let req = test::TestRequest::get()
+        .uri("/videos/missing/stream")
+        .insert_header(("Accept", "text/plain"))
+        .to_http_request();
+    let resp = render_problem(&req, &DomainError::not_found("video", "missing"));
+    assert_eq!(resp.headers().get("Content-Type").unwrap(), "text/plain");
+    let body = actix_web::body::to_bytes(resp.into_body()).await.unwrap();
+    assert_eq!(&body[..], b"video not found: missing");
+}
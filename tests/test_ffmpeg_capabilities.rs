@@ -0,0 +1,210 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use actix_web::{test, web, App};
+use video_streaming_api::{
+    application::dto::{CreateVideoResponse, HealthResponse, ProblemDetails},
+    application::services::VideoCreationAppService,
+    domain::video_creation::{EncoderProfile, VideoCreationJobId, VideoCreationRepository, VideoCreationStatus},
+    infrastructure::cache::BlockCache,
+    infrastructure::ffmpeg::{
+        parse_encoders, parse_filters, parse_formats, parse_version, FFmpegCapabilities, SharedFFmpegCapabilities,
+    },
+    infrastructure::http::{handle_create_video, handle_health_check},
+    infrastructure::jobs::VideoCreationWorkerPool,
+    infrastructure::repositories::{InMemoryVideoCreationRepository, SharedVideoCreationRepository},
+    shared::config::Config,
+};
+
+mod common;
+use common::{scratch_dir, write_script};
+
+const VERSION: &str = "ffmpeg version 6.1.1-3ubuntu5 Copyright (c) 2000-2023 the FFmpeg developers\n\
+built with gcc 13 (Ubuntu 13.2.0-23ubuntu3)\n\
+libavutil      58. 29.100 / 58. 29.100\n";
+
+const ENCODERS: &str = "Encoders:\n \
+V..... = Video\n \
+A..... = Audio\n \
+.F.... = Frame-level multithreading\n \
+------\n \
+V....D libx264              libx264 H.264 / AVC / MPEG-4 AVC / MPEG-4 part 10 (codec h264)\n \
+V....D libvpx-vp9           libvpx VP9 (codec vp9)\n \
+VFS... prores_ks            Apple ProRes (iCodec Pro) (codec prores)\n \
+A....D aac                  AAC (Advanced Audio Coding)\n";
+
+const FILTERS: &str = "Filters:\n  \
+T.. = Timeline support\n  \
+A = Audio input/output\n  \
+V = Video input/output\n  \
+| = Source or sink filter\n \
+... abench            A->A       Benchmark part of a filtergraph.\n \
+TSC scale             V->V       Scale the input video size and/or convert the image format.\n \
+... concat            N->N       Concatenate audio and video streams.\n";
+
+const FORMATS: &str = "File formats:\n \
+D. = Demuxing supported\n \
+.E = Muxing supported\n \
+--\n \
+D  concat          Virtual concatenation script\n \
+DE matroska,webm   Matroska / WebM\n  \
+E mov             QuickTime / MOV\n  \
+E mp4             MP4 (MPEG-4 Part 14)\n  \
+E webm            WebM\n";
+
+/// Write an FFmpeg stand-in into `dir` that prints the listings above,
+/// logs every call to `calls.log` and otherwise writes its output
+fn write_listing_ffmpeg(dir: &Path) -> String {
+    for (name, listing) in [("version", VERSION), ("encoders", ENCODERS), ("filters", FILTERS), ("formats", FORMATS)] {
+        std::fs::write(dir.join(format!("{}.txt", name)), listing).expect("Failed to write listing");
+    }
+    write_script(&dir.join("ffmpeg"), &format!("#!/bin/sh\n\
+        dir={}\n\
+        echo \"$1\" >> \"$dir/calls.log\"\n\
+        case \"$1\" in\n\
+          -version|-encoders|-filters|-formats) cat \"$dir/${{1#-}}.txt\"; exit 0;;\n\
+        esac\n\
+        for output; do :; done\n\
+        echo done > \"$output\"\n", dir.display()))
+}
+
+#[actix_web::test]
+async fn test_listings_are_parsed() {
+    assert_eq!(parse_version(VERSION).as_deref(), Some("6.1.1-3ubuntu5"));
+    assert_eq!(parse_version("command not found"), None);
+
+    let encoders = parse_encoders(ENCODERS);
+    assert_eq!(encoders.into_iter().collect::<Vec<_>>(), vec!["aac", "libvpx-vp9", "libx264", "prores_ks"]);
+
+    let filters = parse_filters(FILTERS);
+    assert_eq!(filters.into_iter().collect::<Vec<_>>(), vec!["abench", "concat", "scale"]);
+
+    let (muxers, demuxers) = parse_formats(FORMATS);
+    assert_eq!(muxers.into_iter().collect::<Vec<_>>(), vec!["matroska", "mov", "mp4", "webm"]);
+    assert_eq!(demuxers.into_iter().collect::<Vec<_>>(), vec!["concat", "matroska", "webm"]);
+}
+
+#[actix_web::test]
+async fn test_missing_encoders_and_filters_are_reported_per_profile() {
+    let capabilities = FFmpegCapabilities::from_listings("ffmpeg", VERSION, ENCODERS, FILTERS, FORMATS);
+    for name in ["h264-web", "vp9-webm", "prores-proxy"] {
+        assert!(capabilities.unsupported(&EncoderProfile::named(name).unwrap()).is_empty(), "{}", name);
+    }
+
+    let errors = capabilities.unsupported(&EncoderProfile::named("av1-archive").unwrap());
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].field, "output.profile");
+    assert_eq!(errors[0].message, "av1-archive needs the libsvtav1 encoder, which FFmpeg 6.1.1-3ubuntu5 lacks");
+
+    // A build without the scale filter cannot create any video
+    let minimal = FFmpegCapabilities::from_listings("ffmpeg", VERSION, ENCODERS, "", FORMATS);
+    let errors = minimal.unsupported(&EncoderProfile::default());
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].field, "output");
+    assert!(errors[0].message.contains("scale filter"));
+
+    // Nothing is known about an FFmpeg that did not run
+    let missing = FFmpegCapabilities::unavailable("/nonexistent/ffmpeg");
+    assert!(!missing.available);
+    assert!(missing.unsupported(&EncoderProfile::default()).is_empty());
+    assert_eq!(FFmpegCapabilities::probe("/nonexistent/ffmpeg"), missing);
+}
+
+#[actix_web::test]
+async fn test_probed_installation_is_used_for_requests_and_health() {
+    let (dir, image) = scratch_dir("probe");
+    let ffmpeg_path = write_listing_ffmpeg(&dir);
+    let capabilities: SharedFFmpegCapabilities = Arc::new(FFmpegCapabilities::probe(&ffmpeg_path));
+    assert!(capabilities.available);
+    assert_eq!(capabilities.version.as_deref(), Some("6.1.1-3ubuntu5"));
+    assert!(capabilities.encoders.contains("libx264"));
+
+    let mut config = Config::new();
+    config.ffmpeg_path = ffmpeg_path;
+    let repository: SharedVideoCreationRepository = Arc::new(InMemoryVideoCreationRepository::new());
+    let workers = VideoCreationWorkerPool::start_with_capabilities(repository.clone(), 1, capabilities.clone());
+    let service = VideoCreationAppService::new(config.clone(), workers).with_ffmpeg_capabilities(capabilities.clone());
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(config))
+            .app_data(web::Data::new(BlockCache::new(1024 * 1024)))
+            .app_data(web::Data::new(capabilities.clone()))
+            .app_data(web::Data::new(service))
+            .route("/videos", web::post().to(handle_create_video))
+            .route("/health", web::get().to(handle_health_check))
+    ).await;
+
+    // The build lacks libsvtav1, so the archive profile is refused before anything is queued
+    let request = serde_json::json!({
+        "video_id": "archive",
+        "images": [{"path": image}],
+        "output": {"path": dir.join("archive.mkv").to_string_lossy(), "profile": "av1-archive"},
+    });
+    let req = test::TestRequest::post().uri("/videos").set_json(&request).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    let problem: ProblemDetails = test::read_body_json(resp).await;
+    assert_eq!(problem.errors.len(), 1);
+    assert_eq!(problem.errors[0].field, "output.profile");
+    assert!(problem.errors[0].message.contains("libsvtav1"));
+
+    // Supported profiles are encoded without asking FFmpeg whether it runs again
+    let request = serde_json::json!({
+        "video_id": "web",
+        "images": [{"path": image}],
+        "output": {"path": dir.join("web.mp4").to_string_lossy()},
+    });
+    let req = test::TestRequest::post().uri("/videos").set_json(&request).to_request();
+    let created: CreateVideoResponse = test::call_and_read_body_json(&app, req).await;
+    let job_id = VideoCreationJobId::new(created.job_id);
+    for _ in 0..100 {
+        if repository.find_job_by_id(&job_id).unwrap().unwrap().status == VideoCreationStatus::Completed {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    assert_eq!(repository.find_job_by_id(&job_id).unwrap().unwrap().status, VideoCreationStatus::Completed);
+
+    let req = test::TestRequest::get().uri("/health").to_request();
+    let health: HealthResponse = test::call_and_read_body_json(&app, req).await;
+    assert!(health.ffmpeg_available);
+    let ffmpeg = health.ffmpeg.unwrap();
+    assert_eq!(ffmpeg.version.as_deref(), Some("6.1.1-3ubuntu5"));
+    assert_eq!((ffmpeg.encoder_count, ffmpeg.filter_count, ffmpeg.muxer_count), (4, 3, 4));
+    let unsupported: Vec<&str> = ffmpeg.encoder_profiles.iter()
+        .filter(|profile| !profile.supported)
+        .map(|profile| profile.name.as_str())
+        .collect();
+    assert_eq!(unsupported, vec!["av1-archive"]);
+
+    let calls = std::fs::read_to_string(dir.join("calls.log")).unwrap();
+    assert_eq!(calls.lines().filter(|call| *call == "-version").count(), 1);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_requests_are_rejected_when_ffmpeg_did_not_run() {
+    let (dir, image) = scratch_dir("unavailable");
+    let capabilities: SharedFFmpegCapabilities = Arc::new(FFmpegCapabilities::probe("/nonexistent/ffmpeg"));
+    let repository: SharedVideoCreationRepository = Arc::new(InMemoryVideoCreationRepository::new());
+    let workers = VideoCreationWorkerPool::start_with_capabilities(repository, 1, capabilities.clone());
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(
+                VideoCreationAppService::new(Config::new(), workers).with_ffmpeg_capabilities(capabilities),
+            ))
+            .route("/videos", web::post().to(handle_create_video))
+    ).await;
+
+    let request = serde_json::json!({
+        "video_id": "clip",
+        "images": [{"path": image}],
+        "output": {"path": dir.join("out.mp4").to_string_lossy()},
+    });
+    let req = test::TestRequest::post().uri("/videos").set_json(&request).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 502);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,102 @@
+use std::sync::Arc;
+use std::time::Duration;
+use video_streaming_api::{
+    domain::video::VideoId,
+    domain::video_creation::{VideoCreationJob, VideoCreationJobId, VideoCreationManager, VideoCreationRepository, VideoCreationStatus},
+    infrastructure::ffmpeg::{FFmpegProgress, FFmpegProgressParser},
+    infrastructure::jobs::VideoCreationWorkerPool,
+    infrastructure::repositories::{InMemoryVideoCreationRepository, SharedVideoCreationRepository},
+};
+
+mod common;
+use common::{scratch_dir, write_fake_ffmpeg};
+
+/// Body of an FFmpeg stand-in that reports progress through a 10 second encode
+/// on stdout, pausing between reports, then writes its output and exits
+const PROGRESS_FFMPEG: &str = "echo \"$@\" > \"$output.args\"\n\
+    for second in 0 2 4 6; do\n\
+      printf 'frame=%s\\nfps=1.0\\nout_time_us=%s000000\\nout_time=00:00:0%s.000000\\nprogress=continue\\n' $second $second $second\n\
+      sleep 0.3\n\
+    done\n\
+    echo done > \"$output\"\n\
+    printf 'frame=10\\nout_time_us=10000000\\nprogress=end\\n'\n\
+    exit 0\n";
+
+fn stored_job(repository: &SharedVideoCreationRepository, job_id: &str) -> VideoCreationJob {
+    repository.find_job_by_id(&VideoCreationJobId::new(job_id.to_string())).unwrap().unwrap()
+}
+
+#[actix_web::test]
+async fn test_parser_emits_one_report_per_block() {
+    let mut parser = FFmpegProgressParser::new();
+    let output = "frame=0\nfps=0.00\nout_time_us=N/A\nout_time_ms=N/A\nout_time=N/A\nprogress=continue\n\
+                  frame=3\nfps=1.5\nout_time_us=3000000\nout_time_ms=3000000\nout_time=00:00:03.000000\nspeed=1.5x\nprogress=continue\n\
+                  frame=5\nout_time=00:01:02.500000\nprogress=end\n";
+
+    let reports: Vec<FFmpegProgress> = output.lines().filter_map(|line| parser.push_line(line)).collect();
+    assert_eq!(reports, vec![
+        FFmpegProgress { frame: Some(0), out_time_seconds: None, finished: false },
+        FFmpegProgress { frame: Some(3), out_time_seconds: Some(3.0), finished: false },
+        FFmpegProgress { frame: Some(5), out_time_seconds: Some(62.5), finished: true },
+    ]);
+
+    // Lines that are not key=value pairs are ignored
+    assert!(parser.push_line("").is_none());
+    assert!(parser.push_line("garbage").is_none());
+    // Negative times show up before the first frame is encoded
+    assert!(parser.push_line("out_time_us=-9223372036854775807").is_none());
+    assert_eq!(parser.push_line("progress=continue"), Some(FFmpegProgress::default()));
+}
+
+#[actix_web::test]
+async fn test_progress_and_eta_are_persisted_while_encoding() {
+    let (dir, image) = scratch_dir("encode");
+    let ffmpeg_path = write_fake_ffmpeg(&dir, PROGRESS_FFMPEG);
+    let repository: SharedVideoCreationRepository = Arc::new(InMemoryVideoCreationRepository::new());
+    let workers = VideoCreationWorkerPool::start_with_ffmpeg(repository.clone(), 1, &ffmpeg_path);
+
+    let mut job = VideoCreationManager::create_job_with_durations(
+        vec![image],
+        vec![Some(10)],
+        dir.join("out.mp4").to_string_lossy().to_string(),
+        VideoId::new("progress".to_string()),
+        None,
+    ).expect("Failed to create job");
+    job.id = VideoCreationJobId::new("job_progress".to_string());
+    workers.submit(job).unwrap();
+
+    // Progress reported mid-encode reaches the repository
+    let mut intermediate = None;
+    for _ in 0..100 {
+        let job = stored_job(&repository, "job_progress");
+        if let Some(progress) = &job.progress {
+            if job.status == VideoCreationStatus::InProgress && progress.percentage > 0.0 {
+                intermediate = Some(progress.clone());
+                break;
+            }
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+    let intermediate = intermediate.expect("intermediate progress should be stored");
+    assert!(intermediate.percentage < 100.0);
+    assert_eq!(intermediate.current_frame as f32, intermediate.percentage / 10.0);
+    assert_eq!(intermediate.total_frames, 10);
+    assert!(intermediate.estimated_time_remaining_seconds.unwrap() > 0.0);
+
+    let args = std::fs::read_to_string(dir.join("out.mp4.args")).unwrap();
+    assert!(args.contains("-progress pipe:1"));
+
+    for _ in 0..200 {
+        if stored_job(&repository, "job_progress").status != VideoCreationStatus::InProgress {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    let finished = stored_job(&repository, "job_progress");
+    assert_eq!(finished.status, VideoCreationStatus::Completed);
+    let progress = finished.progress.unwrap();
+    assert_eq!((progress.current_frame, progress.total_frames), (10, 10));
+    assert_eq!(progress.percentage, 100.0);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -38,7 +38,7 @@ async fn test_ffmpeg_sequence_debug() {
     }
     
     // Build filter complex
-    let filter_complex = format!("[0:v][1:v][2:v]concat=n=3:v=1:a=0,scale=800:600[outv]");
+    let filter_complex = "[0:v][1:v][2:v]concat=n=3:v=1:a=0,scale=800:600[outv]".to_string();
     
     let cmd1_result = cmd1
         .arg("-filter_complex").arg(&filter_complex)
@@ -146,7 +146,7 @@ async fn test_ffmpeg_sequence_debug() {
     }
     
     // Build filter complex with fps filter to control duration
-    let filter_complex = format!("[0:v][1:v][2:v]concat=n=3:v=1:a=0,fps=1,scale=800:600[outv]");
+    let filter_complex = "[0:v][1:v][2:v]concat=n=3:v=1:a=0,fps=1,scale=800:600[outv]".to_string();
     
     let cmd3_result = cmd3
         .arg("-filter_complex").arg(&filter_complex)
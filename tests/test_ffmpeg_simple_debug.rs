@@ -123,7 +123,7 @@ async fn test_ffmpeg_simple_debug() {
     }
     
     // Build filter complex
-    let filter_complex = format!("[0:v][1:v][2:v]concat=n=3:v=1:a=0,scale=800:600[outv]");
+    let filter_complex = "[0:v][1:v][2:v]concat=n=3:v=1:a=0,scale=800:600[outv]".to_string();
     
     let cmd3_result = cmd3
         .arg("-filter_complex").arg(&filter_complex)
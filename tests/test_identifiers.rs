@@ -0,0 +1,139 @@
+use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use actix_web::{test, web, App};
+use video_streaming_api::{
+    application::dto::{CreateSessionRequest, CreateVideoResponse},
+    application::services::VideoCreationAppService,
+    application::use_cases::CreateSessionUseCase,
+    domain::common::{IdGenerator, SequentialIdGenerator, UuidV7Generator},
+    domain::video::VideoId,
+    domain::video_creation::{VideoCreationJobId, VideoCreationManager, VideoCreationRepository, VideoCreationStatus},
+    infrastructure::http::handle_create_video,
+    infrastructure::jobs::VideoCreationWorkerPool,
+    infrastructure::repositories::{InMemorySessionRepository, InMemoryVideoCreationRepository, SharedVideoCreationRepository},
+    shared::config::Config,
+};
+
+mod common;
+use common::{empty_scratch_dir, write_fake_ffmpeg, write_images};
+
+/// Body of an FFmpeg stand-in that keeps a copy of the concat list it was given
+/// next to its output, then succeeds
+const LIST_COPYING_FFMPEG: &str = "previous=\n\
+    for arg; do\n\
+      if [ \"$previous\" = \"-i\" ]; then list=\"$arg\"; fi\n\
+      previous=\"$arg\"\n\
+    done\n\
+    sleep 0.2\n\
+    cp \"$list\" \"$output.list\"\n\
+    echo \"$list\" > \"$output.list_path\"\n\
+    echo done > \"$output\"\n";
+
+/// Helper function to create a scratch directory with `count` (fake) images
+fn scratch_dir(name: &str, count: usize) -> (PathBuf, Vec<String>) {
+    let dir = empty_scratch_dir(name);
+    let images = write_images(&dir, count);
+    (dir, images)
+}
+
+#[actix_web::test]
+async fn test_generated_ids_are_unique_and_time_ordered() {
+    let ids: Vec<String> = (0..1000).map(|_| UuidV7Generator.next_id()).collect();
+    assert_eq!(ids.iter().collect::<HashSet<_>>().len(), ids.len());
+    assert!(ids.windows(2).all(|pair| pair[0] < pair[1]));
+    assert_eq!(uuid::Uuid::parse_str(&ids[0]).unwrap().get_version_num(), 7);
+
+    // Jobs created within the same millisecond no longer share an id
+    let (dir, images) = scratch_dir("unique", 1);
+    let job_ids: HashSet<String> = (0..100)
+        .map(|_| {
+            VideoCreationManager::create_job(images.clone(), "out.mp4".to_string(), VideoId::new("clip".to_string()), None)
+                .unwrap()
+                .id.as_str().to_string()
+        })
+        .collect();
+    assert_eq!(job_ids.len(), 100);
+    assert!(job_ids.iter().all(|id| id.starts_with("job_")));
+
+    let sequential = SequentialIdGenerator::new();
+    assert_eq!(sequential.next_id(), "00000001");
+    assert_eq!(sequential.next_id(), "00000002");
+    assert_eq!(VideoCreationJobId::generate_with(&sequential).as_str(), "job_00000003");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_injected_generator_names_sessions() {
+    let use_case = CreateSessionUseCase::new(InMemorySessionRepository::new())
+        .with_id_generator(Arc::new(SequentialIdGenerator::new()));
+
+    let session_ids: Vec<String> = (0..2)
+        .map(|_| {
+            use_case.execute(CreateSessionRequest {
+                video_id: "clip".to_string(),
+                user_agent: "curl/8.0".to_string(),
+                ip_address: "127.0.0.1".to_string(),
+            }).unwrap().session_id
+        })
+        .collect();
+    assert_eq!(session_ids, vec!["session_00000001", "session_00000002"]);
+}
+
+#[actix_web::test]
+async fn test_concurrent_jobs_get_their_own_file_lists() {
+    let (dir, images) = scratch_dir("lists", 2);
+    let ffmpeg_path = write_fake_ffmpeg(&dir, LIST_COPYING_FFMPEG);
+    let repository: SharedVideoCreationRepository = Arc::new(InMemoryVideoCreationRepository::new());
+    let workers = VideoCreationWorkerPool::start_with_ffmpeg(repository.clone(), 2, &ffmpeg_path);
+    let service = VideoCreationAppService::new(Config::new(), workers)
+        .with_id_generator(Arc::new(SequentialIdGenerator::new()));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(service))
+            .route("/videos", web::post().to(handle_create_video))
+    ).await;
+
+    // Both requests land within the same millisecond and are encoded side by side
+    let mut job_ids = Vec::new();
+    for (index, image) in images.iter().enumerate() {
+        let request = serde_json::json!({
+            "video_id": format!("clip{}", index),
+            "images": [{"path": image}],
+            "output": {"path": dir.join(format!("out{}.mp4", index)).to_string_lossy()},
+        });
+        let req = test::TestRequest::post().uri("/videos").set_json(&request).to_request();
+        let created: CreateVideoResponse = test::call_and_read_body_json(&app, req).await;
+        job_ids.push(created.job_id);
+    }
+    assert_eq!(job_ids, vec!["job_00000001", "job_00000002"]);
+
+    for job_id in &job_ids {
+        let job_id = VideoCreationJobId::new(job_id.clone());
+        for _ in 0..100 {
+            if repository.find_job_by_id(&job_id).unwrap().unwrap().status == VideoCreationStatus::Completed {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(50)).await;
+        }
+        assert_eq!(repository.find_job_by_id(&job_id).unwrap().unwrap().status, VideoCreationStatus::Completed);
+    }
+
+    // Each encode read a list naming its own image, and the lists are gone afterwards
+    let mut list_paths = HashSet::new();
+    for (index, image) in images.iter().enumerate() {
+        let output = dir.join(format!("out{}.mp4", index));
+        let list = std::fs::read_to_string(output.with_extension("mp4.list")).unwrap();
+        assert!(list.contains(image.as_str()));
+        assert!(!list.contains(images[1 - index].as_str()));
+
+        let list_path = std::fs::read_to_string(output.with_extension("mp4.list_path")).unwrap();
+        assert!(!PathBuf::from(list_path.trim()).exists());
+        list_paths.insert(list_path);
+    }
+    assert_eq!(list_paths.len(), 2);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
@@ -0,0 +1,259 @@
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use actix_web::{test, web, App};
+use video_streaming_api::{
+    application::dto::{CreateVideoResponse, ProblemDetails, VideoCreationJobStatusResponse},
+    application::services::VideoCreationAppService,
+    domain::video::VideoId,
+    domain::common::DomainError,
+    domain::common::DomainResult,
+    domain::video_creation::{
+        VideoCreationJob, VideoCreationJobId, VideoCreationJobQuery, VideoCreationManager, VideoCreationRepository,
+        VideoCreationStatus, VideoCreator,
+    },
+    infrastructure::ffmpeg::FFmpegVideoCreator,
+    infrastructure::http::{handle_cancel_job, handle_create_video, handle_get_job_status},
+    infrastructure::jobs::VideoCreationWorkerPool,
+    infrastructure::repositories::{InMemoryVideoCreationRepository, SharedVideoCreationRepository},
+    shared::config::Config,
+};
+
+mod common;
+use common::{scratch_dir, write_fake_ffmpeg};
+
+/// Body of an FFmpeg stand-in that writes part of its output, records its pid and
+/// arguments next to the output file, then hangs like a long render
+const HANGING_FFMPEG: &str = "echo \"$@\" > \"$output.args\"\n\
+    echo partial > \"$output\"\n\
+    echo $$ > \"$output.pid\"\n\
+    exec sleep 30\n";
+
+async fn wait_until(what: &str, condition: impl Fn() -> bool) {
+    for _ in 0..100 {
+        if condition() {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("timed out waiting for {}", what);
+}
+
+/// Repository in which the job completes right after it was read, as if its worker
+/// finished between the read and the write of a cancel request
+struct CompletesAfterRead {
+    inner: SharedVideoCreationRepository,
+}
+
+impl VideoCreationRepository for CompletesAfterRead {
+    fn save_job(&self, job: &VideoCreationJob) -> DomainResult<()> {
+        self.inner.save_job(job)
+    }
+
+    fn find_job_by_id(&self, id: &VideoCreationJobId) -> DomainResult<Option<VideoCreationJob>> {
+        let job = self.inner.find_job_by_id(id)?;
+        if let Some(mut finished) = job.clone() {
+            finished.complete()?;
+            self.inner.update_job(&finished)?;
+        }
+        Ok(job)
+    }
+
+    fn find_jobs_by_status(&self, status: &VideoCreationStatus) -> DomainResult<Vec<VideoCreationJob>> {
+        self.inner.find_jobs_by_status(status)
+    }
+
+    fn query_jobs(&self, query: &VideoCreationJobQuery) -> DomainResult<Vec<VideoCreationJob>> {
+        self.inner.query_jobs(query)
+    }
+
+    fn update_job(&self, job: &VideoCreationJob) -> DomainResult<()> {
+        self.inner.update_job(job)
+    }
+
+    fn update_job_unless_finished(&self, job: &VideoCreationJob) -> DomainResult<bool> {
+        self.inner.update_job_unless_finished(job)
+    }
+}
+
+fn stored_status(repository: &SharedVideoCreationRepository, job_id: &str) -> VideoCreationStatus {
+    repository.find_job_by_id(&VideoCreationJobId::new(job_id.to_string())).unwrap().unwrap().status
+}
+
+macro_rules! init_app {
+    ($repository:expr, $ffmpeg_path:expr) => {
+        test::init_service(
+            App::new()
+                .app_data(web::Data::new(VideoCreationAppService::new(
+                    Config::new(),
+                    VideoCreationWorkerPool::start_with_ffmpeg($repository, 1, $ffmpeg_path),
+                )))
+                .route("/videos", web::post().to(handle_create_video))
+                .route("/job/{job_id}", web::get().to(handle_get_job_status))
+                .route("/job/{job_id}", web::delete().to(handle_cancel_job))
+        ).await
+    };
+}
+
+fn create_request(dir: &Path, image: &str, name: &str) -> serde_json::Value {
+    serde_json::json!({
+        "video_id": name,
+        "images": [{"path": image}],
+        "output": {"path": dir.join(format!("{}.mp4", name)).to_string_lossy()},
+    })
+}
+
+#[actix_web::test]
+async fn test_cancel_kills_ffmpeg_and_cleans_up() {
+    let (dir, image) = scratch_dir("running");
+    let ffmpeg_path = write_fake_ffmpeg(&dir, HANGING_FFMPEG);
+    let repository: SharedVideoCreationRepository = Arc::new(InMemoryVideoCreationRepository::new());
+    let app = init_app!(repository.clone(), &ffmpeg_path);
+
+    // The single worker renders the first job; the second waits in the queue
+    let mut job_ids = Vec::new();
+    for name in ["running", "queued"] {
+        let req = test::TestRequest::post().uri("/videos").set_json(create_request(&dir, &image, name)).to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 202);
+        let created: CreateVideoResponse = test::read_body_json(resp).await;
+        job_ids.push(created.job_id);
+    }
+
+    let output = dir.join("running.mp4");
+    let pid_file = dir.join("running.mp4.pid");
+    wait_until("ffmpeg to start", || pid_file.exists()).await;
+    let pid = std::fs::read_to_string(&pid_file).unwrap().trim().to_string();
+    let args = std::fs::read_to_string(dir.join("running.mp4.args")).unwrap();
+    let list_file = args.split_whitespace()
+        .skip_while(|arg| *arg != "-i")
+        .nth(1)
+        .expect("ffmpeg should get a file list")
+        .to_string();
+    assert!(Path::new(&list_file).exists());
+    assert_eq!(stored_status(&repository, &job_ids[0]), VideoCreationStatus::InProgress);
+
+    // Cancelling the queued job keeps it from ever starting
+    let req = test::TestRequest::delete().uri(&format!("/job/{}", job_ids[1])).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let cancelled: VideoCreationJobStatusResponse = test::read_body_json(resp).await;
+    assert_eq!(cancelled.status, "cancelled");
+
+    let req = test::TestRequest::delete().uri(&format!("/job/{}", job_ids[0])).to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let cancelled: VideoCreationJobStatusResponse = test::read_body_json(resp).await;
+    assert_eq!(cancelled.status, "cancelled");
+    assert!(cancelled.completed_at.is_some());
+
+    // FFmpeg is killed and everything it left behind is removed
+    let process = PathBuf::from(format!("/proc/{}", pid));
+    wait_until("ffmpeg to be killed", || !process.exists()).await;
+    wait_until("the partial output to be removed", || !output.exists()).await;
+    wait_until("the file list to be removed", || !Path::new(&list_file).exists()).await;
+    assert_eq!(stored_status(&repository, &job_ids[0]), VideoCreationStatus::Cancelled);
+
+    // The worker skips the cancelled queued job
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    let queued = repository.find_job_by_id(&VideoCreationJobId::new(job_ids[1].clone())).unwrap().unwrap();
+    assert_eq!(queued.status, VideoCreationStatus::Cancelled);
+    assert_eq!(queued.attempts, 0);
+    assert!(!dir.join("queued.mp4.pid").exists());
+
+    let req = test::TestRequest::get().uri(&format!("/job/{}", job_ids[0])).to_request();
+    let status: VideoCreationJobStatusResponse = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(status.status, "cancelled");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_cancel_unknown_and_finished_jobs() {
+    let (dir, image) = scratch_dir("finished");
+    let ffmpeg_path = write_fake_ffmpeg(&dir, HANGING_FFMPEG);
+    let repository: SharedVideoCreationRepository = Arc::new(InMemoryVideoCreationRepository::new());
+    let app = init_app!(repository.clone(), &ffmpeg_path);
+
+    let req = test::TestRequest::delete().uri("/job/missing_job").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+
+    let mut job = VideoCreationManager::create_job(
+        vec![image],
+        dir.join("done.mp4").to_string_lossy().to_string(),
+        VideoId::new("done".to_string()),
+        None,
+    ).unwrap();
+    job.id = VideoCreationJobId::new("job_done".to_string());
+    job.start().unwrap();
+    job.complete().unwrap();
+    repository.save_job(&job).unwrap();
+
+    let req = test::TestRequest::delete().uri("/job/job_done").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 409);
+    let problem: ProblemDetails = test::read_body_json(resp).await;
+    assert_eq!(problem.code, "conflict");
+    assert_eq!(stored_status(&repository, "job_done"), VideoCreationStatus::Completed);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_finished_jobs_are_never_overwritten() {
+    let repository: SharedVideoCreationRepository = Arc::new(InMemoryVideoCreationRepository::new());
+    let (dir, image) = scratch_dir("overwrite");
+
+    let job = VideoCreationManager::create_job(
+        vec![image],
+        dir.join("late.mp4").to_string_lossy().to_string(),
+        VideoId::new("late".to_string()),
+        None,
+    ).unwrap();
+    repository.save_job(&job).unwrap();
+
+    // The job is cancelled after a worker took it from the queue but before it started
+    let mut stored = job.clone();
+    stored.cancel().unwrap();
+    repository.update_job(&stored).unwrap();
+
+    let finished = FFmpegVideoCreator::new(repository.clone()).create_video(job.clone()).unwrap();
+    assert_eq!(finished.status, VideoCreationStatus::Cancelled);
+    assert_eq!(stored_status(&repository, job.id.as_str()), VideoCreationStatus::Cancelled);
+    assert!(!dir.join("late.mp4.pid").exists());
+
+    // Finished jobs cannot fail afterwards either
+    let mut completed = job;
+    completed.start().unwrap();
+    completed.complete().unwrap();
+    assert!(matches!(completed.fail("too late".to_string()), Err(DomainError::Conflict(_))));
+    assert_eq!(completed.status, VideoCreationStatus::Completed);
+    assert!(matches!(stored.fail("too late".to_string()), Err(DomainError::Conflict(_))));
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_cancel_loses_to_a_job_that_completes_meanwhile() {
+    let inner: SharedVideoCreationRepository = Arc::new(InMemoryVideoCreationRepository::new());
+    let repository: SharedVideoCreationRepository = Arc::new(CompletesAfterRead { inner: inner.clone() });
+    let (dir, image) = scratch_dir("race");
+
+    let mut job = VideoCreationManager::create_job(
+        vec![image],
+        dir.join("race.mp4").to_string_lossy().to_string(),
+        VideoId::new("race".to_string()),
+        None,
+    ).unwrap();
+    job.id = VideoCreationJobId::new("job_race".to_string());
+    job.start().unwrap();
+    inner.save_job(&job).unwrap();
+
+    let pool = VideoCreationWorkerPool::start(repository, 1);
+    let result = pool.cancel(&job.id);
+    assert!(matches!(result, Err(DomainError::Conflict(_))));
+    assert_eq!(stored_status(&inner, "job_race"), VideoCreationStatus::Completed);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
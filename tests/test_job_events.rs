@@ -0,0 +1,234 @@
+use std::sync::Arc;
+use std::time::Duration;
+use actix_web::{test, web, App};
+use video_streaming_api::{
+    application::dto::VideoCreationJobStatusResponse,
+    application::services::VideoCreationAppService,
+    domain::video::VideoId,
+    domain::video_creation::{VideoCreationEventKind, VideoCreationJob, VideoCreationJobId, VideoCreationManager, VideoCreationRepository},
+    infrastructure::events::JobEventLog,
+    infrastructure::http::handle_job_events,
+    infrastructure::jobs::VideoCreationWorkerPool,
+    infrastructure::repositories::{InMemoryVideoCreationRepository, SharedVideoCreationRepository},
+    shared::config::Config,
+};
+
+mod common;
+use common::{scratch_dir, write_fake_ffmpeg};
+
+/// Body of an FFmpeg stand-in that reports progress through a 4 second encode, then succeeds
+const PROGRESS_FFMPEG: &str = "for second in 1 2 3; do\n\
+      sleep 0.2\n\
+      printf 'frame=%s\\nout_time_us=%s000000\\nprogress=continue\\n' $second $second\n\
+    done\n\
+    echo done > \"$output\"\n\
+    printf 'frame=4\\nout_time_us=4000000\\nprogress=end\\n'\n";
+
+fn new_job(dir: &std::path::Path, image: &str, id: &str) -> VideoCreationJob {
+    let mut job = VideoCreationManager::create_job_with_durations(
+        vec![image.to_string()],
+        vec![Some(4)],
+        dir.join(format!("{}.mp4", id)).to_string_lossy().to_string(),
+        VideoId::new(id.to_string()),
+        None,
+    ).expect("Failed to create job");
+    job.id = VideoCreationJobId::new(id.to_string());
+    job
+}
+
+/// One parsed `text/event-stream` event: id, event name and status payload
+fn parse_events(body: &[u8]) -> Vec<(u64, String, VideoCreationJobStatusResponse)> {
+    let body = std::str::from_utf8(body).unwrap();
+    body.split("\n\n")
+        .filter(|block| !block.trim().is_empty() && !block.starts_with(':'))
+        .map(|block| {
+            let field = |name: &str| block.lines()
+                .find_map(|line| line.strip_prefix(name))
+                .unwrap_or_else(|| panic!("event without {}: {}", name, block))
+                .to_string();
+            (
+                field("id: ").parse().unwrap(),
+                field("event: "),
+                serde_json::from_str(&field("data: ")).unwrap(),
+            )
+        })
+        .collect()
+}
+
+macro_rules! init_app {
+    ($workers:expr) => {
+        test::init_service(
+            App::new()
+                .app_data(web::Data::new(VideoCreationAppService::new(Config::new(), $workers)))
+                .route("/job/{job_id}/events", web::get().to(handle_job_events))
+        ).await
+    };
+}
+
+#[actix_web::test]
+async fn test_event_kinds_follow_the_job_lifecycle() {
+    let (dir, image) = scratch_dir("kinds");
+    let pending = new_job(&dir, &image, "kinds");
+    assert_eq!(VideoCreationEventKind::between(None, &pending), None);
+
+    let mut started = pending.clone();
+    started.start().unwrap();
+    assert_eq!(VideoCreationEventKind::between(Some(&pending), &started), Some(VideoCreationEventKind::Started));
+    assert_eq!(VideoCreationEventKind::between(Some(&started), &started), None);
+
+    let mut progressed = started.clone();
+    progressed.record_encoding_progress(2, 2.0, Some(1.0)).unwrap();
+    assert_eq!(VideoCreationEventKind::between(Some(&started), &progressed), Some(VideoCreationEventKind::Progress));
+
+    // A re-queued job that starts again starts over
+    let mut restarted = progressed.clone();
+    restarted.requeue().unwrap();
+    restarted.start().unwrap();
+    assert_eq!(VideoCreationEventKind::between(Some(&progressed), &restarted), Some(VideoCreationEventKind::Started));
+
+    let mut cancelled = restarted.clone();
+    cancelled.cancel().unwrap();
+    let kind = VideoCreationEventKind::between(Some(&restarted), &cancelled).unwrap();
+    assert_eq!(kind, VideoCreationEventKind::Cancelled);
+    assert!(kind.is_terminal());
+    assert_eq!(VideoCreationEventKind::between(Some(&cancelled), &cancelled), None);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_events_stream_until_the_job_completes() {
+    let (dir, image) = scratch_dir("complete");
+    let ffmpeg_path = write_fake_ffmpeg(&dir, PROGRESS_FFMPEG);
+    let repository: SharedVideoCreationRepository = Arc::new(InMemoryVideoCreationRepository::new());
+    let workers = VideoCreationWorkerPool::start_with_ffmpeg(repository, 1, &ffmpeg_path);
+    let app = init_app!(workers.clone());
+
+    workers.submit(new_job(&dir, &image, "job_live")).unwrap();
+    let req = test::TestRequest::get().uri("/job/job_live/events").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("Content-Type").unwrap(), "text/event-stream");
+    assert_eq!(resp.headers().get("Cache-Control").unwrap(), "no-cache");
+
+    // The body ends once the job has completed
+    let body = tokio::time::timeout(Duration::from_secs(10), test::read_body(resp)).await
+        .expect("stream should close after the job completes");
+    let events = parse_events(&body);
+    let kinds: Vec<&str> = events.iter().map(|(_, kind, _)| kind.as_str()).collect();
+    assert_eq!(kinds.first(), Some(&"started"));
+    assert_eq!(kinds.last(), Some(&"completed"));
+    assert!(kinds.contains(&"progress"));
+    assert!(events.windows(2).all(|pair| pair[0].0 < pair[1].0));
+
+    let (_, _, started) = &events[0];
+    assert_eq!(started.status, "in_progress");
+    let (_, _, progress) = events.iter().find(|(_, kind, _)| kind == "progress").unwrap();
+    let percentage = progress.progress.as_ref().unwrap().percentage;
+    assert!(percentage > 0.0 && percentage < 100.0);
+    let (last_id, _, completed) = events.last().unwrap();
+    assert_eq!(completed.status, "completed");
+
+    // Reconnecting resumes after the last event seen: the latest progress (FFmpeg's
+    // final report) and the outcome
+    let req = test::TestRequest::get()
+        .uri("/job/job_live/events")
+        .insert_header(("Last-Event-ID", events[0].0.to_string()))
+        .to_request();
+    let resumed = parse_events(&test::call_and_read_body(&app, req).await);
+    let kinds: Vec<&str> = resumed.iter().map(|(_, kind, _)| kind.as_str()).collect();
+    assert_eq!(kinds, vec!["progress", "completed"]);
+    assert_eq!(resumed[0].2.progress.as_ref().unwrap().percentage, 100.0);
+
+    // Nothing is left after the terminal event
+    let req = test::TestRequest::get()
+        .uri("/job/job_live/events")
+        .insert_header(("Last-Event-ID", last_id.to_string()))
+        .to_request();
+    assert!(test::call_and_read_body(&app, req).await.is_empty());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_events_for_cancelled_finished_and_unknown_jobs() {
+    let repository: SharedVideoCreationRepository = Arc::new(InMemoryVideoCreationRepository::new());
+    let workers = VideoCreationWorkerPool::start(repository.clone(), 1);
+    let app = init_app!(workers.clone());
+    let (dir, image) = scratch_dir("other");
+
+    let req = test::TestRequest::get().uri("/job/missing/events").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+
+    // A job finished before this process started gets one event for its stored state
+    let mut done = new_job(&dir, &image, "job_done");
+    done.start().unwrap();
+    done.complete().unwrap();
+    repository.save_job(&done).unwrap();
+    let req = test::TestRequest::get().uri("/job/job_done/events").to_request();
+    let events = parse_events(&test::call_and_read_body(&app, req).await);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].1, "completed");
+
+    // A queued job that is never run: the subscriber waits until it is cancelled
+    workers.repository().save_job(&new_job(&dir, &image, "job_queued")).unwrap();
+    let req = test::TestRequest::get().uri("/job/job_queued/events").to_request();
+    let resp = test::call_service(&app, req).await;
+    let cancel = async {
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        workers.cancel(&VideoCreationJobId::new("job_queued".to_string())).unwrap();
+    };
+    let (body, _) = tokio::join!(test::read_body(resp), cancel);
+    let events = parse_events(&body);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].1, "cancelled");
+    assert_eq!(events[0].2.status, "cancelled");
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_stale_event_ids_and_finished_jobs_are_forgotten() {
+    let (dir, image) = scratch_dir("retention");
+    let log = JobEventLog::with_retention(Duration::from_millis(100));
+
+    let pending = new_job(&dir, &image, "job_old");
+    let mut started = pending.clone();
+    started.start().unwrap();
+    let mut progressed = started.clone();
+    progressed.record_encoding_progress(2, 2.0, Some(1.0)).unwrap();
+    let first = log.record(&started).unwrap();
+    let latest = log.record(&progressed).unwrap();
+
+    // An id from before a restart is lower than anything this log hands out: only the current state is sent
+    let snapshot = log.events_after(&pending.id, 42);
+    assert_eq!(snapshot.len(), 1);
+    assert_eq!(snapshot[0].id, latest.id);
+    assert_eq!(snapshot[0].kind, VideoCreationEventKind::Progress);
+    assert_eq!(log.events_after(&pending.id, latest.id + 1000).len(), 1);
+    assert_eq!(log.events_after(&pending.id, first.id).len(), 1);
+    assert_eq!(log.events_after(&pending.id, 0).len(), 2);
+
+    let mut cancelled = progressed.clone();
+    cancelled.cancel().unwrap();
+    log.record(&cancelled).unwrap();
+    assert!(log.is_finished(&pending.id));
+
+    // Events of a job that finished longer ago than the retention go with the next recorded event
+    std::thread::sleep(Duration::from_millis(150));
+    let mut other = new_job(&dir, &image, "job_new");
+    other.start().unwrap();
+    log.record(&other).unwrap();
+    assert!(log.events_after(&pending.id, 0).is_empty());
+    assert!(!log.is_finished(&pending.id));
+    assert_eq!(log.events_after(&other.id, 0).len(), 1);
+
+    // A subscriber arriving later gets the stored state again
+    log.record_if_unknown(&cancelled);
+    let events = log.events_after(&pending.id, latest.id);
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].kind, VideoCreationEventKind::Cancelled);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
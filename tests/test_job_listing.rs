@@ -0,0 +1,162 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::{Duration, UNIX_EPOCH};
+use actix_web::{test, web, App};
+use video_streaming_api::{
+    application::dto::{ProblemDetails, VideoCreationJobListResponse},
+    application::services::VideoCreationAppService,
+    domain::video::VideoId,
+    domain::video_creation::{
+        VideoCreationJob, VideoCreationJobCursor, VideoCreationJobId, VideoCreationJobQuery, VideoCreationManager,
+        VideoCreationRepository, VideoCreationStatus,
+    },
+    infrastructure::http::handle_list_jobs,
+    infrastructure::jobs::VideoCreationWorkerPool,
+    infrastructure::repositories::{InMemoryVideoCreationRepository, SharedVideoCreationRepository},
+    infrastructure::sqlite::{SqliteDatabase, SqliteVideoCreationRepository},
+    shared::config::Config,
+};
+
+mod common;
+use common::scratch_dir;
+
+/// A job created `created_at` seconds after the epoch, optionally failed
+fn job_at(dir: &Path, image: &str, id: &str, video_id: &str, created_at: u64, failed: bool) -> VideoCreationJob {
+    let mut job = VideoCreationManager::create_job(
+        vec![image.to_string()],
+        dir.join(format!("{}.mp4", id)).to_string_lossy().to_string(),
+        VideoId::new(video_id.to_string()),
+        None,
+    ).expect("Failed to create job");
+    job.id = VideoCreationJobId::new(id.to_string());
+    job.created_at = UNIX_EPOCH + Duration::from_secs(created_at);
+    if failed {
+        job.start().unwrap();
+        job.fail("ffmpeg failed: boom".to_string()).unwrap();
+    }
+    job
+}
+
+/// Seven jobs, saved out of order; job_c and job_d share a creation time
+fn seed(repository: &dyn VideoCreationRepository, dir: &Path, image: &str) {
+    for job in [
+        job_at(dir, image, "job_e", "intro", 500, false),
+        job_at(dir, image, "job_a", "intro", 100, true),
+        job_at(dir, image, "job_d", "outro", 300, true),
+        job_at(dir, image, "job_c", "intro", 300, true),
+        job_at(dir, image, "job_b", "outro", 200, false),
+        job_at(dir, image, "job_g", "intro", 700, true),
+        job_at(dir, image, "job_f", "intro", 600, false),
+    ] {
+        repository.save_job(&job).unwrap();
+    }
+}
+
+fn ids(jobs: &[VideoCreationJob]) -> Vec<&str> {
+    jobs.iter().map(|job| job.id.as_str()).collect()
+}
+
+/// The same queries must give the same answers on every backend
+fn check_query_jobs(repository: &dyn VideoCreationRepository) {
+    let all = repository.query_jobs(&VideoCreationJobQuery::new(100)).unwrap();
+    assert_eq!(ids(&all), vec!["job_a", "job_b", "job_c", "job_d", "job_e", "job_f", "job_g"]);
+
+    let mut query = VideoCreationJobQuery::new(100);
+    query.status = Some(VideoCreationStatus::Failed);
+    query.video_id = Some(VideoId::new("intro".to_string()));
+    query.since = Some(UNIX_EPOCH + Duration::from_secs(300));
+    assert_eq!(ids(&repository.query_jobs(&query).unwrap()), vec!["job_c", "job_g"]);
+
+    // Pages continue after the cursor, also between jobs created at the same time
+    let mut query = VideoCreationJobQuery::new(3);
+    assert_eq!(ids(&repository.query_jobs(&query).unwrap()), vec!["job_a", "job_b", "job_c"]);
+    query.after = Some(VideoCreationJobCursor::for_job(&all[2]));
+    assert_eq!(ids(&repository.query_jobs(&query).unwrap()), vec!["job_d", "job_e", "job_f"]);
+    query.after = Some(VideoCreationJobCursor::for_job(&all[6]));
+    assert!(repository.query_jobs(&query).unwrap().is_empty());
+}
+
+#[actix_web::test]
+async fn test_query_jobs_in_memory() {
+    let (dir, image) = scratch_dir("memory");
+    let repository = InMemoryVideoCreationRepository::new();
+    seed(&repository, &dir, &image);
+    check_query_jobs(&repository);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_query_jobs_in_sqlite() {
+    let (dir, image) = scratch_dir("sqlite");
+    let repository = SqliteVideoCreationRepository::new(SqliteDatabase::open_in_memory().unwrap());
+    seed(&repository, &dir, &image);
+    check_query_jobs(&repository);
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_cursor_round_trip() {
+    let cursor = VideoCreationJobCursor {
+        created_at_millis: 1_700_000_000_123,
+        job_id: VideoCreationJobId::new("job_1.5".to_string()),
+    };
+    assert_eq!(VideoCreationJobCursor::parse(&cursor.encode()).unwrap(), cursor);
+    assert!(VideoCreationJobCursor::parse("job_1").is_err());
+    assert!(VideoCreationJobCursor::parse("soon.job_1").is_err());
+    assert!(VideoCreationJobCursor::parse("17.").is_err());
+
+    assert_eq!(VideoCreationStatus::parse("in_progress").unwrap(), VideoCreationStatus::InProgress);
+    assert_eq!(VideoCreationStatus::parse("FAILED").unwrap(), VideoCreationStatus::Failed);
+}
+
+#[actix_web::test]
+async fn test_list_jobs_endpoint_pages_through_filtered_jobs() {
+    let (dir, image) = scratch_dir("http");
+    let repository: SharedVideoCreationRepository = Arc::new(InMemoryVideoCreationRepository::new());
+    seed(repository.as_ref(), &dir, &image);
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(VideoCreationAppService::new(
+                Config::new(),
+                VideoCreationWorkerPool::start(repository, 1),
+            )))
+            .route("/jobs", web::get().to(handle_list_jobs))
+    ).await;
+
+    let req = test::TestRequest::get().uri("/jobs").to_request();
+    let page: VideoCreationJobListResponse = test::call_and_read_body_json(&app, req).await;
+    assert_eq!(page.jobs.len(), 7);
+    assert!(page.next_cursor.is_none());
+
+    // Follow the cursors through the failed jobs
+    let mut seen = Vec::new();
+    let mut uri = "/jobs?status=failed&limit=2".to_string();
+    loop {
+        let req = test::TestRequest::get().uri(&uri).to_request();
+        let page: VideoCreationJobListResponse = test::call_and_read_body_json(&app, req).await;
+        assert!(page.jobs.len() <= 2);
+        assert!(page.jobs.iter().all(|job| job.status == "failed"));
+        seen.extend(page.jobs.into_iter().map(|job| job.job_id));
+        match page.next_cursor {
+            Some(cursor) => uri = format!("/jobs?status=failed&limit=2&cursor={}", cursor),
+            None => break,
+        }
+    }
+    assert_eq!(seen, vec!["job_a", "job_c", "job_d", "job_g"]);
+
+    let req = test::TestRequest::get().uri("/jobs?video_id=outro&since=250").to_request();
+    let page: VideoCreationJobListResponse = test::call_and_read_body_json(&app, req).await;
+    let listed: Vec<&str> = page.jobs.iter().map(|job| job.job_id.as_str()).collect();
+    assert_eq!(listed, vec!["job_d"]);
+    assert_eq!(page.jobs[0].error_message.as_deref(), Some("ffmpeg failed: boom"));
+
+    // Every bad parameter is reported at once
+    let req = test::TestRequest::get().uri("/jobs?status=stuck&limit=0&since=yesterday&cursor=nope").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    let problem: ProblemDetails = test::read_body_json(resp).await;
+    let fields: Vec<String> = problem.errors.into_iter().map(|error| error.field).collect();
+    assert_eq!(fields, vec!["status", "since", "limit", "cursor"]);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
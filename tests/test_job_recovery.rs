@@ -0,0 +1,129 @@
+use std::path::Path;
+use std::sync::Arc;
+use std::time::Duration;
+use video_streaming_api::{
+    domain::video::VideoId,
+    domain::video_creation::{VideoCreationJob, VideoCreationJobId, VideoCreationManager, VideoCreationRepository, VideoCreationStatus},
+    infrastructure::jobs::{JobRecovery, VideoCreationWorkerPool},
+    infrastructure::repositories::SharedVideoCreationRepository,
+    infrastructure::sqlite::{SqliteDatabase, SqliteVideoCreationRepository},
+};
+
+mod common;
+use common::{scratch_dir, write_fake_ffmpeg};
+
+/// A job that was running (or queued) when the previous process stopped
+fn unfinished_job(dir: &Path, image: &str, id: &str, attempts: Option<u32>) -> VideoCreationJob {
+    let output = dir.join(format!("{}.mp4", id));
+    let mut job = VideoCreationManager::create_job(
+        vec![image.to_string()],
+        output.to_string_lossy().to_string(),
+        VideoId::new(id.to_string()),
+        None,
+    ).expect("Failed to create job");
+    job.id = VideoCreationJobId::new(id.to_string());
+
+    if let Some(attempts) = attempts {
+        job.start().unwrap();
+        job.attempts = attempts;
+        std::fs::write(&output, b"half an mp4").expect("Failed to write partial output");
+    }
+    job
+}
+
+async fn wait_for_finished_job(repository: &SharedVideoCreationRepository, job_id: &str) -> VideoCreationJob {
+    let job_id = VideoCreationJobId::new(job_id.to_string());
+    for _ in 0..200 {
+        let job = repository.find_job_by_id(&job_id).unwrap().expect("job should be stored");
+        if matches!(job.status, VideoCreationStatus::Completed | VideoCreationStatus::Failed) {
+            return job;
+        }
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+    panic!("job {} did not finish in time", job_id.as_str());
+}
+
+fn decision_for<'a>(decisions: &'a [JobRecovery], id: &str) -> &'a JobRecovery {
+    decisions.iter()
+        .find(|decision| match decision {
+            JobRecovery::Resumed { job_id }
+            | JobRecovery::Requeued { job_id, .. }
+            | JobRecovery::Failed { job_id, .. } => job_id.as_str() == id,
+        })
+        .unwrap_or_else(|| panic!("no recovery decision for {}", id))
+}
+
+#[actix_web::test]
+async fn test_requeue_lifecycle() {
+    let (dir, image) = scratch_dir("lifecycle");
+    let mut job = unfinished_job(&dir, &image, "lifecycle", None);
+
+    assert!(job.requeue().is_err());
+    job.start().unwrap();
+    assert_eq!(job.attempts, 1);
+    job.requeue().unwrap();
+    assert_eq!(job.status, VideoCreationStatus::Pending);
+    assert!(job.progress.is_none());
+    job.start().unwrap();
+    assert_eq!(job.attempts, 2);
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_interrupted_jobs_are_recovered_after_restart() {
+    let (dir, image) = scratch_dir("restart");
+    let database_path = dir.join("jobs.db");
+
+    // The previous process left jobs behind in the database
+    {
+        let repository = SqliteVideoCreationRepository::new(SqliteDatabase::open(&database_path).unwrap());
+        repository.save_job(&unfinished_job(&dir, &image, "job_retry", Some(1))).unwrap();
+        repository.save_job(&unfinished_job(&dir, &image, "job_exhausted", Some(3))).unwrap();
+        repository.save_job(&unfinished_job(&dir, &image, "job_pending", None)).unwrap();
+
+        let mut done = unfinished_job(&dir, &image, "job_done", None);
+        done.start().unwrap();
+        done.complete().unwrap();
+        repository.save_job(&done).unwrap();
+    }
+
+    let repository: SharedVideoCreationRepository =
+        Arc::new(SqliteVideoCreationRepository::new(SqliteDatabase::open(&database_path).unwrap()));
+    let ffmpeg_path = write_fake_ffmpeg(&dir, "echo done > \"$output\"\n");
+    let workers = VideoCreationWorkerPool::start_with_ffmpeg(repository.clone(), 1, &ffmpeg_path);
+    let decisions = workers.recover_interrupted_jobs(2).expect("Failed to recover jobs");
+    assert_eq!(decisions.len(), 3);
+
+    assert_eq!(decision_for(&decisions, "job_retry"), &JobRecovery::Requeued {
+        job_id: VideoCreationJobId::new("job_retry".to_string()),
+        attempt: 2,
+    });
+    assert!(matches!(decision_for(&decisions, "job_exhausted"), JobRecovery::Failed { .. }));
+    assert!(matches!(decision_for(&decisions, "job_pending"), JobRecovery::Resumed { .. }));
+
+    // Partial outputs of interrupted jobs are gone
+    assert!(!dir.join("job_retry.mp4").exists());
+    assert!(!dir.join("job_exhausted.mp4").exists());
+
+    let exhausted = repository.find_job_by_id(&VideoCreationJobId::new("job_exhausted".to_string())).unwrap().unwrap();
+    assert_eq!(exhausted.status, VideoCreationStatus::Failed);
+    assert_eq!(
+        exhausted.error_message.as_deref(),
+        Some("Interrupted by a server restart; gave up after 3 attempts"),
+    );
+
+    // Re-queued jobs run again, counting the new attempt
+    let retried = wait_for_finished_job(&repository, "job_retry").await;
+    assert_eq!(retried.attempts, 2);
+    let resumed = wait_for_finished_job(&repository, "job_pending").await;
+    assert_eq!(resumed.attempts, 1);
+
+    let done = repository.find_job_by_id(&VideoCreationJobId::new("job_done".to_string())).unwrap().unwrap();
+    assert_eq!(done.status, VideoCreationStatus::Completed);
+
+    // Nothing is left to recover on the next start
+    assert!(workers.recover_interrupted_jobs(2).unwrap().is_empty());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
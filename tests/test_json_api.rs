@@ -0,0 +1,135 @@
+use std::sync::Arc;
+use actix_web::{test, web, App};
+use video_streaming_api::{
+    application::services::VideoCreationAppService,
+    application::dto::{HealthResponse, ProblemDetails, ValidateImagesResponse},
+    infrastructure::cache::BlockCache,
+    infrastructure::http::{handle_create_video_query, handle_get_job_status, handle_health_check, handle_validate_images},
+    infrastructure::responses::ResponseFormat,
+    infrastructure::jobs::VideoCreationWorkerPool,
+    infrastructure::repositories::InMemoryVideoCreationRepository,
+    shared::config::Config,
+};
+
+macro_rules! init_app {
+    () => {
+        test::init_service(
+            App::new()
+                .app_data(web::Data::new(Config::new()))
+                .app_data(web::Data::new(VideoCreationAppService::new(
+                    Config::new(),
+                    VideoCreationWorkerPool::start(Arc::new(InMemoryVideoCreationRepository::new()), 1),
+                )))
+                .app_data(web::Data::new(BlockCache::new(1024 * 1024)))
+                .route("/create-video", web::post().to(handle_create_video_query))
+                .route("/job/{job_id}", web::get().to(handle_get_job_status))
+                .route("/validate-images", web::get().to(handle_validate_images))
+                .route("/health", web::get().to(handle_health_check))
+        ).await
+    };
+}
+
+#[actix_web::test]
+async fn test_accept_header_negotiation() {
+    assert_eq!(ResponseFormat::from_accept("*/*"), ResponseFormat::Json);
+    assert_eq!(ResponseFormat::from_accept("application/json"), ResponseFormat::Json);
+    assert_eq!(ResponseFormat::from_accept("text/plain"), ResponseFormat::PlainText);
+    assert_eq!(ResponseFormat::from_accept("text/*"), ResponseFormat::PlainText);
+    assert_eq!(ResponseFormat::from_accept("text/plain, application/json"), ResponseFormat::Json);
+    assert_eq!(ResponseFormat::from_accept("application/json;q=0.5, text/plain"), ResponseFormat::PlainText);
+    assert_eq!(ResponseFormat::from_accept("text/plain;q=0.9, */*;q=0.1"), ResponseFormat::PlainText);
+    assert_eq!(ResponseFormat::from_accept("text/plain;q=0, */*"), ResponseFormat::Json);
+    assert_eq!(ResponseFormat::from_accept("image/png"), ResponseFormat::Json);
+}
+
+#[actix_web::test]
+async fn test_health_check_json_and_plain_text() {
+    let app = init_app!();
+
+    let req = test::TestRequest::get().uri("/health").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("Content-Type").unwrap(), "application/json");
+    let health: HealthResponse = test::read_body_json(resp).await;
+    assert_eq!(health.status, "OK");
+    assert_eq!(health.block_cache.capacity, 1024 * 1024);
+    assert_eq!(health.default_image_spec.width, Config::new().default_image_width);
+
+    let req = test::TestRequest::get()
+        .uri("/health")
+        .insert_header(("Accept", "text/plain"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("Content-Type").unwrap(), "text/plain");
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert!(body.starts_with("Video Streaming API - Health Check\nStatus: OK\n"));
+    assert!(body.contains("Block Cache: 0 hits, 0 misses"));
+}
+
+#[actix_web::test]
+async fn test_validate_images_reports_json() {
+    let app = init_app!();
+
+    let req = test::TestRequest::get()
+        .uri("/validate-images?image1=does/not/exist.jpg")
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    let problem: ProblemDetails = test::read_body_json(resp).await;
+    assert_eq!(problem.code, "validation_failed");
+    assert_eq!(problem.errors.len(), 1);
+    assert_eq!(problem.errors[0].field, "image1");
+    assert_eq!(problem.errors[0].message, "image file does not exist");
+
+    let req = test::TestRequest::get().uri("/validate-images").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    let json: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(json["errors"][0]["message"], "No image paths provided. Use image1, image2, etc. parameters");
+
+    let image = std::env::temp_dir().join(format!("json_api_image_{}.png", std::process::id()));
+    std::fs::write(&image, b"png").unwrap();
+    let req = test::TestRequest::get()
+        .uri(&format!("/validate-images?image1={}", image.to_string_lossy()))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let validation: ValidateImagesResponse = test::read_body_json(resp).await;
+    assert!(validation.valid);
+    assert_eq!(validation.message, "All 1 images are valid");
+    let _ = std::fs::remove_file(&image);
+}
+
+#[actix_web::test]
+async fn test_errors_in_both_formats() {
+    let app = init_app!();
+
+    let req = test::TestRequest::get().uri("/job/missing_job").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    assert_eq!(resp.headers().get("Content-Type").unwrap(), "application/problem+json");
+    let json: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(json["type"], "/problems/not-found");
+    assert_eq!(json["title"], "Not Found");
+    assert_eq!(json["status"], 404);
+    assert_eq!(json["code"], "not_found");
+    assert_eq!(json["entity"], "job");
+    assert_eq!(json["detail"], "job not found: missing_job");
+    assert_eq!(json["instance"], "/job/missing_job");
+
+    let req = test::TestRequest::get()
+        .uri("/job/missing_job")
+        .insert_header(("Accept", "text/plain"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert_eq!(body, "job not found: missing_job");
+
+    let req = test::TestRequest::post().uri("/create-video?output_path=out.mp4").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 422);
+    let json: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(json["errors"][0]["field"], "video_id");
+}
@@ -0,0 +1,143 @@
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use actix_web::{test, web, App};
+use video_streaming_api::{
+    domain::common::{DomainError, DomainResult, FilePath},
+    domain::media::{MediaInfo, MediaProbe, MediaStreamKind},
+    infrastructure::http::handle_video_metadata,
+    infrastructure::probe::{CachedMediaProbe, SharedMediaProbe, parse_ffprobe_output},
+    infrastructure::repositories::{DirectoryVideoRepository, SharedVideoRepository},
+};
+
+const FFPROBE_OUTPUT: &str = r#"{
+    "streams": [
+        {
+            "index": 0,
+            "codec_name": "h264",
+            "codec_type": "video",
+            "width": 1920,
+            "height": 1080,
+            "r_frame_rate": "30000/1001",
+            "avg_frame_rate": "30000/1001",
+            "duration": "10.010000",
+            "bit_rate": "4500000"
+        },
+        {
+            "index": 1,
+            "codec_name": "aac",
+            "codec_type": "audio",
+            "r_frame_rate": "0/0",
+            "avg_frame_rate": "0/0",
+            "bit_rate": "128000"
+        }
+    ],
+    "format": {
+        "filename": "clip.mp4",
+        "format_name": "mov,mp4,m4a,3gp,3g2,mj2",
+        "duration": "10.010000",
+        "bit_rate": "4638213"
+    }
+}"#;
+
+/// Test probe that counts how often it is asked
+struct CountingProbe {
+    calls: Arc<AtomicUsize>,
+}
+
+impl MediaProbe for CountingProbe {
+    fn probe(&self, _file_path: &FilePath) -> DomainResult<MediaInfo> {
+        self.calls.fetch_add(1, Ordering::SeqCst);
+        parse_ffprobe_output(FFPROBE_OUTPUT)
+    }
+}
+
+#[actix_web::test]
+async fn test_parse_ffprobe_output() {
+    let info = parse_ffprobe_output(FFPROBE_OUTPUT).expect("Failed to parse ffprobe output");
+
+    assert_eq!(info.duration, Some(10.01));
+    assert_eq!(info.bitrate, Some(4_638_213));
+    assert_eq!(info.format.as_deref(), Some("mov,mp4,m4a,3gp,3g2,mj2"));
+    assert_eq!(info.streams.len(), 2);
+    assert_eq!(info.streams[1].kind, MediaStreamKind::Audio);
+    assert_eq!(info.streams[1].frame_rate, None);
+
+    assert_eq!(info.width(), Some(1920));
+    assert_eq!(info.height(), Some(1080));
+    assert_eq!(info.video_codec(), Some("h264"));
+    assert_eq!(info.audio_codec(), Some("aac"));
+    assert!((info.frame_rate().unwrap() - 29.97).abs() < 0.01);
+
+    assert_eq!(parse_ffprobe_output("{}").unwrap(), MediaInfo::default());
+    assert!(matches!(parse_ffprobe_output("not json"), Err(DomainError::ProbeFailed(_))));
+}
+
+#[actix_web::test]
+async fn test_probe_results_cached_per_file_version() {
+    let path = std::env::temp_dir().join(format!("media_probe_{}.mp4", std::process::id()));
+    std::fs::write(&path, [0u8; 10]).expect("Failed to write test video");
+    let file_path = FilePath::new(path.to_string_lossy().to_string());
+
+    let calls = Arc::new(AtomicUsize::new(0));
+    let probe = CachedMediaProbe::new(CountingProbe { calls: calls.clone() });
+
+    probe.probe(&file_path).unwrap();
+    probe.probe(&file_path).unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // A new file version is probed again
+    std::fs::write(&path, [0u8; 20]).expect("Failed to rewrite test video");
+    probe.probe(&file_path).unwrap();
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+    let _ = std::fs::remove_file(&path);
+    assert!(matches!(probe.probe(&file_path), Err(DomainError::NotFound { entity: "file", .. })));
+}
+
+#[actix_web::test]
+async fn test_video_metadata_endpoint() {
+    let root = std::env::temp_dir().join(format!("media_probe_http_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).expect("Failed to create media root");
+    std::fs::write(root.join("clip.mp4"), [0u8; 64]).expect("Failed to write clip.mp4");
+
+    let repository: SharedVideoRepository = Arc::new(DirectoryVideoRepository::scan(&root).expect("Failed to scan media root"));
+    let probe: SharedMediaProbe = Arc::new(CountingProbe { calls: Arc::new(AtomicUsize::new(0)) });
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(repository))
+            .app_data(web::Data::new(probe))
+            .route("/videos/{video_id}/metadata", web::get().to(handle_video_metadata))
+    ).await;
+
+    let req = test::TestRequest::get().uri("/videos/clip/metadata").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("Content-Type").unwrap(), "application/json");
+    let json: serde_json::Value = test::read_body_json(resp).await;
+    assert_eq!(json["total_size"], 64);
+    assert_eq!(json["width"], 1920);
+    assert_eq!(json["video_codec"], "h264");
+    assert_eq!(json["streams"].as_array().map(Vec::len), Some(2));
+
+    let req = test::TestRequest::get()
+        .uri("/videos/clip/metadata")
+        .insert_header(("Accept", "text/plain"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    let body = String::from_utf8(test::read_body(resp).await.to_vec()).unwrap();
+    assert!(body.contains("Size: 64 bytes"));
+    assert!(body.contains("Duration: 10.010s"));
+    assert!(body.contains("Bitrate: 4638213 bps"));
+    assert!(body.contains("Resolution: 1920x1080"));
+    assert!(body.contains("Video Codec: h264"));
+    assert!(body.contains("Audio Codec: aac"));
+    assert!(body.contains("Streams: 2"));
+
+    let req = test::TestRequest::get().uri("/videos/missing/metadata").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,261 @@
+use std::sync::Arc;
+use actix_web::{test, web, App};
+use video_streaming_api::{
+    domain::common::{ByteRange, DomainError},
+    domain::video::{RangeParser, RangeSelection, RangeSpec, MAX_RANGE_SPECS},
+    infrastructure::http::handle_catalog_video_stream,
+    infrastructure::cache::BlockCache,
+    infrastructure::repositories::{DirectoryVideoRepository, SharedVideoRepository},
+    shared::config::Config,
+};
+
+/// Helper function to build a ByteRange that is known to be valid
+fn range(start: u64, end: u64, total_size: u64) -> ByteRange {
+    ByteRange::new(start, end, total_size).expect("Invalid test range")
+}
+
+#[actix_web::test]
+async fn test_parse_single_range_unchanged() {
+    let parsed = RangeParser::parse_range_header(Some("bytes=0-99"), 1000).unwrap();
+    assert_eq!(parsed, range(0, 99, 1000));
+
+    let parsed = RangeParser::parse_range_header(Some("bytes=900-"), 1000).unwrap();
+    assert_eq!(parsed, range(900, 999, 1000));
+
+    let parsed = RangeParser::parse_range_header(None, 1000).unwrap();
+    assert_eq!(parsed, range(0, 999, 1000));
+}
+
+#[actix_web::test]
+async fn test_parse_multiple_ranges() {
+    let ranges = RangeParser::parse_ranges(Some("bytes=0-99,500-599"), 1000).unwrap();
+    assert_eq!(ranges, vec![range(0, 99, 1000), range(500, 599, 1000)]);
+
+    let ranges = RangeParser::parse_ranges(Some("bytes=500-599, 0-99"), 1000).unwrap();
+    assert_eq!(ranges, vec![range(0, 99, 1000), range(500, 599, 1000)]);
+
+    // A multi-range header is not a single range
+    assert!(RangeParser::parse_range_header(Some("bytes=0-99,500-599"), 1000).is_err());
+}
+
+#[actix_web::test]
+async fn test_coalesce_overlapping_and_adjacent_ranges() {
+    let ranges = RangeParser::parse_ranges(Some("bytes=0-99,50-149,150-199,400-499"), 1000).unwrap();
+    assert_eq!(ranges, vec![range(0, 199, 1000), range(400, 499, 1000)]);
+
+    let ranges = RangeParser::parse_ranges(Some("bytes=0-99,10-20"), 1000).unwrap();
+    assert_eq!(ranges, vec![range(0, 99, 1000)]);
+}
+
+#[actix_web::test]
+async fn test_parse_suffix_and_open_ended_ranges() {
+    let parsed = RangeParser::parse_range_header(Some("bytes=-500"), 1000).unwrap();
+    assert_eq!(parsed, range(500, 999, 1000));
+
+    // A suffix longer than the file selects the whole file
+    let parsed = RangeParser::parse_range_header(Some("bytes=-5000"), 1000).unwrap();
+    assert_eq!(parsed, range(0, 999, 1000));
+
+    // Last positions past the end are clamped
+    let parsed = RangeParser::parse_range_header(Some("bytes=100-99999999999999999999999"), 1000).unwrap();
+    assert_eq!(parsed, range(100, 999, 1000));
+
+    let specs = RangeParser::parse_specs("bytes=0-1, 5-, -3").unwrap().unwrap();
+    assert_eq!(specs, vec![
+        RangeSpec::Bounded { first: 0, last: 1 },
+        RangeSpec::From { first: 5 },
+        RangeSpec::Suffix { length: 3 },
+    ]);
+}
+
+#[actix_web::test]
+async fn test_range_units_and_malformed_headers() {
+    // Unknown units are ignored and the whole file is served
+    assert_eq!(RangeParser::select(Some("items=0-5"), 1000).unwrap(), RangeSelection::Full);
+    assert_eq!(RangeParser::select(Some("BYTES=0-5"), 1000).unwrap(), RangeSelection::Partial(vec![range(0, 5, 1000)]));
+
+    // Headers that cannot be parsed are ignored as well
+    for header in ["bytes=abc-def", "bytes=", "bytes=-", "bytes=5-2", "bytes=1-2-3", "bytes=+1-2", "0-99"] {
+        assert!(RangeParser::parse_specs(header).is_err(), "{} should not parse", header);
+        assert_eq!(RangeParser::select(Some(header), 1000).unwrap(), RangeSelection::Full, "{}", header);
+    }
+}
+
+#[actix_web::test]
+async fn test_headers_with_too_many_ranges_are_ignored() {
+    let ranges = |count: u64| format!("bytes={}", (0..count).map(|i| format!("{}-{}", i * 2, i * 2)).collect::<Vec<_>>().join(","));
+
+    match RangeParser::select(Some(&ranges(MAX_RANGE_SPECS as u64)), 1000).unwrap() {
+        RangeSelection::Partial(selected) => assert_eq!(selected.len(), MAX_RANGE_SPECS),
+        RangeSelection::Full => panic!("{} ranges are allowed", MAX_RANGE_SPECS),
+    }
+    assert_eq!(RangeParser::select(Some(&ranges(MAX_RANGE_SPECS as u64 + 1)), 1000).unwrap(), RangeSelection::Full);
+    assert_eq!(RangeParser::select(Some(&ranges(5000)), 100_000).unwrap(), RangeSelection::Full);
+}
+
+#[actix_web::test]
+async fn test_unsatisfiable_ranges() {
+    assert!(matches!(RangeParser::select(Some("bytes=1000-"), 1000), Err(DomainError::RangeNotSatisfiable(1000))));
+    assert!(matches!(RangeParser::select(Some("bytes=-0"), 1000), Err(DomainError::RangeNotSatisfiable(1000))));
+
+    // Unsatisfiable specs are dropped while others remain
+    let ranges = RangeParser::parse_ranges(Some("bytes=2000-3000,0-9"), 1000).unwrap();
+    assert_eq!(ranges, vec![range(0, 9, 1000)]);
+
+    // Zero-length files have no satisfiable ranges but can still be served whole
+    assert!(matches!(RangeParser::select(Some("bytes=0-"), 0), Err(DomainError::RangeNotSatisfiable(0))));
+    assert!(matches!(RangeParser::select(Some("bytes=-10"), 0), Err(DomainError::RangeNotSatisfiable(0))));
+    assert_eq!(RangeParser::parse_ranges(None, 0).unwrap(), Vec::<ByteRange>::new());
+}
+
+#[actix_web::test]
+async fn test_multipart_byteranges_response() {
+    let root = std::env::temp_dir().join(format!("range_requests_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).expect("Failed to create media root");
+    let data: Vec<u8> = (0..=255u8).collect();
+    std::fs::write(root.join("clip.mp4"), &data).expect("Failed to write clip.mp4");
+
+    let repository: SharedVideoRepository = Arc::new(DirectoryVideoRepository::scan(&root).expect("Failed to scan media root"));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(repository))
+            .app_data(web::Data::new(Config::new()))
+            .app_data(web::Data::new(BlockCache::new(1024 * 1024)))
+            .route("/videos/{video_id}/stream", web::get().to(handle_catalog_video_stream))
+    ).await;
+
+    let req = test::TestRequest::get()
+        .uri("/videos/clip/stream")
+        .insert_header(("Range", "bytes=0-3,10-13"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 206);
+    assert!(resp.headers().get("Content-Range").is_none());
+
+    let content_type = resp.headers().get("Content-Type").unwrap().to_str().unwrap().to_string();
+    let boundary = content_type.strip_prefix("multipart/byteranges; boundary=")
+        .expect("Expected a multipart/byteranges content type")
+        .to_string();
+
+    let body = test::read_body(resp).await;
+    let mut expected = Vec::new();
+    expected.extend_from_slice(format!("--{}\r\nContent-Type: video/mp4\r\nContent-Range: bytes 0-3/256\r\n\r\n", boundary).as_bytes());
+    expected.extend_from_slice(&[0, 1, 2, 3]);
+    expected.extend_from_slice(format!("\r\n--{}\r\nContent-Type: video/mp4\r\nContent-Range: bytes 10-13/256\r\n\r\n", boundary).as_bytes());
+    expected.extend_from_slice(&[10, 11, 12, 13]);
+    expected.extend_from_slice(format!("\r\n--{}--\r\n", boundary).as_bytes());
+    assert_eq!(body.as_ref(), expected.as_slice());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[actix_web::test]
+async fn test_unsatisfiable_range_response() {
+    let root = std::env::temp_dir().join(format!("range_requests_416_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).expect("Failed to create media root");
+    std::fs::write(root.join("clip.webm"), [0u8; 64]).expect("Failed to write clip.webm");
+    std::fs::write(root.join("empty.webm"), []).expect("Failed to write empty.webm");
+
+    let repository: SharedVideoRepository = Arc::new(DirectoryVideoRepository::scan(&root).expect("Failed to scan media root"));
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(repository))
+            .app_data(web::Data::new(Config::new()))
+            .app_data(web::Data::new(BlockCache::new(1024 * 1024)))
+            .route("/videos/{video_id}/stream", web::get().to(handle_catalog_video_stream))
+    ).await;
+
+    let req = test::TestRequest::get()
+        .uri("/videos/clip/stream")
+        .insert_header(("Range", "bytes=100-200"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 416);
+    assert_eq!(resp.headers().get("Content-Range").unwrap(), "bytes */64");
+
+    let req = test::TestRequest::get()
+        .uri("/videos/clip/stream")
+        .insert_header(("Range", "bytes=-16"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 206);
+    assert_eq!(resp.headers().get("Content-Range").unwrap(), "bytes 48-63/64");
+
+    // A malformed header is ignored and the whole file is sent
+    let req = test::TestRequest::get()
+        .uri("/videos/clip/stream")
+        .insert_header(("Range", "bytes=abc-def"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(test::read_body(resp).await.len(), 64);
+
+    // Too many ranges: the whole file is sent instead of a huge multipart body
+    let many = (0..100).map(|i| format!("{}-{}", i % 64, i % 64)).collect::<Vec<_>>().join(",");
+    let req = test::TestRequest::get()
+        .uri("/videos/clip/stream")
+        .insert_header(("Range", format!("bytes={}", many)))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(test::read_body(resp).await.len(), 64);
+
+    let req = test::TestRequest::get().uri("/videos/empty/stream").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert!(test::read_body(resp).await.is_empty());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[actix_web::test]
+async fn test_ranges_capped_to_max_chunk_size() {
+    let selection = RangeParser::select(Some("bytes=10-,0-3"), 1000).unwrap().capped(Some(100));
+    assert_eq!(selection, RangeSelection::Partial(vec![range(0, 3, 1000), range(10, 109, 1000)]));
+    assert_eq!(RangeParser::select(None, 1000).unwrap().capped(Some(100)), RangeSelection::Full);
+
+    let root = std::env::temp_dir().join(format!("range_requests_capped_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(&root).expect("Failed to create media root");
+    std::fs::write(root.join("clip.webm"), [0u8; 1000]).expect("Failed to write clip.webm");
+
+    let repository: SharedVideoRepository = Arc::new(DirectoryVideoRepository::scan(&root).expect("Failed to scan media root"));
+    let config = Config { max_range_chunk_bytes: Some(256), ..Config::new() };
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(repository))
+            .app_data(web::Data::new(config))
+            .app_data(web::Data::new(BlockCache::new(1024 * 1024)))
+            .route("/videos/{video_id}/stream", web::get().to(handle_catalog_video_stream))
+    ).await;
+
+    // Open-ended and oversized closed ranges are both cut to the window
+    for header in ["bytes=500-", "bytes=500-999"] {
+        let req = test::TestRequest::get()
+            .uri("/videos/clip/stream")
+            .insert_header(("Range", header))
+            .to_request();
+        let resp = test::call_service(&app, req).await;
+        assert_eq!(resp.status(), 206);
+        assert_eq!(resp.headers().get("Content-Range").unwrap(), "bytes 500-755/1000");
+        assert_eq!(test::read_body(resp).await.len(), 256);
+    }
+
+    // The final window ends at the last byte
+    let req = test::TestRequest::get()
+        .uri("/videos/clip/stream")
+        .insert_header(("Range", "bytes=900-"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.headers().get("Content-Range").unwrap(), "bytes 900-999/1000");
+
+    // Requests without a Range header still get the whole file
+    let req = test::TestRequest::get().uri("/videos/clip/stream").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(test::read_body(resp).await.len(), 1000);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
@@ -0,0 +1,228 @@
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use video_streaming_api::{
+    domain::common::{ByteRange, FilePath},
+    domain::streaming::{ClientInfo, SessionId, SessionRepository, SessionState, StreamingSession},
+    domain::video::{Video, VideoId, VideoRepository},
+    domain::video_creation::{VideoCreationJobId, VideoCreationManager, VideoCreationRepository, VideoCreationStatus},
+    infrastructure::repositories::{session_repository, video_creation_repository, video_repository, DirectoryVideoRepository},
+    infrastructure::sqlite::{SqliteDatabase, SqliteSessionRepository, SqliteVideoCreationRepository, SqliteVideoRepository},
+    shared::config::{Config, StorageBackend},
+};
+
+/// Helper function to create a scratch directory for a test
+fn scratch_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("sqlite_repositories_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::fs::create_dir_all(&dir).expect("Failed to create scratch directory");
+    dir
+}
+
+fn create_image(dir: &Path, name: &str) -> String {
+    let path = dir.join(name);
+    std::fs::write(&path, b"not really a png").expect("Failed to write image");
+    path.to_string_lossy().to_string()
+}
+
+#[actix_web::test]
+async fn test_migrations_are_applied_once() {
+    let dir = scratch_dir("migrations");
+    let path = dir.join("nested").join("jobs.db");
+
+    let database = SqliteDatabase::open(&path).expect("Failed to open database");
+    assert_eq!(database.schema_version().unwrap(), SqliteDatabase::latest_schema_version());
+    drop(database);
+
+    // Reopening an up-to-date database leaves the schema alone
+    let database = SqliteDatabase::open(&path).expect("Failed to reopen database");
+    assert_eq!(database.schema_version().unwrap(), SqliteDatabase::latest_schema_version());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_jobs_survive_reopening_the_database() {
+    let dir = scratch_dir("jobs");
+    let path = dir.join("jobs.db");
+    let images = vec![create_image(&dir, "a.png"), create_image(&dir, "b.png")];
+
+    let mut job = VideoCreationManager::create_job_with_durations(
+        images.clone(),
+        vec![None, Some(3)],
+        "out.mp4".to_string(),
+        VideoId::new("clip".to_string()),
+        None,
+    ).expect("Failed to create job");
+    job.id = VideoCreationJobId::new("job_persisted".to_string());
+
+    {
+        let repository = SqliteVideoCreationRepository::new(SqliteDatabase::open(&path).unwrap());
+        repository.save_job(&job).unwrap();
+        job.start().unwrap();
+        job.update_progress(1).unwrap();
+        repository.update_job(&job).unwrap();
+    }
+
+    let repository = SqliteVideoCreationRepository::new(SqliteDatabase::open(&path).unwrap());
+    let stored = repository.find_job_by_id(&job.id).unwrap().expect("job should be stored");
+    assert_eq!(stored.status, VideoCreationStatus::InProgress);
+    assert_eq!(stored.request.video_id.as_str(), "clip");
+    assert_eq!(stored.request.image_paths[1].as_str(), images[1]);
+    assert_eq!(stored.request.image_duration(1), 3);
+    assert_eq!(stored.request.total_duration(), 4);
+    let progress = stored.progress.clone().expect("progress should be stored");
+    assert_eq!((progress.current_frame, progress.total_frames), (1, 4));
+    assert_eq!(progress.percentage, 25.0);
+    assert_eq!(
+        stored.created_at.duration_since(std::time::UNIX_EPOCH).unwrap().as_millis(),
+        job.created_at.duration_since(std::time::UNIX_EPOCH).unwrap().as_millis(),
+    );
+
+    assert_eq!(repository.find_jobs_by_status(&VideoCreationStatus::InProgress).unwrap().len(), 1);
+    assert!(repository.find_jobs_by_status(&VideoCreationStatus::Pending).unwrap().is_empty());
+
+    let mut failed = stored.clone();
+    failed.fail("ffmpeg failed: boom".to_string()).unwrap();
+    assert!(repository.update_job_unless_finished(&failed).unwrap());
+    let stored = repository.find_job_by_id(&job.id).unwrap().unwrap();
+    assert_eq!(stored.status, VideoCreationStatus::Failed);
+    assert_eq!(stored.error_message.as_deref(), Some("ffmpeg failed: boom"));
+    assert!(stored.completed_at.is_some());
+
+    // A finished job is left alone by the conditional write
+    let mut cancelled = job.clone();
+    cancelled.cancel().unwrap();
+    assert!(!repository.update_job_unless_finished(&cancelled).unwrap());
+    assert_eq!(repository.find_job_by_id(&job.id).unwrap().unwrap().status, VideoCreationStatus::Failed);
+
+    assert!(repository.find_job_by_id(&VideoCreationJobId::new("missing".to_string())).unwrap().is_none());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_sessions_round_trip() {
+    let repository = SqliteSessionRepository::new(SqliteDatabase::open_in_memory().unwrap());
+
+    let mut session = StreamingSession::new(
+        SessionId::new("session_1".to_string()),
+        VideoId::new("clip".to_string()),
+        ClientInfo::new("curl/8.0".to_string(), "127.0.0.1".to_string()),
+    );
+    session.start().unwrap();
+    session.request_chunk(&ByteRange::new(0, 1023, 4096).unwrap()).unwrap();
+    session.pause().unwrap();
+    session.resume().unwrap();
+    repository.save(&session).unwrap();
+
+    let idle = StreamingSession::new(
+        SessionId::new("session_2".to_string()),
+        VideoId::new("clip".to_string()),
+        ClientInfo::new("curl/8.0".to_string(), "127.0.0.1".to_string()),
+    );
+    repository.save(&idle).unwrap();
+
+    let stored = repository.find_by_id(&session.id).unwrap().expect("session should be stored");
+    assert_eq!(stored.state, SessionState::Active);
+    assert_eq!(stored.client_info.supported_formats, session.client_info.supported_formats);
+    assert_eq!(stored.metrics.bytes_requested, 1024);
+    assert_eq!(stored.metrics.chunks_requested, 1);
+    assert_eq!(stored.metrics.pause_count, 1);
+    assert_eq!(stored.metrics.total_pause_duration, Duration::from_secs(1));
+
+    let active = repository.find_active_sessions().unwrap();
+    assert_eq!(active.len(), 1);
+    assert_eq!(active[0].id.as_str(), "session_1");
+
+    repository.delete(&session.id).unwrap();
+    assert!(repository.find_by_id(&session.id).unwrap().is_none());
+}
+
+#[actix_web::test]
+async fn test_videos_round_trip() {
+    let dir = scratch_dir("videos");
+    let file = dir.join("sample.mp4");
+    let mut data = vec![0, 0, 0, 16];
+    data.extend_from_slice(b"ftypisom");
+    data.extend_from_slice(&[0u8; 1024]);
+    std::fs::write(&file, &data).unwrap();
+
+    let repository = SqliteVideoRepository::new(SqliteDatabase::open_in_memory().unwrap());
+    let video = Video::new(
+        VideoId::new("sample".to_string()),
+        FilePath::new(file.to_string_lossy().to_string()),
+    ).expect("Failed to read video");
+
+    repository.save(&video).unwrap();
+    let stored = repository.find_by_id(&video.id).unwrap().expect("video should be stored");
+    assert_eq!(stored.file_path.as_str(), video.file_path.as_str());
+    assert_eq!(stored.metadata.total_size, data.len() as u64);
+    assert_eq!(stored.metadata.content_type.as_str(), "video/mp4");
+
+    // Files removed from disk are no longer served
+    std::fs::remove_file(&file).unwrap();
+    assert!(repository.find_by_id(&video.id).unwrap().is_none());
+
+    repository.delete(&video.id).unwrap();
+    assert!(repository.find_by_id(&video.id).unwrap().is_none());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_storage_backend_from_config() {
+    assert_eq!("sqlite".parse::<StorageBackend>(), Ok(StorageBackend::Sqlite));
+    assert_eq!("Memory".parse::<StorageBackend>(), Ok(StorageBackend::Memory));
+    assert!("redis".parse::<StorageBackend>().is_err());
+
+    let dir = scratch_dir("config");
+    let config = Config {
+        storage_backend: StorageBackend::Sqlite,
+        database_path: dir.join("service.db").to_string_lossy().to_string(),
+        ..Config::new()
+    };
+    let images = vec![create_image(&dir, "a.png")];
+    let job = VideoCreationManager::create_job(images, "out.mp4".to_string(), VideoId::new("clip".to_string()), None).unwrap();
+
+    video_creation_repository(&config).unwrap().save_job(&job).unwrap();
+    // A second repository on the same file sees the job
+    let reopened = video_creation_repository(&config).unwrap();
+    assert!(reopened.find_job_by_id(&job.id).unwrap().is_some());
+
+    let memory = video_creation_repository(&Config::new()).unwrap();
+    assert!(memory.find_job_by_id(&job.id).unwrap().is_none());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[actix_web::test]
+async fn test_sessions_and_catalog_follow_the_storage_backend() {
+    let dir = scratch_dir("backend");
+    let media_root = dir.join("media");
+    std::fs::create_dir_all(&media_root).unwrap();
+    std::fs::write(media_root.join("clip.mp4"), b"....ftypisom....").expect("Failed to write clip.mp4");
+    let config = Config {
+        storage_backend: StorageBackend::Sqlite,
+        database_path: dir.join("service.db").to_string_lossy().to_string(),
+        ..Config::new()
+    };
+
+    let session = StreamingSession::new(
+        SessionId::new("session_1".to_string()),
+        VideoId::new("clip".to_string()),
+        ClientInfo::new("curl/8.0".to_string(), "127.0.0.1".to_string()),
+    );
+    session_repository(&config).unwrap().save(&session).unwrap();
+    assert!(session_repository(&config).unwrap().find_by_id(&session.id).unwrap().is_some());
+    assert!(session_repository(&Config::new()).unwrap().find_by_id(&session.id).unwrap().is_none());
+
+    // The scanned catalog is stored, so it is still known when the next start finds no media
+    let catalog = DirectoryVideoRepository::scan(&media_root).unwrap();
+    video_repository(&config, &catalog).unwrap();
+    let empty = DirectoryVideoRepository::new(dir.join("elsewhere"));
+    let video_id = VideoId::new("clip".to_string());
+    assert!(video_repository(&config, &empty).unwrap().find_by_id(&video_id).unwrap().is_some());
+    assert!(video_repository(&Config::new(), &empty).unwrap().find_by_id(&video_id).unwrap().is_none());
+
+    let _ = std::fs::remove_dir_all(&dir);
+}
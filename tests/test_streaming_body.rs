@@ -0,0 +1,67 @@
+use futures_util::StreamExt;
+use video_streaming_api::{
+    domain::common::{ByteRange, FilePath},
+    domain::video::{Video, VideoId, VideoStreamingService},
+    infrastructure::services::{FileVideoStreamingService, stream_file_range},
+};
+
+/// Helper function to write a file of `size` bytes with a recognizable pattern
+fn create_video_file(name: &str, size: usize) -> std::path::PathBuf {
+    let path = std::env::temp_dir().join(format!("streaming_body_{}_{}.webm", name, std::process::id()));
+    let data: Vec<u8> = (0..size).map(|i| (i % 251) as u8).collect();
+    std::fs::write(&path, data).expect("Failed to write test video");
+    path
+}
+
+#[tokio::test]
+async fn test_range_is_streamed_in_bounded_buffers() {
+    let path = create_video_file("bounded", 10_000);
+    let video = Video::new(VideoId::new("clip".to_string()), FilePath::new(path.to_string_lossy().to_string()))
+        .expect("Failed to load test video");
+
+    let service = FileVideoStreamingService::with_buffer_size(1024);
+    let range = ByteRange::new(100, 5099, 10_000).unwrap();
+    let mut stream = service.open_stream(&video, &range).expect("Failed to open stream");
+
+    let mut received = Vec::new();
+    let mut buffers = 0;
+    while let Some(buffer) = stream.next().await {
+        let buffer = buffer.expect("Read failed");
+        assert!(buffer.len() <= 1024);
+        received.extend_from_slice(&buffer);
+        buffers += 1;
+    }
+
+    assert_eq!(received.len(), 5000);
+    assert!(buffers >= 5);
+    let expected: Vec<u8> = (100..5100).map(|i| (i % 251) as u8).collect();
+    assert_eq!(received, expected);
+
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn test_stream_is_lazy_and_reports_truncation() {
+    let path = create_video_file("lazy", 100);
+
+    // Nothing is opened until the stream is polled
+    let mut stream = stream_file_range(&path, 0, 200, 64);
+    std::fs::write(&path, [1u8; 50]).expect("Failed to truncate test video");
+
+    let mut received = 0;
+    let mut failed = false;
+    while let Some(buffer) = stream.next().await {
+        match buffer {
+            Ok(buffer) => received += buffer.len(),
+            Err(_) => failed = true,
+        }
+    }
+    assert_eq!(received, 50);
+    assert!(failed, "A short file must end the stream with an error");
+
+    let missing = std::env::temp_dir().join("streaming_body_missing.webm");
+    let mut stream = stream_file_range(&missing, 0, 10, 64);
+    assert!(matches!(stream.next().await, Some(Err(_))));
+
+    let _ = std::fs::remove_file(&path);
+}
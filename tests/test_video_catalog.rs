@@ -0,0 +1,216 @@
+use std::path::PathBuf;
+use std::sync::Arc;
+use actix_web::{test, web, App};
+use actix_web::body::{BodySize, MessageBody};
+use video_streaming_api::{
+    domain::video::{VideoId, VideoRepository},
+    infrastructure::http::{handle_catalog_video_stream, handle_video_stream},
+    infrastructure::cache::BlockCache,
+    infrastructure::repositories::{DirectoryVideoRepository, SharedVideoRepository},
+    shared::config::Config,
+};
+
+/// Helper function to create a media root with a few fake videos
+fn create_media_root(name: &str) -> PathBuf {
+    let root = std::env::temp_dir().join(format!("video_catalog_{}_{}", name, std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("nested")).expect("Failed to create media root");
+
+    let data: Vec<u8> = (0..100u8).collect();
+    std::fs::write(root.join("clip.webm"), &data).expect("Failed to write clip.webm");
+    std::fs::write(root.join("nested/other.mp4"), &data).expect("Failed to write other.mp4");
+    std::fs::write(root.join("notes.txt"), b"not a video").expect("Failed to write notes.txt");
+
+    root
+}
+
+#[actix_web::test]
+async fn test_directory_repository_registers_videos() {
+    let root = create_media_root("scan");
+    let repository = DirectoryVideoRepository::scan(&root).expect("Failed to scan media root");
+
+    let ids: Vec<String> = repository.list_ids().iter().map(|id| id.as_str().to_string()).collect();
+    assert_eq!(ids, vec!["clip".to_string(), "other".to_string()]);
+
+    let clip = repository.find_by_id(&VideoId::new("clip".to_string()))
+        .expect("Lookup failed")
+        .expect("clip should be registered");
+    assert_eq!(clip.metadata.total_size, 100);
+    assert_eq!(clip.metadata.content_type.as_str(), "video/webm");
+
+    assert!(repository.find_by_id(&VideoId::new("notes".to_string())).unwrap().is_none());
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[actix_web::test]
+async fn test_scan_survives_symlink_loops() {
+    let root = create_media_root("symlinks");
+    // nested/loop points back at the media root
+    std::os::unix::fs::symlink(&root, root.join("nested/loop")).expect("Failed to create symlink");
+
+    let repository = DirectoryVideoRepository::scan(&root).expect("Failed to scan media root");
+    let ids: Vec<String> = repository.list_ids().iter().map(|id| id.as_str().to_string()).collect();
+    assert_eq!(ids, vec!["clip".to_string(), "other".to_string()]);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[actix_web::test]
+async fn test_scan_skips_symlinks_leaving_the_root() {
+    let root = create_media_root("escape");
+    let outside = std::env::temp_dir().join(format!("video_catalog_outside_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&outside);
+    std::fs::create_dir_all(&outside).expect("Failed to create outside directory");
+    std::fs::write(outside.join("secret.mp4"), b"....ftypisom....").expect("Failed to write secret.mp4");
+    std::os::unix::fs::symlink(&outside, root.join("nested/elsewhere")).expect("Failed to create symlink");
+    std::os::unix::fs::symlink(outside.join("secret.mp4"), root.join("leak.mp4")).expect("Failed to create symlink");
+
+    let repository = DirectoryVideoRepository::scan(&root).expect("Failed to scan media root");
+    let ids: Vec<String> = repository.list_ids().iter().map(|id| id.as_str().to_string()).collect();
+    assert_eq!(ids, vec!["clip".to_string(), "other".to_string()]);
+
+    let _ = std::fs::remove_dir_all(&root);
+    let _ = std::fs::remove_dir_all(&outside);
+}
+
+#[actix_web::test]
+async fn test_stream_catalog_video_by_id() {
+    let root = create_media_root("stream");
+    let repository: SharedVideoRepository = Arc::new(DirectoryVideoRepository::scan(&root).expect("Failed to scan media root"));
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(repository))
+            .app_data(web::Data::new(Config::new()))
+            .app_data(web::Data::new(BlockCache::new(1024 * 1024)))
+            .route("/videos/{video_id}/stream", web::get().to(handle_catalog_video_stream))
+    ).await;
+
+    let req = test::TestRequest::get()
+        .uri("/videos/clip/stream")
+        .insert_header(("Range", "bytes=10-19"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 206);
+    assert_eq!(resp.headers().get("Content-Range").unwrap(), "bytes 10-19/100");
+    assert_eq!(resp.headers().get("Content-Type").unwrap(), "video/webm");
+    let body = test::read_body(resp).await;
+    assert_eq!(body.as_ref(), &(10..20u8).collect::<Vec<u8>>()[..]);
+
+    let req = test::TestRequest::get().uri("/videos/missing/stream").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[actix_web::test]
+async fn test_full_body_and_head_responses() {
+    let root = create_media_root("full");
+    let repository: SharedVideoRepository = Arc::new(DirectoryVideoRepository::scan(&root).expect("Failed to scan media root"));
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(repository))
+            .app_data(web::Data::new(Config::new()))
+            .app_data(web::Data::new(BlockCache::new(1024 * 1024)))
+            .route("/videos/{video_id}/stream", web::get().to(handle_catalog_video_stream))
+            .route("/videos/{video_id}/stream", web::head().to(handle_catalog_video_stream))
+    ).await;
+
+    // Without a Range header the whole file is a plain 200
+    let req = test::TestRequest::get().uri("/videos/clip/stream").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("Accept-Ranges").unwrap(), "bytes");
+    assert!(resp.headers().get("Content-Range").is_none());
+    assert_eq!(resp.response().body().size(), BodySize::Sized(100));
+    assert_eq!(test::read_body(resp).await.len(), 100);
+
+    // HEAD advertises the same length without a body
+    let req = test::TestRequest::default().method(actix_web::http::Method::HEAD).uri("/videos/clip/stream").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.headers().get("Content-Type").unwrap(), "video/webm");
+    assert!(resp.headers().get("ETag").is_some());
+    assert_eq!(resp.response().body().size(), BodySize::Sized(100));
+    assert!(test::read_body(resp).await.is_empty());
+
+    let req = test::TestRequest::default()
+        .method(actix_web::http::Method::HEAD)
+        .uri("/videos/clip/stream")
+        .insert_header(("Range", "bytes=0-9"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 206);
+    assert_eq!(resp.headers().get("Content-Range").unwrap(), "bytes 0-9/100");
+    assert_eq!(resp.response().body().size(), BodySize::Sized(10));
+
+    // HEAD of a multi-range request reports the exact multipart length
+    let req = test::TestRequest::get()
+        .uri("/videos/clip/stream")
+        .insert_header(("Range", "bytes=0-9,50-59"))
+        .to_request();
+    let get_length = test::read_body(test::call_service(&app, req).await).await.len() as u64;
+    let req = test::TestRequest::default()
+        .method(actix_web::http::Method::HEAD)
+        .uri("/videos/clip/stream")
+        .insert_header(("Range", "bytes=0-9,50-59"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.response().body().size(), BodySize::Sized(get_length));
+
+    let _ = std::fs::remove_dir_all(&root);
+}
+
+#[actix_web::test]
+async fn test_single_video_stream_route() {
+    let root = create_media_root("single");
+    let config = Config {
+        video_path: root.join("clip.webm").to_string_lossy().to_string(),
+        ..Config::new()
+    };
+
+    let app = test::init_service(
+        App::new()
+            .app_data(web::Data::new(config))
+            .app_data(web::Data::new(BlockCache::new(1024 * 1024)))
+            .route("/stream", web::get().to(handle_video_stream))
+            .route("/stream", web::head().to(handle_video_stream))
+    ).await;
+
+    let req = test::TestRequest::get().uri("/stream").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(test::read_body(resp).await.len(), 100);
+
+    let req = test::TestRequest::get()
+        .uri("/stream")
+        .insert_header(("Range", "bytes=-10"))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 206);
+    assert_eq!(resp.headers().get("Content-Range").unwrap(), "bytes 90-99/100");
+
+    let req = test::TestRequest::default().method(actix_web::http::Method::HEAD).uri("/stream").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 200);
+    assert_eq!(resp.response().body().size(), BodySize::Sized(100));
+
+    // Preconditions go through the same use case as catalog videos
+    let etag = resp.headers().get("ETag").unwrap().clone();
+    let req = test::TestRequest::get()
+        .uri("/stream")
+        .insert_header(("If-None-Match", etag))
+        .to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 304);
+
+    std::fs::remove_file(root.join("clip.webm")).unwrap();
+    let req = test::TestRequest::get().uri("/stream").to_request();
+    let resp = test::call_service(&app, req).await;
+    assert_eq!(resp.status(), 404);
+
+    let _ = std::fs::remove_dir_all(&root);
+}